@@ -0,0 +1,385 @@
+//! Derive macro for fastnbt. Use it through the `derive` feature of fastnbt
+//! rather than depending on this crate directly:
+//!
+//! ```toml
+//! [dependencies]
+//! fastnbt = { version = "2", features = ["derive"] }
+//! ```
+//!
+//! See [`Nbt`] for the attributes supported.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Ident, LitStr,
+    Result, Type,
+};
+
+/// Implement serde's `Serialize` and `Deserialize` for a struct with named
+/// fields, with attributes for the parts of NBT that serde's own attributes
+/// can't express without wrapper types:
+///
+/// * `#[nbt(byte_array)]`, `#[nbt(int_array)]` and `#[nbt(long_array)]` store
+///   a `Vec<i8>`, `Vec<i32>` or `Vec<i64>` as the matching NBT array rather
+///   than a List. Any type that is `AsRef<[i64]>` and `From<Vec<i64>>` (etc)
+///   works.
+/// * `#[nbt(byte_bool)]` stores a `bool` as a Byte, 0 or 1. Any integer is
+///   accepted when deserializing.
+/// * `#[nbt(list_of = "compound")]` makes an empty `Vec` serialize as a List
+///   of the given tag, rather than a List of End. The tag is named as
+///   `fastnbt::Tag` displays it, eg `"int"` or `"long-array"`.
+/// * `#[nbt(rename = "Name")]` uses a different name for the field in the
+///   compound.
+///
+/// Fields without attributes (de)serialize as usual, and missing `Option`
+/// fields deserialize as `None`. Unknown fields are ignored. Serde's own
+/// attributes are not supported alongside this derive.
+///
+/// ```rust
+/// use fastnbt::Nbt;
+///
+/// #[derive(Nbt, Debug, PartialEq)]
+/// struct Section {
+///     #[nbt(rename = "Y")]
+///     y: i8,
+///     #[nbt(long_array, rename = "BlockStates")]
+///     block_states: Vec<i64>,
+///     #[nbt(byte_bool)]
+///     lit: bool,
+///     #[nbt(list_of = "compound", rename = "Palette")]
+///     palette: Vec<fastnbt::Value>,
+/// }
+///
+/// let section = Section {
+///     y: 4,
+///     block_states: vec![1, 2, 3],
+///     lit: true,
+///     palette: vec![],
+/// };
+///
+/// let value = fastnbt::to_value(&section).unwrap();
+/// assert_eq!(
+///     value,
+///     fastnbt::nbt!({
+///         "Y": 4b,
+///         "BlockStates": [L; 1, 2, 3],
+///         "lit": 1b,
+///         "Palette": fastnbt::Value::List(fastnbt::Tag::Compound, vec![]),
+///     })
+/// );
+///
+/// let bytes = fastnbt::to_bytes(&section).unwrap();
+/// assert_eq!(fastnbt::from_bytes::<Section>(&bytes).unwrap(), section);
+/// ```
+#[proc_macro_derive(Nbt, attributes(nbt))]
+pub fn derive_nbt(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a field is stored in NBT.
+enum Kind {
+    Plain,
+    ByteArray,
+    IntArray,
+    LongArray,
+    ByteBool,
+    ListOf(Ident),
+}
+
+struct Field {
+    ident: Ident,
+    ty: Type,
+    name: String,
+    kind: Kind,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    if let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("nbt")) {
+        return Err(Error::new_spanned(attr, "nbt attributes go on fields"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Nbt can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Nbt can only be derived for structs",
+            ))
+        }
+    };
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("fields are named");
+            let mut name = ident.to_string().trim_start_matches("r#").to_owned();
+            let mut kind = Kind::Plain;
+
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("nbt")) {
+                attr.parse_nested_meta(|meta| {
+                    let new_kind = if meta.path.is_ident("rename") {
+                        name = meta.value()?.parse::<LitStr>()?.value();
+                        return Ok(());
+                    } else if meta.path.is_ident("byte_array") {
+                        Kind::ByteArray
+                    } else if meta.path.is_ident("int_array") {
+                        Kind::IntArray
+                    } else if meta.path.is_ident("long_array") {
+                        Kind::LongArray
+                    } else if meta.path.is_ident("byte_bool") {
+                        Kind::ByteBool
+                    } else if meta.path.is_ident("list_of") {
+                        let tag: LitStr = meta.value()?.parse()?;
+                        Kind::ListOf(parse_tag(&tag)?)
+                    } else {
+                        return Err(meta.error("unknown nbt attribute"));
+                    };
+
+                    if !matches!(kind, Kind::Plain) {
+                        return Err(meta.error("a field can only have one NBT type attribute"));
+                    }
+                    kind = new_kind;
+                    Ok(())
+                })?;
+            }
+
+            Ok(Field {
+                ident,
+                ty: field.ty.clone(),
+                name,
+                kind,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let ser = expand_serialize(&input, &fields);
+    let de = expand_deserialize(&input, &fields);
+
+    Ok(quote! {
+        const _: () = {
+            #ser
+            #de
+        };
+    })
+}
+
+/// Get the `fastnbt::Tag` variant for a tag name as `Tag` displays it.
+fn parse_tag(tag: &LitStr) -> Result<Ident> {
+    let variant = match tag.value().as_str() {
+        "byte" => "Byte",
+        "short" => "Short",
+        "int" => "Int",
+        "long" => "Long",
+        "float" => "Float",
+        "double" => "Double",
+        "byte-array" => "ByteArray",
+        "string" => "String",
+        "list" => "List",
+        "compound" => "Compound",
+        "int-array" => "IntArray",
+        "long-array" => "LongArray",
+        _ => {
+            return Err(Error::new_spanned(
+                tag,
+                "expected one of byte, short, int, long, float, double, byte-array, \
+                 string, list, compound, int-array or long-array",
+            ))
+        }
+    };
+    Ok(Ident::new(variant, Span::call_site()))
+}
+
+fn expand_serialize(input: &DeriveInput, fields: &[Field]) -> TokenStream2 {
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param
+                .bounds
+                .push(parse_quote!(::fastnbt::__private::serde::Serialize));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let struct_name = ident.to_string();
+    let len = fields.len();
+    let serialize_fields = fields.iter().map(|field| {
+        let Field {
+            ident, name, kind, ..
+        } = field;
+        let value = match kind {
+            Kind::Plain => quote!(&self.#ident),
+            Kind::ByteArray => quote! {
+                &::fastnbt::__private::ByteArrayRef(::core::convert::AsRef::<[i8]>::as_ref(&self.#ident))
+            },
+            Kind::IntArray => quote! {
+                &::fastnbt::__private::IntArrayRef(::core::convert::AsRef::<[i32]>::as_ref(&self.#ident))
+            },
+            Kind::LongArray => quote! {
+                &::fastnbt::__private::LongArrayRef(::core::convert::AsRef::<[i64]>::as_ref(&self.#ident))
+            },
+            Kind::ByteBool => quote!(&::fastnbt::__private::ByteBool(self.#ident)),
+            Kind::ListOf(tag) => quote! {
+                &::fastnbt::__private::ListOf(
+                    ::fastnbt::Tag::#tag,
+                    ::core::convert::AsRef::<[_]>::as_ref(&self.#ident),
+                )
+            },
+        };
+        quote! {
+            ::fastnbt::__private::serde::ser::SerializeStruct::serialize_field(
+                &mut state,
+                #name,
+                #value,
+            )?;
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::fastnbt::__private::serde::Serialize for #ident #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::fastnbt::__private::serde::Serializer,
+            {
+                let mut state = ::fastnbt::__private::serde::Serializer::serialize_struct(
+                    serializer,
+                    #struct_name,
+                    #len,
+                )?;
+                #(#serialize_fields)*
+                ::fastnbt::__private::serde::ser::SerializeStruct::end(state)
+            }
+        }
+    }
+}
+
+fn expand_deserialize(input: &DeriveInput, fields: &[Field]) -> TokenStream2 {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    // The input may outlive any lifetime of the struct, so that fields such
+    // as `&'a str` can borrow from it.
+    let mut generics = input.generics.clone();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param
+                .bounds
+                .push(parse_quote!(::fastnbt::__private::serde::Deserialize<'de>));
+        }
+    }
+    generics
+        .params
+        .insert(0, parse_quote!('de: #(#lifetimes)+*));
+    let (impl_generics, visitor_ty_generics, where_clause) = generics.split_for_impl();
+
+    let struct_name = ident.to_string();
+    let expecting = format!("struct {}", ident);
+    let names: Vec<_> = fields.iter().map(|f| &f.name).collect();
+    let idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    // Locals for the fields, so that they can't clash with our own.
+    let locals: Vec<_> = (0..fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    let read_values = fields.iter().map(|field| match &field.kind {
+        Kind::Plain | Kind::ListOf(_) => quote!(map.next_value()?),
+        Kind::ByteArray => quote! {
+            ::core::convert::From::from(map.next_value::<::fastnbt::__private::ByteArrayBuf>()?.0)
+        },
+        Kind::IntArray => quote! {
+            ::core::convert::From::from(map.next_value::<::fastnbt::__private::IntArrayBuf>()?.0)
+        },
+        Kind::LongArray => quote! {
+            ::core::convert::From::from(map.next_value::<::fastnbt::__private::LongArrayBuf>()?.0)
+        },
+        Kind::ByteBool => quote!(map.next_value::<::fastnbt::__private::ByteBool>()?.0),
+    });
+
+    let missing = fields.iter().map(|field| {
+        let name = &field.name;
+        match field.kind {
+            Kind::Plain | Kind::ListOf(_) => quote!(::fastnbt::__private::missing_field(#name)?),
+            _ => quote! {
+                return ::core::result::Result::Err(
+                    ::fastnbt::__private::serde::de::Error::missing_field(#name),
+                )
+            },
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::fastnbt::__private::serde::Deserialize<'de> for #ident #ty_generics #where_clause {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::fastnbt::__private::serde::Deserializer<'de>,
+            {
+                struct Visitor #impl_generics #where_clause {
+                    marker: ::core::marker::PhantomData<fn() -> #ident #ty_generics>,
+                    lifetime: ::core::marker::PhantomData<&'de ()>,
+                }
+
+                impl #impl_generics ::fastnbt::__private::serde::de::Visitor<'de> for Visitor #visitor_ty_generics #where_clause {
+                    type Value = #ident #ty_generics;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_map<A>(self, mut map: A) -> ::core::result::Result<Self::Value, A::Error>
+                    where
+                        A: ::fastnbt::__private::serde::de::MapAccess<'de>,
+                    {
+                        #(let mut #locals: ::core::option::Option<#types> = ::core::option::Option::None;)*
+
+                        while let ::core::option::Option::Some(key) = map.next_key::<::fastnbt::__private::Key>()? {
+                            match &*key.0 {
+                                #(#names => {
+                                    if #locals.is_some() {
+                                        return ::core::result::Result::Err(
+                                            ::fastnbt::__private::serde::de::Error::duplicate_field(#names),
+                                        );
+                                    }
+                                    #locals = ::core::option::Option::Some(#read_values);
+                                })*
+                                _ => {
+                                    map.next_value::<::fastnbt::__private::serde::de::IgnoredAny>()?;
+                                }
+                            }
+                        }
+
+                        #(let #locals = match #locals {
+                            ::core::option::Option::Some(v) => v,
+                            ::core::option::Option::None => #missing,
+                        };)*
+
+                        ::core::result::Result::Ok(#ident { #(#idents: #locals),* })
+                    }
+                }
+
+                const FIELDS: &[&str] = &[#(#names),*];
+                deserializer.deserialize_struct(
+                    #struct_name,
+                    FIELDS,
+                    Visitor {
+                        marker: ::core::marker::PhantomData,
+                        lifetime: ::core::marker::PhantomData,
+                    },
+                )
+            }
+        }
+    }
+}
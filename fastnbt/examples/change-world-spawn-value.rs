@@ -6,17 +6,11 @@
 //! The change-world-spawn example uses an actual LevelDat struct.
 
 use fastnbt::Value;
-use flate2::{read::GzDecoder, write::GzEncoder, Compression};
-use std::io::{Read, Write};
 
 fn main() {
     let args: Vec<_> = std::env::args_os().collect();
-    let file = std::fs::File::open(&args[1]).unwrap();
-    let mut decoder = GzDecoder::new(file);
-    let mut bytes = vec![];
-    decoder.read_to_end(&mut bytes).unwrap();
-
-    let mut leveldat: Value = fastnbt::from_bytes(&bytes).unwrap();
+    let bytes = std::fs::read(&args[1]).unwrap();
+    let mut leveldat: Value = fastnbt::from_compressed_bytes(&bytes).unwrap();
 
     match &mut leveldat {
         Value::Compound(level) => {
@@ -33,8 +27,6 @@ fn main() {
         _ => panic!(),
     }
 
-    let new_bytes = fastnbt::to_bytes(&leveldat).unwrap();
     let outfile = std::fs::File::create("level.dat").unwrap();
-    let mut encoder = GzEncoder::new(outfile, Compression::fast());
-    encoder.write_all(&new_bytes).unwrap();
+    fastnbt::to_gzip_writer(outfile, &leveldat).unwrap();
 }
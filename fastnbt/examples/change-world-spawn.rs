@@ -3,12 +3,8 @@
 //! the world spawn is set to 0,0.
 
 use fastnbt::Value;
-use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    io::{Read, Write},
-};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 struct LevelDat {
@@ -29,19 +25,13 @@ struct Data {
 
 fn main() {
     let args: Vec<_> = std::env::args_os().collect();
-    let file = std::fs::File::open(&args[1]).unwrap();
-    let mut decoder = GzDecoder::new(file);
-    let mut bytes = vec![];
-    decoder.read_to_end(&mut bytes).unwrap();
-
-    let mut leveldat: LevelDat = fastnbt::from_bytes(&bytes).unwrap();
+    let bytes = std::fs::read(&args[1]).unwrap();
+    let mut leveldat: LevelDat = fastnbt::from_compressed_bytes(&bytes).unwrap();
 
     leveldat.data.spawn_x = 250;
     leveldat.data.spawn_y = 200;
     leveldat.data.spawn_z = 250;
 
-    let new_bytes = fastnbt::to_bytes(&leveldat).unwrap();
     let outfile = std::fs::File::create("level.dat").unwrap();
-    let mut encoder = GzEncoder::new(outfile, Compression::fast());
-    encoder.write_all(&new_bytes).unwrap();
+    fastnbt::to_gzip_writer(outfile, &leveldat).unwrap();
 }
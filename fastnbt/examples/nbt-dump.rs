@@ -1,4 +1,4 @@
-use fastnbt::stream::{Parser, Value};
+use fastnbt::fmt::{self, FmtOpts, Style};
 use flate2::read::GzDecoder;
 use std::io;
 
@@ -10,30 +10,11 @@ fn main() {
     let stdin = io::stdin();
     let decoder = GzDecoder::new(stdin);
 
-    let mut parser = Parser::new(decoder);
-    let mut indent = 0;
+    let opts = FmtOpts::new().style(Style::Tree).truncate_arrays(32);
+    let result = fmt::reader_to_writer(decoder, io::stdout(), opts);
+    println!();
 
-    loop {
-        match parser.next() {
-            Err(e) => {
-                println!("{:?}", e);
-                break;
-            }
-            Ok(value) => {
-                match value {
-                    Value::CompoundEnd => indent -= 4,
-                    Value::ListEnd => indent -= 4,
-                    _ => {}
-                }
-
-                println!("{:indent$}{:?}", "", value, indent = indent);
-
-                match value {
-                    Value::Compound(_) => indent += 4,
-                    Value::List(_, _, _) => indent += 4,
-                    _ => {}
-                }
-            }
-        }
+    if let Err(e) = result {
+        println!("{:?}", e);
     }
 }
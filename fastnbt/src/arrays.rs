@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+};
 
 use byteorder::{BigEndian, ByteOrder, NativeEndian, ReadBytesExt};
 use serde::{de::Visitor, Deserialize, Serialize};
@@ -10,6 +13,81 @@ pub(crate) const BYTE_ARRAY_TOKEN: &str = "__fastnbt_byte_array";
 pub(crate) const INT_ARRAY_TOKEN: &str = "__fastnbt_int_array";
 pub(crate) const LONG_ARRAY_TOKEN: &str = "__fastnbt_long_array";
 
+/// The raw data of an NBT array as handed to us by a deserializer. The binary
+/// deserializer can lend us the data straight from the input, but others (eg
+/// SNBT) have to build the data up first, so we accept both.
+pub(crate) struct ArrayBytes<'de>(pub(crate) Cow<'de, [u8]>);
+
+/// Serialize the native endian data of an array.
+pub(crate) fn serialize_array<S>(
+    token: &'static str,
+    data: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    // We can't know anything about NBT here, since we might be serializing
+    // to a different format. But we can serialize as a hidden enum variant
+    // named by the token to signal the serializer.
+    serializer.serialize_newtype_variant("Inner", 0, token, Bytes::new(data))
+}
+
+pub(crate) fn i8s_as_bytes(data: &[i8]) -> &[u8] {
+    // Safe to treat [i8] as [u8].
+    unsafe { &*(data as *const [i8] as *const [u8]) }
+}
+
+pub(crate) fn i32s_as_bytes(data: &[i32]) -> &[u8] {
+    // Alignment of i32 is >= alignment of bytes so this should always work.
+    let (_, data, _) = unsafe { data.align_to::<u8>() };
+    data
+}
+
+pub(crate) fn i64s_as_bytes(data: &[i64]) -> &[u8] {
+    // Alignment of i64 is >= alignment of bytes so this should always work.
+    let (_, data, _) = unsafe { data.align_to::<u8>() };
+    data
+}
+
+impl<'de> Deserialize<'de> for ArrayBytes<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct InnerVisitor;
+        impl<'de> Visitor<'de> for InnerVisitor {
+            type Value = ArrayBytes<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("nbt array data")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(ArrayBytes(Cow::Borrowed(v)))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(ArrayBytes(Cow::Owned(v.to_vec())))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(ArrayBytes(Cow::Owned(v)))
+            }
+        }
+        deserializer.deserialize_bytes(InnerVisitor)
+    }
+}
+
 /// NBT ByteArray that owns its data. This type preserves the exact NBT type
 /// when (de)serializing. This dereferences into a i8 slice, so should be usable
 /// basically anywhere a slice should be.
@@ -39,10 +117,10 @@ impl<'de> Deserialize<'de> for ByteArray {
                 let token = map.next_key::<&str>()?.ok_or_else(|| {
                     serde::de::Error::custom("expected NBT byte array token, but got empty map")
                 })?;
-                let data = map.next_value::<&[u8]>()?;
+                let data = map.next_value::<ArrayBytes>()?.0;
 
                 if token == BYTE_ARRAY_TOKEN {
-                    Ok(ByteArray::from_bytes(data))
+                    Ok(ByteArray::from_bytes(&data))
                 } else {
                     Err(serde::de::Error::custom("expected NBT byte array token"))
                 }
@@ -57,19 +135,7 @@ impl Serialize for ByteArray {
     where
         S: serde::Serializer,
     {
-        // We can't know anything about NBT here, since we might be serializing
-        // to a different format. But we can create a hidden inner type to
-        // signal the serializer.
-        #[derive(Serialize)]
-        #[allow(non_camel_case_types)]
-        enum Inner<'a> {
-            __fastnbt_byte_array(&'a Bytes),
-        }
-
-        let data = self.to_bytes();
-        let array = Inner::__fastnbt_byte_array(Bytes::new(data));
-
-        array.serialize(serializer)
+        serialize_array(BYTE_ARRAY_TOKEN, self.to_bytes(), serializer)
     }
 }
 
@@ -79,6 +145,10 @@ impl ByteArray {
         Self { data }
     }
 
+    pub(crate) fn into_inner(self) -> Vec<i8> {
+        self.data
+    }
+
     /// Produce a ByteArray from raw data.
     pub(crate) fn from_bytes(data: &[u8]) -> Self {
         // Safe to treat [u8] as [i8].
@@ -89,8 +159,7 @@ impl ByteArray {
     }
 
     pub(crate) fn to_bytes(&self) -> &[u8] {
-        // Safe to treat [i8] as [u8].
-        unsafe { &*(self.data.as_slice() as *const [i8] as *const [u8]) }
+        i8s_as_bytes(&self.data)
     }
 }
 
@@ -123,6 +192,10 @@ impl IntArray {
         Self { data }
     }
 
+    pub(crate) fn into_inner(self) -> Vec<i32> {
+        self.data
+    }
+
     /// Produce a IntArray from raw data.
     pub(crate) fn from_bytes<Ord: ByteOrder>(data: &[u8]) -> std::io::Result<Self> {
         let data = data
@@ -134,9 +207,7 @@ impl IntArray {
     }
 
     pub(crate) fn to_bytes(&self) -> &[u8] {
-        // Alignment of i32 is >= alignment of bytes so this should always work.
-        let (_, data, _) = unsafe { self.data.as_slice().align_to::<u8>() };
-        data
+        i32s_as_bytes(&self.data)
     }
 }
 
@@ -160,12 +231,12 @@ impl<'de> Deserialize<'de> for IntArray {
                 let token = map.next_key::<&str>()?.ok_or_else(|| {
                     serde::de::Error::custom("expected NBT int array token, but got empty map")
                 })?;
-                let data = map.next_value::<&[u8]>()?;
+                let data = map.next_value::<ArrayBytes>()?.0;
 
                 match token {
-                    INT_ARRAY_TOKEN => IntArray::from_bytes::<BigEndian>(data)
+                    INT_ARRAY_TOKEN => IntArray::from_bytes::<BigEndian>(&data)
                         .map_err(|_| serde::de::Error::custom("could not read i32 for int array")),
-                    INT_ARRAY_VALUE_TOKEN => IntArray::from_bytes::<NativeEndian>(data)
+                    INT_ARRAY_VALUE_TOKEN => IntArray::from_bytes::<NativeEndian>(&data)
                         .map_err(|_| serde::de::Error::custom("could not read i32 for int array")),
                     _ => Err(serde::de::Error::custom("expected NBT int array token")),
                 }
@@ -180,19 +251,7 @@ impl Serialize for IntArray {
     where
         S: serde::Serializer,
     {
-        // We can't know anything about NBT here, since we might be serializing
-        // to a different format. But we can create a hidden inner type to
-        // signal the serializer.
-        #[derive(Serialize)]
-        #[allow(non_camel_case_types)]
-        enum Inner<'a> {
-            __fastnbt_int_array(&'a Bytes),
-        }
-
-        let data = self.to_bytes();
-        let array = Inner::__fastnbt_int_array(Bytes::new(data));
-
-        array.serialize(serializer)
+        serialize_array(INT_ARRAY_TOKEN, self.to_bytes(), serializer)
     }
 }
 
@@ -225,6 +284,10 @@ impl LongArray {
         Self { data }
     }
 
+    pub(crate) fn into_inner(self) -> Vec<i64> {
+        self.data
+    }
+
     pub(crate) fn from_bytes<Ord: ByteOrder>(data: &[u8]) -> std::io::Result<Self> {
         let data = data
             .chunks_exact(8)
@@ -235,9 +298,7 @@ impl LongArray {
     }
 
     pub(crate) fn to_bytes(&self) -> &[u8] {
-        // Alignment of i64 is >= alignment of bytes so this should always work.
-        let (_, data, _) = unsafe { self.data.as_slice().align_to::<u8>() };
-        data
+        i64s_as_bytes(&self.data)
     }
 }
 
@@ -261,12 +322,12 @@ impl<'de> Deserialize<'de> for LongArray {
                 let token = map.next_key::<&str>()?.ok_or_else(|| {
                     serde::de::Error::custom("expected NBT long array token, but got empty map")
                 })?;
-                let data = map.next_value::<&[u8]>()?;
+                let data = map.next_value::<ArrayBytes>()?.0;
 
                 match token {
-                    LONG_ARRAY_TOKEN => LongArray::from_bytes::<BigEndian>(data)
+                    LONG_ARRAY_TOKEN => LongArray::from_bytes::<BigEndian>(&data)
                         .map_err(|_| serde::de::Error::custom("could not read i64 for long array")),
-                    LONG_ARRAY_VALUE_TOKEN => LongArray::from_bytes::<NativeEndian>(data)
+                    LONG_ARRAY_VALUE_TOKEN => LongArray::from_bytes::<NativeEndian>(&data)
                         .map_err(|_| serde::de::Error::custom("could not read i64 for long array")),
                     _ => Err(serde::de::Error::custom("expected NBT long array token")),
                 }
@@ -281,19 +342,7 @@ impl Serialize for LongArray {
     where
         S: serde::Serializer,
     {
-        // We can't know anything about NBT here, since we might be serializing
-        // to a different format. But we can create a hidden inner type to
-        // signal the serializer.
-        #[derive(Serialize)]
-        #[allow(non_camel_case_types)]
-        enum Inner<'a> {
-            __fastnbt_long_array(&'a Bytes),
-        }
-
-        let data = self.to_bytes();
-        let array = Inner::__fastnbt_long_array(Bytes::new(data));
-
-        array.serialize(serializer)
+        serialize_array(LONG_ARRAY_TOKEN, self.to_bytes(), serializer)
     }
 }
 
@@ -309,4 +358,4 @@ impl DerefMut for LongArray {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.data
     }
-}
\ No newline at end of file
+}
@@ -16,6 +16,11 @@
 //! The `iter()` methods return an iterator to the values read on demand from an
 //! internal reference to the input data.
 //!
+//! For NBT of unknown structure, [`Value`] is a borrowing version of
+//! [`fastnbt::Value`][`crate::Value`]. Strings and arrays are borrowed from the
+//! input where possible, so walking large amounts of NBT does not need to
+//! allocate for every string and array.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -48,10 +53,14 @@ use serde_bytes::Bytes;
 
 use crate::{BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN};
 
+mod value;
+
+pub use self::value::{CompoundMap, Value};
+
 /// ByteArray can be used to deserialize the NBT data of the same name. This
 /// borrows from the original input data when deserializing. The carving masks
 /// in a chunk use this type, for example.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ByteArray<'a> {
     data: &'a [u8],
 }
@@ -141,7 +150,7 @@ impl<'a> Serialize for ByteArray<'a> {
 /// IntArray can be used to deserialize the NBT data of the same name. This
 /// borrows from the original input data when deserializing. Biomes in the chunk
 /// format are an example of this data type.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct IntArray<'a> {
     data: &'a [u8],
 }
@@ -231,7 +240,7 @@ impl<'a> Serialize for IntArray<'a> {
 /// LongArray can be used to deserialize the NBT data of the same name. This
 /// borrows from the original input data when deserializing. Block states
 /// (storage of all the blocks in a chunk) are an exmple of when this is used.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LongArray<'a> {
     data: &'a [u8],
 }
@@ -0,0 +1,340 @@
+use std::{borrow::Cow, fmt};
+
+use serde::{
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::SerializeTupleStruct,
+    Deserialize, Serialize,
+};
+
+use super::{ByteArray, IntArray, LongArray};
+use crate::{
+    value::{empty_list_token, EMPTY_LIST_TOKEN},
+    Tag,
+};
+
+/// The map used for [`Value::Compound`]. This is a `HashMap`, or an `IndexMap`
+/// with the `preserve_order` feature, like [`crate::CompoundMap`].
+#[cfg(not(feature = "preserve_order"))]
+pub type CompoundMap<'a> = std::collections::HashMap<Cow<'a, str>, Value<'a>>;
+
+/// The map used for [`Value::Compound`]. This is a `HashMap`, or an `IndexMap`
+/// with the `preserve_order` feature, like [`crate::CompoundMap`].
+#[cfg(feature = "preserve_order")]
+pub type CompoundMap<'a> = indexmap::IndexMap<Cow<'a, str>, Value<'a>>;
+
+/// Value is a complete NBT value that borrows from the input where it can.
+/// Strings are borrowed unless they needed decoding from the Java CESU-8
+/// encoding, and arrays are always borrowed. This makes it cheaper than the
+/// owned [`fastnbt::Value`][`crate::Value`] for inspecting NBT of unknown
+/// structure.
+///
+/// Borrowing requires deserializing from a slice with
+/// [`from_bytes`][`crate::from_bytes`]. Use [`Value::into_owned`] to convert it
+/// into the owned `Value`.
+///
+/// ```no_run
+/// # use fastnbt::borrow::Value;
+/// # use fastnbt::error::Result;
+/// #
+/// # fn main() -> Result<()> {
+/// #   let buf: Vec<u8> = vec![];
+///     let chunk: Value = fastnbt::from_bytes(&buf)?;
+///     if let Value::Compound(chunk) = &chunk {
+///         if let Some(Value::String(status)) = chunk.get("Status") {
+///             println!("Status: {}", status);
+///         }
+///     }
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(Cow<'a, str>),
+    ByteArray(ByteArray<'a>),
+    IntArray(IntArray<'a>),
+    LongArray(LongArray<'a>),
+    /// A list and the tag of its elements. See [`crate::Value::List`].
+    List(Tag, Vec<Value<'a>>),
+    Compound(CompoundMap<'a>),
+}
+
+impl<'a> Value<'a> {
+    /// Convert into the owned [`fastnbt::Value`][`crate::Value`], copying any
+    /// borrowed strings and arrays.
+    pub fn into_owned(self) -> crate::Value {
+        match self {
+            Value::Byte(v) => crate::Value::Byte(v),
+            Value::Short(v) => crate::Value::Short(v),
+            Value::Int(v) => crate::Value::Int(v),
+            Value::Long(v) => crate::Value::Long(v),
+            Value::Float(v) => crate::Value::Float(v),
+            Value::Double(v) => crate::Value::Double(v),
+            Value::String(v) => crate::Value::String(v.into_owned()),
+            Value::ByteArray(v) => {
+                crate::Value::ByteArray(crate::ByteArray::new(v.iter().collect()))
+            }
+            Value::IntArray(v) => crate::Value::IntArray(crate::IntArray::new(v.iter().collect())),
+            Value::LongArray(v) => {
+                crate::Value::LongArray(crate::LongArray::new(v.iter().collect()))
+            }
+            Value::List(tag, v) => {
+                crate::Value::List(tag, v.into_iter().map(Value::into_owned).collect())
+            }
+            Value::Compound(v) => crate::Value::Compound(
+                v.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The tag of this value.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::Byte(_) => Tag::Byte,
+            Value::Short(_) => Tag::Short,
+            Value::Int(_) => Tag::Int,
+            Value::Long(_) => Tag::Long,
+            Value::Float(_) => Tag::Float,
+            Value::Double(_) => Tag::Double,
+            Value::String(_) => Tag::String,
+            Value::ByteArray(_) => Tag::ByteArray,
+            Value::IntArray(_) => Tag::IntArray,
+            Value::LongArray(_) => Tag::LongArray,
+            Value::List(..) => Tag::List,
+            Value::Compound(_) => Tag::Compound,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<Value<'a>> for crate::Value {
+    fn from(val: Value<'a>) -> Self {
+        val.into_owned()
+    }
+}
+
+impl<'a> Serialize for Value<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::Byte(v) => serializer.serialize_i8(*v),
+            Value::Short(v) => serializer.serialize_i16(*v),
+            Value::Int(v) => serializer.serialize_i32(*v),
+            Value::Long(v) => serializer.serialize_i64(*v),
+            Value::Float(v) => serializer.serialize_f32(*v),
+            Value::Double(v) => serializer.serialize_f64(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::ByteArray(v) => v.serialize(serializer),
+            // Arrays borrowed from NBT hold big endian data, while the Serialize
+            // impls of the borrowed arrays expect native endian data. Going
+            // through the owned arrays writes the values iter() sees.
+            Value::IntArray(v) => crate::IntArray::new(v.iter().collect()).serialize(serializer),
+            Value::LongArray(v) => crate::LongArray::new(v.iter().collect()).serialize(serializer),
+            Value::List(tag, v) if v.is_empty() && *tag != Tag::End => serializer
+                .serialize_tuple_struct(empty_list_token(*tag), 0)?
+                .end(),
+            Value::List(_, v) => v.serialize(serializer),
+            Value::Compound(v) => v.serialize(serializer),
+        }
+    }
+}
+
+impl<'a, 'de: 'a> Deserialize<'de> for Value<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(EMPTY_LIST_TOKEN, ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("valid NBT")
+    }
+
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Byte(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Short(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Long(v))
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::Double(v))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::String(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::String(Cow::Owned(v.to_owned())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Value::String(Cow::Owned(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(el) = seq.next_element()? {
+            v.push(el);
+        }
+
+        let tag = v.first().map_or(Tag::End, Value::tag);
+        Ok(Value::List(tag, v))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key_seed(KeyClassifier)? {
+            Some(KeyClass::Compound(first_key)) => {
+                let mut compound = CompoundMap::new();
+
+                compound.insert(first_key, map.next_value()?);
+                while let Some(key) = map.next_key_seed(CowStrSeed)? {
+                    compound.insert(key, map.next_value()?);
+                }
+
+                Ok(Value::Compound(compound))
+            }
+            Some(KeyClass::ByteArray) => {
+                let data = map.next_value::<&[u8]>()?;
+                Ok(Value::ByteArray(ByteArray::from_bytes(data)))
+            }
+            Some(KeyClass::IntArray) => {
+                let data = map.next_value::<&[u8]>()?;
+                Ok(Value::IntArray(IntArray::from_bytes(data)))
+            }
+            Some(KeyClass::LongArray) => {
+                let data = map.next_value::<&[u8]>()?;
+                Ok(Value::LongArray(LongArray::from_bytes(data)))
+            }
+            Some(KeyClass::EmptyList) => {
+                let tag = map.next_value::<u8>()?;
+                let tag =
+                    Tag::try_from(tag).map_err(|_| serde::de::Error::custom("invalid list tag"))?;
+                Ok(Value::List(tag, vec![]))
+            }
+            // No keys just means an empty compound.
+            None => Ok(Value::Compound(Default::default())),
+        }
+    }
+}
+
+/// Deserializes a compound key, keeping it borrowed if possible.
+struct CowStrSeed;
+
+impl<'de> DeserializeSeed<'de> for CowStrSeed {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        super::deserialize_cow_str(deserializer)
+    }
+}
+
+enum KeyClass<'de> {
+    Compound(Cow<'de, str>),
+    ByteArray,
+    IntArray,
+    LongArray,
+    EmptyList,
+}
+
+/// Tells apart the first key of a compound from the tokens used for arrays.
+struct KeyClassifier;
+
+impl<'de> DeserializeSeed<'de> for KeyClassifier {
+    type Value = KeyClass<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let key = super::deserialize_cow_str(deserializer)?;
+
+        Ok(match key.as_ref() {
+            crate::BYTE_ARRAY_TOKEN => KeyClass::ByteArray,
+            crate::INT_ARRAY_TOKEN => KeyClass::IntArray,
+            crate::LONG_ARRAY_TOKEN => KeyClass::LongArray,
+            EMPTY_LIST_TOKEN => KeyClass::EmptyList,
+            _ => KeyClass::Compound(key),
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Helpers for compressed NBT. Requires the `compression` feature.
+//!
+//! Most NBT files are compressed. `level.dat`, player data and schematics use
+//! GZip, while chunks in region files are usually Zlib. The functions here
+//! detect the compression used, so you can deserialize these files directly:
+//!
+//! ```no_run
+//! # use fastnbt::Value;
+//! # use fastnbt::error::Result;
+//! # fn main() -> Result<()> {
+//! let file = std::fs::read("level.dat")?;
+//! let mut level: Value = fastnbt::from_compressed_bytes(&file)?;
+//!
+//! // ...modify the level...
+//!
+//! let out = std::fs::File::create("level.dat")?;
+//! fastnbt::to_gzip_writer(out, &level)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use flate2::{
+    bufread::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::Result, from_bytes_with_opts, from_reader_with_opts, to_writer_with_opts, DeOpts,
+    SerOpts,
+};
+
+/// The compression applied to some NBT data.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Compression {
+    /// Uncompressed NBT.
+    None,
+    /// GZip compression, used by `level.dat` and player data.
+    Gzip,
+    /// Zlib compression, used by chunks in region files.
+    Zlib,
+}
+
+impl Compression {
+    /// Detect the compression of some data from its first few bytes. Anything
+    /// that isn't GZip or Zlib is assumed to be uncompressed.
+    pub fn detect(data: &[u8]) -> Self {
+        match data {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            // Java NBT almost always has a compound root.
+            [0x0a, ..] => Compression::None,
+            // Zlib has deflate as the compression method in the lower nibble
+            // of the first byte, and a header checksum. Uncompressed NBT
+            // starts with a tag, and only a String tag (8) has that lower
+            // nibble. As a first byte of Zlib, 8 would mean a 256 byte
+            // window, which zlib and flate2 never write, so treat it as a
+            // String root, as network NBT can have.
+            [cmf, flg, ..]
+                if *cmf != 0x08
+                    && cmf & 0x0f == 8
+                    && u16::from_be_bytes([*cmf, *flg]) % 31 == 0 =>
+            {
+                Compression::Zlib
+            }
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Deserialize into a `T` from NBT data that might be compressed with GZip or
+/// Zlib. The compression is detected automatically.
+pub fn from_compressed_bytes<T>(input: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_compressed_bytes_with_opts(input, Default::default())
+}
+
+/// Similar to [`from_compressed_bytes`] but with options.
+pub fn from_compressed_bytes_with_opts<T>(input: &[u8], opts: DeOpts) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    match Compression::detect(input) {
+        Compression::None => from_bytes_with_opts(input, opts),
+        Compression::Gzip => from_reader_with_opts(GzDecoder::new(input), opts),
+        Compression::Zlib => from_reader_with_opts(ZlibDecoder::new(input), opts),
+    }
+}
+
+/// Deserialize into a `T` from a reader of NBT data that might be compressed
+/// with GZip or Zlib. The compression is detected automatically.
+pub fn from_compressed_reader<R, T>(reader: R) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    from_compressed_reader_with_opts(reader, Default::default())
+}
+
+/// Similar to [`from_compressed_reader`] but with options.
+pub fn from_compressed_reader_with_opts<R, T>(reader: R, opts: DeOpts) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut reader = BufReader::new(reader);
+
+    // Peeking like this could in theory only see a single byte, but this will
+    // only be the case for a tiny input which can't be compressed anyway.
+    match Compression::detect(reader.fill_buf()?) {
+        Compression::None => from_reader_with_opts(reader, opts),
+        Compression::Gzip => from_reader_with_opts(GzDecoder::new(reader), opts),
+        Compression::Zlib => from_reader_with_opts(ZlibDecoder::new(reader), opts),
+    }
+}
+
+/// Serialize some `T` into GZip compressed NBT data, as used for `level.dat`
+/// and player data.
+pub fn to_gzip_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
+    to_compressed_writer_with_opts(writer, v, Compression::Gzip, Default::default())
+}
+
+/// Serialize some `T` into Zlib compressed NBT data, as used for chunks in
+/// region files.
+pub fn to_zlib_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
+    to_compressed_writer_with_opts(writer, v, Compression::Zlib, Default::default())
+}
+
+/// Serialize some `T` into NBT data with the given compression and options.
+pub fn to_compressed_writer_with_opts<T: Serialize, W: Write>(
+    writer: W,
+    v: &T,
+    compression: Compression,
+    opts: SerOpts,
+) -> Result<()> {
+    let level = flate2::Compression::default();
+
+    match compression {
+        Compression::None => to_writer_with_opts(writer, v, opts),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(writer, level);
+            to_writer_with_opts(&mut encoder, v, opts)?;
+            encoder.finish()?;
+            Ok(())
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(writer, level);
+            to_writer_with_opts(&mut encoder, v, opts)?;
+            encoder.finish()?;
+            Ok(())
+        }
+    }
+}
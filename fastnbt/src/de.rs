@@ -5,16 +5,21 @@
 //! * an arbitrary [`Value`](../enum.Value.html).
 //! * enums. For NBT typically you want either internally or untagged enums.
 //!
-//! This deserializer only supports [`from_bytes`](fn.from_bytes.html). This is
+//! Typically you will want [`from_bytes`](../fn.from_bytes.html). This is
 //! usually fine as most structures stored in this format are reasonably small,
 //! the largest likely being an individual Chunk which maxes out at 1 MiB
 //! compressed. This enables zero-copy deserialization in places.
 //!
+//! If the data is not already in memory, [`from_reader`](../fn.from_reader.html)
+//! deserializes from any [`Read`][`std::io::Read`] instead. Nothing can be
+//! borrowed from a reader, so the target type must be
+//! [`DeserializeOwned`][`serde::de::DeserializeOwned`].
+//!
 //! # Avoiding allocations
 //!
-//! Due to having all the input in memory, we can avoid allocations for things
-//! like strings and vectors, instead deserializing into a reference to the
-//! input data.
+//! When deserializing from bytes, all the input is in memory, so we can avoid
+//! allocations for things like strings and vectors, instead deserializing into
+//! a reference to the input data.
 //!
 //! The following table summarises what types you likely want to store NBT data
 //! in for owned or borrowed types:
@@ -223,41 +228,70 @@
 
 use std::borrow::Cow;
 use std::convert::{TryFrom, TryInto};
-use std::ops::Range;
+use std::io::Read;
 
 use crate::de_arrays::ArrayWrapperAccess;
 use crate::error::{Error, Result};
-use crate::{DeOpts, Tag};
-use byteorder::{BigEndian, ReadBytesExt};
+use crate::limits::Limits;
+use crate::named_root::NAMED_ROOT_TOKEN;
+use crate::{varint, Coercion, DeOpts, Flavour, Tag};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 
+use serde::de::value::CowStrDeserializer;
 use serde::de::Unexpected;
 use serde::{de, forward_to_deserialize_any, serde_if_integer128};
 
+pub use crate::input::{Input, Reader, Slice};
+
 /// Deserializer for NBT data. See the [`de`] module for more information.
 ///
 /// [`de`]: ./index.html
-pub struct Deserializer<'de> {
-    pub(crate) input: InputHelper<'de>,
+pub struct Deserializer<In> {
+    pub(crate) input: InputHelper<In>,
     layers: Vec<Layer>,
     last_hint: Option<&'static str>,
+    root_header_read: bool,
     pub(crate) opts: DeOpts,
 }
 
-impl<'de> Deserializer<'de> {
-    /// Create Deserializer for a `T` from some NBT data. See the [`de`] module
-    /// for more information.
-    ///
-    /// [`de`]: ./index.html
-    pub fn from_bytes(input: &'de [u8], opts: DeOpts) -> Self {
+impl<In> Deserializer<In> {
+    /// Create Deserializer from some [`Input`]. Typically you want
+    /// [`Deserializer::from_bytes`] or [`Deserializer::from_reader`] instead.
+    pub fn new(input: In, opts: DeOpts) -> Self {
         Self {
-            input: InputHelper(input),
+            input: InputHelper {
+                input,
+                flavour: opts.flavour,
+                limits: Limits::new(&opts),
+            },
             layers: vec![],
             last_hint: None,
+            root_header_read: false,
             opts,
         }
     }
 }
 
+impl<'de> Deserializer<Slice<'de>> {
+    /// Create Deserializer for a `T` from some NBT data. See the [`de`] module
+    /// for more information.
+    ///
+    /// [`de`]: ./index.html
+    pub fn from_bytes(input: &'de [u8], opts: DeOpts) -> Self {
+        Self::new(Slice::new(input), opts)
+    }
+}
+
+impl<R: Read> Deserializer<Reader<R>> {
+    /// Create Deserializer for a `T` from a reader of NBT data. See the [`de`]
+    /// module for more information.
+    ///
+    /// [`de`]: ./index.html
+    pub fn from_reader(reader: R, opts: DeOpts) -> Self {
+        Self::new(Reader::new(reader), opts)
+    }
+}
+
 enum Stage {
     Tag,
     Name,
@@ -266,20 +300,77 @@ enum Stage {
 
 enum Layer {
     List {
+        len: i32,
         remaining_elements: i32, // would make more sense as usize, but format is i32.
         element_tag: Tag,
     },
     Compound {
         current_tag: Option<Tag>,
         stage: Stage,
+        // Only used for error reporting. Kept as a String to reuse the
+        // allocation from key to key.
+        current_name: String,
     },
 }
 
+impl<'de, In: Input<'de>> Deserializer<In> {
+    /// Attach the current position in the input to an error.
+    pub(crate) fn locate(&self, e: Error) -> Error {
+        e.at(self.input.input.position(), Some(self.path()))
+    }
+
+    /// Consume the tag and name of the root compound, returning the name.
+    /// Network NBT has no name.
+    fn consume_root_header(&mut self) -> Result<Cow<'de, str>> {
+        let tag = self.input.consume_tag()?;
+        if tag != Tag::Compound {
+            return Err(Error::no_root_compound());
+        }
+
+        if self.opts.network_nbt {
+            Ok(Cow::Borrowed(""))
+        } else {
+            self.input.consume_name()
+        }
+    }
+
+    /// The path of keys and indices to the current value, eg `a[3].b`.
+    fn path(&self) -> String {
+        let mut path = String::new();
+        for layer in &self.layers {
+            match layer {
+                Layer::Compound { current_name, .. } => {
+                    if !current_name.is_empty() {
+                        if !path.is_empty() {
+                            path.push('.');
+                        }
+                        path.push_str(current_name);
+                    }
+                }
+                Layer::List {
+                    len,
+                    remaining_elements,
+                    ..
+                } => {
+                    if remaining_elements < len {
+                        path.push_str(&format!("[{}]", len - remaining_elements - 1));
+                    }
+                }
+            }
+        }
+        path
+    }
+}
+
 /// Without this we would not be able to implement helper functions for the
 /// input. If we wrote the helper functions as part of the Deserializer impl, it
 /// would force borrowing the entire deserializer mutably. This helper allows us
 /// to borrow just the input, making us free to also borrow/mutate the layers.
-pub(crate) struct InputHelper<'de>(pub(crate) &'de [u8]);
+pub(crate) struct InputHelper<In> {
+    pub(crate) input: In,
+    flavour: Flavour,
+    limits: Limits,
+}
 
 fn visit_cow_str<'de, V>(v: V, s: Cow<'de, str>) -> Result<V::Value>
 where
@@ -291,25 +382,38 @@ where
     }
 }
 
-fn consume_value<'de, V>(de: &mut Deserializer<'de>, visitor: V, tag: Tag) -> Result<V::Value>
+fn visit_cow_bytes<'de, V>(v: V, bs: Cow<'de, [u8]>) -> Result<V::Value>
 where
     V: de::Visitor<'de>,
+{
+    match bs {
+        Cow::Borrowed(bs) => v.visit_borrowed_bytes(bs),
+        Cow::Owned(bs) => v.visit_byte_buf(bs),
+    }
+}
+
+fn consume_value<'de, In, V>(de: &mut Deserializer<In>, visitor: V, tag: Tag) -> Result<V::Value>
+where
+    In: Input<'de>,
+    V: de::Visitor<'de>,
 {
     let last_hint = de.last_hint;
     de.last_hint = None;
 
     match tag {
-        Tag::Byte => visitor.visit_i8(de.input.0.read_i8()?),
-        Tag::Short => visitor.visit_i16(de.input.0.read_i16::<BigEndian>()?),
-        Tag::Int => visitor.visit_i32(de.input.0.read_i32::<BigEndian>()?),
-        Tag::Long => visitor.visit_i64(de.input.0.read_i64::<BigEndian>()?),
+        Tag::Byte => visitor.visit_i8(de.input.consume_byte()?),
+        Tag::Short => visitor.visit_i16(de.input.consume_short()?),
+        Tag::Int => visitor.visit_i32(de.input.consume_int()?),
+        Tag::Long => visitor.visit_i64(de.input.consume_long()?),
         Tag::String => visit_cow_str(visitor, de.input.consume_size_prefixed_string()?),
         Tag::Float => visitor.visit_f32(de.input.consume_float()?),
         Tag::Double => visitor.visit_f64(de.input.consume_double()?),
         Tag::Compound => {
+            de.input.limits.depth(de.layers.len() + 1)?;
             de.layers.push(Layer::Compound {
                 current_tag: None,
                 stage: Stage::Tag,
+                current_name: String::new(),
             });
 
             visitor.visit_map(CompoundAccess::new(de))
@@ -333,13 +437,29 @@ where
             }
 
             if size as usize >= de.opts.max_seq_len {
-                return Err(Error::bespoke(format!(
+                return Err(Error::size(format!(
                     "size ({}) greater than max sequence length ({})",
                     size, de.opts.max_seq_len,
                 )));
             }
 
+            de.input.limits.depth(de.layers.len() + 1)?;
+            de.input.limits.list(element_tag, size as usize)?;
+
+            // An empty list has no elements to tell Value its type, so give it
+            // the element tag directly.
+            if last_hint == Some(crate::value::EMPTY_LIST_TOKEN)
+                && size == 0
+                && element_tag != Tag::End
+            {
+                return visitor.visit_map(de::value::MapDeserializer::new(std::iter::once((
+                    crate::value::EMPTY_LIST_TOKEN,
+                    element_tag as u8,
+                ))));
+            }
+
             de.layers.push(Layer::List {
+                len: size,
                 remaining_elements: size,
                 element_tag,
             });
@@ -348,50 +468,36 @@ where
         }
         Tag::ByteArray => {
             if last_hint == Some("seq") {
-                return Err(Error::bespoke(
+                return Err(Error::type_mismatch(
                     "expected NBT Array, found seq: use ByteArray, IntArray or LongArray types"
                         .into(),
                 ));
             }
 
-            let size = de.input.consume_list_size()?;
-
-            // visitor.visit_borrowed_bytes(bs)
-            visitor.visit_map(ArrayWrapperAccess::bytes(
-                de,
-                size.try_into()
-                    .map_err(|_| Error::bespoke("nbt array size was negative".to_string()))?,
-            )?)
+            let size = de.input.consume_array_len(Tag::Byte)?;
+            visitor.visit_map(ArrayWrapperAccess::bytes(de, size))
         }
         Tag::IntArray => {
             if last_hint == Some("seq") {
-                return Err(Error::bespoke(
+                return Err(Error::type_mismatch(
                     "expected NBT Array, found seq: use ByteArray, IntArray or LongArray types"
                         .into(),
                 ));
             }
 
-            let size = de.input.consume_list_size()?;
-            visitor.visit_map(ArrayWrapperAccess::ints(
-                de,
-                size.try_into()
-                    .map_err(|_| Error::bespoke("nbt array size was negative".to_string()))?,
-            )?)
+            let size = de.input.consume_array_len(Tag::Int)?;
+            visitor.visit_map(ArrayWrapperAccess::ints(de, size))
         }
         Tag::LongArray => {
             if last_hint == Some("seq") {
-                return Err(Error::bespoke(
+                return Err(Error::type_mismatch(
                     "expected NBT Array, found seq: use ByteArray, IntArray or LongArray types"
                         .into(),
                 ));
             }
 
-            let size = de.input.consume_list_size()?;
-            visitor.visit_map(ArrayWrapperAccess::longs(
-                de,
-                size.try_into()
-                    .map_err(|_| Error::bespoke("nbt array size was negative".to_string()))?,
-            )?)
+            let size = de.input.consume_array_len(Tag::Long)?;
+            visitor.visit_map(ArrayWrapperAccess::longs(de, size))
         }
         // This would really only occur when we encounter a list where the
         // element type is 'End', but we specifically handle that case, so we
@@ -402,7 +508,149 @@ where
     }
 }
 
-fn get_i128_value<'de>(de: &mut Deserializer<'de>) -> Result<i128> {
+/// The primitive number types a visitor can ask for.
+#[derive(Clone, Copy)]
+enum Number {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+}
+
+impl Number {
+    /// The tag that this type is serialized as.
+    fn tag(self) -> Tag {
+        match self {
+            Number::I8 | Number::U8 => Tag::Byte,
+            Number::I16 | Number::U16 => Tag::Short,
+            Number::I32 | Number::U32 => Tag::Int,
+            Number::I64 | Number::U64 => Tag::Long,
+            Number::F32 => Tag::Float,
+            Number::F64 => Tag::Double,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Number::I8 => "i8",
+            Number::I16 => "i16",
+            Number::I32 => "i32",
+            Number::I64 => "i64",
+            Number::U8 => "u8",
+            Number::U16 => "u16",
+            Number::U32 => "u32",
+            Number::U64 => "u64",
+            Number::F32 => "f32",
+            Number::F64 => "f64",
+        }
+    }
+}
+
+macro_rules! deserialize_numbers {
+    ($($method:ident => $number:expr,)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: de::Visitor<'de>,
+            {
+                deserialize_number(self, visitor, $number)
+            }
+        )*
+    };
+}
+
+/// Deserialize a primitive number, converting it according to the
+/// [`Coercion`] option.
+fn deserialize_number<'de, In, V>(
+    de: &mut Deserializer<In>,
+    visitor: V,
+    number: Number,
+) -> Result<V::Value>
+where
+    In: Input<'de>,
+    V: de::Visitor<'de>,
+{
+    if de.opts.coercion == Coercion::Visitor {
+        return de::Deserializer::deserialize_any(de, visitor);
+    }
+
+    let tag = match de.layers.last_mut() {
+        Some(Layer::Compound {
+            current_tag: Some(tag),
+            stage: stage @ Stage::Value,
+            ..
+        }) => {
+            *stage = Stage::Tag;
+            *tag
+        }
+        Some(Layer::List { element_tag, .. }) => *element_tag,
+        // Compound keys and the root are not numbers.
+        _ => return de::Deserializer::deserialize_any(de, visitor),
+    };
+
+    let mismatch = || {
+        Error::type_mismatch(format!(
+            "expected {} for {}, found {}",
+            number.tag(),
+            number.name(),
+            tag
+        ))
+    };
+
+    if de.opts.coercion == Coercion::Strict && tag != number.tag() {
+        return Err(mismatch());
+    }
+
+    let int = match tag {
+        Tag::Byte => de.input.consume_byte()? as i64,
+        Tag::Short => de.input.consume_short()? as i64,
+        Tag::Int => de.input.consume_int()? as i64,
+        Tag::Long => de.input.consume_long()?,
+        Tag::Float | Tag::Double => {
+            let float = match tag {
+                Tag::Float => de.input.consume_float()? as f64,
+                _ => de.input.consume_double()?,
+            };
+            return match number {
+                Number::F32 => visitor.visit_f32(float as f32),
+                Number::F64 => visitor.visit_f64(float),
+                _ => Err(mismatch()),
+            };
+        }
+        _ => return Err(mismatch()),
+    };
+
+    let out_of_range = |_| {
+        Error::type_mismatch(format!(
+            "{} {} is out of range for {}",
+            tag,
+            int,
+            number.name()
+        ))
+    };
+
+    match number {
+        Number::I8 => visitor.visit_i8(int.try_into().map_err(out_of_range)?),
+        Number::I16 => visitor.visit_i16(int.try_into().map_err(out_of_range)?),
+        Number::I32 => visitor.visit_i32(int.try_into().map_err(out_of_range)?),
+        Number::I64 => visitor.visit_i64(int),
+        Number::U8 => visitor.visit_u8(int.try_into().map_err(out_of_range)?),
+        Number::U16 => visitor.visit_u16(int.try_into().map_err(out_of_range)?),
+        Number::U32 => visitor.visit_u32(int.try_into().map_err(out_of_range)?),
+        Number::U64 => visitor.visit_u64(int.try_into().map_err(out_of_range)?),
+        Number::F32 => visitor.visit_f32(int as f32),
+        Number::F64 => visitor.visit_f64(int as f64),
+    }
+}
+
+fn get_i128_value<'de, In: Input<'de>>(de: &mut Deserializer<In>) -> Result<i128> {
     let tag = match de.layers.last() {
         Some(Layer::Compound { current_tag, .. }) => current_tag.as_ref().ok_or_else(|| {
             Error::bespoke("deserialize i128: did not know value's tag".to_string())
@@ -415,34 +663,36 @@ fn get_i128_value<'de>(de: &mut Deserializer<'de>) -> Result<i128> {
 
     match tag {
         Tag::IntArray => {
-            let size = de.input.consume_list_size()?;
-            let bs = de.input.consume_bytes_usize(try_size(size, 4)?)?;
-            match bs.try_into() {
+            let size = de.input.consume_array_len(Tag::Int)?;
+            let bs = de.input.consume_numbers(Tag::Int, size)?;
+            match bs.as_ref().try_into() {
                 Ok(bs) => Ok(i128::from_be_bytes(bs)),
-                Err(_) => Err(Error::bespoke(format!(
+                Err(_) => Err(Error::type_mismatch(format!(
                     "deserialize i128: expected IntArray of length 4 with 16 bytes, found {} bytes",
                     bs.len()
                 ))),
             }
         }
-        _ => Err(Error::bespoke(
+        _ => Err(Error::type_mismatch(
             "deserialize i128: expected IntArray value".to_string(),
         )),
     }
 }
 
-impl<'de> InputHelper<'de> {
-    // Safely get a subslice, erroring if there's not enough input.
-    pub(crate) fn subslice(&self, r: Range<usize>) -> Result<&'de [u8]> {
-        if r.end <= self.0.len() {
-            Ok(&self.0[r])
-        } else {
-            Err(Error::unexpected_eof())
+fn decode_str(data: &[u8], flavour: Flavour) -> Result<Cow<'_, str>> {
+    let s = match flavour {
+        Flavour::JavaBigEndian => cesu8::from_java_cesu8(data).ok(),
+        Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+            std::str::from_utf8(data).ok().map(Cow::Borrowed)
         }
-    }
+    };
 
+    s.ok_or_else(|| Error::nonunicode_string(data))
+}
+
+impl<'de, In: Input<'de>> InputHelper<In> {
     fn consume_tag(&mut self) -> Result<Tag> {
-        let tag_byte = self.0.read_u8()?;
+        let tag_byte = self.input.read_u8()?;
         Tag::try_from(tag_byte).map_err(|_| Error::invalid_tag(tag_byte))
     }
 
@@ -451,65 +701,167 @@ impl<'de> InputHelper<'de> {
     }
 
     fn consume_size_prefixed_string(&mut self) -> Result<Cow<'de, str>> {
-        let len = self.0.read_u16::<BigEndian>()? as usize;
-        let str_data = self.subslice(0..len)?;
-        let s = cesu8::from_java_cesu8(str_data)
-            .map_err(|_| Error::nonunicode_string(&self.0[..len]))?;
+        match self.consume_size_prefixed_bytes()? {
+            Cow::Borrowed(str_data) => decode_str(str_data, self.flavour),
+            Cow::Owned(str_data) => {
+                decode_str(&str_data, self.flavour).map(|s| Cow::Owned(s.into_owned()))
+            }
+        }
+    }
 
-        self.0 = &self.0[len..];
-        Ok(s)
+    fn consume_string_len(&mut self) -> Result<usize> {
+        let len = match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_u16::<BigEndian>()? as usize,
+            Flavour::BedrockLittleEndian => self.input.read_u16::<LittleEndian>()? as usize,
+            Flavour::BedrockNetwork => varint::read_var_u32(&mut self.input)? as usize,
+        };
+        self.limits.string(len)?;
+        Ok(len)
     }
 
     fn ignore_size_prefixed_string(&mut self) -> Result<()> {
-        let len = self.0.read_u16::<BigEndian>()? as usize;
-        self.0 = &self.0[len..];
-        Ok(())
+        let len = self.consume_string_len()?;
+        self.input.ignore_bytes(len)
     }
 
-    fn consume_size_prefixed_bytes(&mut self) -> Result<&'de [u8]> {
-        let len = self.0.read_u16::<BigEndian>()? as usize;
-        let str_data = self.subslice(0..len)?;
+    fn consume_size_prefixed_bytes(&mut self) -> Result<Cow<'de, [u8]>> {
+        let len = self.consume_string_len()?;
+        self.consume_bytes_usize(len)
+    }
 
-        self.0 = &self.0[len..];
-        Ok(str_data)
+    pub(crate) fn consume_bytes_usize(&mut self, size: usize) -> Result<Cow<'de, [u8]>> {
+        self.input.consume_bytes(size)
     }
 
-    pub(crate) fn consume_bytes(&mut self, size: i32) -> Result<&'de [u8]> {
-        let size: usize = size.try_into().map_err(|_| Error::invalid_size(size))?;
-        self.consume_bytes_usize(size)
+    /// Consume `count` numbers of type `element`, which must be an integral
+    /// tag, returning their big endian bytes. For Java's big endian NBT this
+    /// borrows from the input if the input allows it.
+    pub(crate) fn consume_numbers(&mut self, element: Tag, count: usize) -> Result<Cow<'de, [u8]>> {
+        let (width, size) = numbers_size(element, count)?;
+
+        match (self.flavour, element) {
+            (Flavour::JavaBigEndian, _) | (_, Tag::Byte) => self.consume_bytes_usize(size),
+            (Flavour::BedrockLittleEndian, _) | (Flavour::BedrockNetwork, Tag::Short) => {
+                let mut bs = self.consume_bytes_usize(size)?.into_owned();
+                for chunk in bs.chunks_exact_mut(width) {
+                    chunk.reverse();
+                }
+                Ok(Cow::Owned(bs))
+            }
+            (Flavour::BedrockNetwork, _) => {
+                // The count comes from the input, so don't trust it for
+                // preallocating. The buffer grows as values are actually read.
+                let mut bs = Vec::new();
+                for _ in 0..count {
+                    if element == Tag::Int {
+                        bs.extend_from_slice(&self.consume_int()?.to_be_bytes());
+                    } else {
+                        bs.extend_from_slice(&self.consume_long()?.to_be_bytes());
+                    }
+                }
+                Ok(Cow::Owned(bs))
+            }
+        }
     }
 
-    pub(crate) fn consume_bytes_usize(&mut self, size: usize) -> Result<&'de [u8]> {
-        let bs = self.subslice(0..size)?;
-        self.0 = &self.0[size..];
-        Ok(bs)
+    /// Skip `count` numbers of type `element` like [`Self::consume_numbers`].
+    /// Only variable length numbers need to be read to skip them.
+    fn ignore_numbers(&mut self, element: Tag, count: usize) -> Result<()> {
+        match (self.flavour, element) {
+            (Flavour::BedrockNetwork, Tag::Int) => {
+                for _ in 0..count {
+                    self.consume_int()?;
+                }
+                Ok(())
+            }
+            (Flavour::BedrockNetwork, Tag::Long) => {
+                for _ in 0..count {
+                    self.consume_long()?;
+                }
+                Ok(())
+            }
+            _ => {
+                let (_, size) = numbers_size(element, count)?;
+                self.input.ignore_bytes(size)
+            }
+        }
+    }
+
+    fn consume_byte(&mut self) -> Result<i8> {
+        Ok(self.input.read_i8()?)
+    }
+
+    fn consume_short(&mut self) -> Result<i16> {
+        Ok(match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_i16::<BigEndian>()?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.input.read_i16::<LittleEndian>()?
+            }
+        })
+    }
+
+    fn consume_int(&mut self) -> Result<i32> {
+        Ok(match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_i32::<BigEndian>()?,
+            Flavour::BedrockLittleEndian => self.input.read_i32::<LittleEndian>()?,
+            Flavour::BedrockNetwork => varint::read_var_i32(&mut self.input)?,
+        })
+    }
+
+    fn consume_long(&mut self) -> Result<i64> {
+        Ok(match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_i64::<BigEndian>()?,
+            Flavour::BedrockLittleEndian => self.input.read_i64::<LittleEndian>()?,
+            Flavour::BedrockNetwork => varint::read_var_i64(&mut self.input)?,
+        })
     }
 
     fn consume_list_size(&mut self) -> Result<i32> {
-        Ok(self.0.read_i32::<BigEndian>()?)
+        self.consume_int()
+    }
+
+    /// Consume the length of an NBT array of `element`s, checking it against
+    /// the limits.
+    fn consume_array_len(&mut self, element: Tag) -> Result<usize> {
+        let size = self.consume_list_size()?;
+        let size: usize = size.try_into().map_err(|_| Error::invalid_size(size))?;
+        self.limits.array(element, size)?;
+        Ok(size)
     }
 
     fn consume_float(&mut self) -> Result<f32> {
-        Ok(self.0.read_f32::<BigEndian>()?)
+        Ok(match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_f32::<BigEndian>()?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.input.read_f32::<LittleEndian>()?
+            }
+        })
     }
 
     fn consume_double(&mut self) -> Result<f64> {
-        Ok(self.0.read_f64::<BigEndian>()?)
+        Ok(match self.flavour {
+            Flavour::JavaBigEndian => self.input.read_f64::<BigEndian>()?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.input.read_f64::<LittleEndian>()?
+            }
+        })
     }
 
-    fn ignore_value(&mut self, tag: Tag) -> Result<()> {
+    /// Ignore a value with the given tag, which is inside `depth` compounds
+    /// and lists.
+    fn ignore_value(&mut self, tag: Tag, depth: usize) -> Result<()> {
         match tag {
             Tag::Byte => {
-                self.0.read_i8()?;
+                self.consume_byte()?;
             }
             Tag::Short => {
-                self.0.read_i16::<BigEndian>()?;
+                self.consume_short()?;
             }
             Tag::Int => {
-                self.0.read_i32::<BigEndian>()?;
+                self.consume_int()?;
             }
             Tag::Long => {
-                self.0.read_i64::<BigEndian>()?;
+                self.consume_long()?;
             }
             Tag::Float => {
                 self.consume_float()?;
@@ -521,18 +873,20 @@ impl<'de> InputHelper<'de> {
                 self.ignore_size_prefixed_string()?;
             }
             Tag::ByteArray => {
-                let size = self.consume_list_size()?;
-                self.consume_bytes(size)?;
+                let size = self.consume_array_len(Tag::Byte)?;
+                self.input.ignore_bytes(size)?;
             }
             Tag::IntArray => {
-                let size = self.consume_list_size()?;
-                self.consume_bytes_usize(try_size(size, 4)?)?;
+                let size = self.consume_array_len(Tag::Int)?;
+                self.ignore_numbers(Tag::Int, size)?;
             }
             Tag::LongArray => {
-                let size = self.consume_list_size()?;
-                self.consume_bytes_usize(try_size(size, 8)?)?;
+                let size = self.consume_array_len(Tag::Long)?;
+                self.ignore_numbers(Tag::Long, size)?;
             }
             Tag::Compound => {
+                self.limits.depth(depth + 1)?;
+
                 // Need to loop and ignore each value until we reach an end tag.
 
                 // we need to enter the compound, then ignore it's value.
@@ -544,14 +898,16 @@ impl<'de> InputHelper<'de> {
 
                     // consume the name.
                     self.ignore_size_prefixed_string()?;
-                    self.ignore_value(tag)?;
+                    self.ignore_value(tag, depth + 1)?;
                 }
             }
             Tag::List => {
+                self.limits.depth(depth + 1)?;
                 let element_tag = self.consume_tag()?;
                 let size = self.consume_list_size()?;
+                self.limits.list(element_tag, size.max(0) as usize)?;
                 for _ in 0..size {
-                    self.ignore_value(element_tag)?;
+                    self.ignore_value(element_tag, depth + 1)?;
                 }
             }
             Tag::End => {
@@ -570,10 +926,23 @@ impl<'de> InputHelper<'de> {
     }
 }
 
-impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+impl<'de, 'a, In: Input<'de>> de::Deserializer<'de> for &'a mut Deserializer<In> {
     type Error = Error;
 
-    forward_to_deserialize_any!(struct map identifier char i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 str string tuple);
+    forward_to_deserialize_any!(struct map identifier char str string tuple);
+
+    deserialize_numbers! {
+        deserialize_i8 => Number::I8,
+        deserialize_i16 => Number::I16,
+        deserialize_i32 => Number::I32,
+        deserialize_i64 => Number::I64,
+        deserialize_u8 => Number::U8,
+        deserialize_u16 => Number::U16,
+        deserialize_u32 => Number::U32,
+        deserialize_u64 => Number::U64,
+        deserialize_f32 => Number::F32,
+        deserialize_f64 => Number::F64,
+    }
 
     fn is_human_readable(&self) -> bool {
         false
@@ -587,17 +956,17 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             None => {
                 // No existing layers. This means we should be at the start of
                 // parsing, and we should be parsing a Compound. We need to get
-                // the tag and the following name and discard it.
-                let tag = self.input.consume_tag()?;
-                if tag != Tag::Compound {
-                    return Err(Error::no_root_compound());
+                // the tag and the following name and discard it, unless a
+                // NamedRoot already has.
+                if !std::mem::take(&mut self.root_header_read) {
+                    self.consume_root_header()?;
                 }
 
-                self.input.consume_name()?;
-
+                self.input.limits.depth(1)?;
                 self.layers.push(Layer::Compound {
                     current_tag: None,
                     stage: Stage::Tag,
+                    current_name: String::new(),
                 });
 
                 return visitor.visit_map(CompoundAccess::new(self));
@@ -608,15 +977,22 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
                     Layer::Compound {
                         ref mut current_tag,
                         ref mut stage,
+                        ref mut current_name,
                     } => match stage {
                         Stage::Tag => {
                             *current_tag = Some(self.input.consume_tag()?);
                             *stage = Stage::Value;
-                            return visit_cow_str(visitor, self.input.consume_name()?);
+                            let name = self.input.consume_name()?;
+                            current_name.clear();
+                            current_name.push_str(&name);
+                            return visit_cow_str(visitor, name);
                         }
                         Stage::Name => {
                             *stage = Stage::Value;
-                            return visit_cow_str(visitor, self.input.consume_name()?);
+                            let name = self.input.consume_name()?;
+                            current_name.clear();
+                            current_name.push_str(&name);
+                            return visit_cow_str(visitor, name);
                         }
                         Stage::Value => {
                             *stage = Stage::Tag;
@@ -625,10 +1001,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
                             current_tag.unwrap()
                         }
                     },
-                    Layer::List {
-                        remaining_elements: _,
-                        element_tag,
-                    } => *element_tag,
+                    Layer::List { element_tag, .. } => *element_tag,
                 }
             }
         };
@@ -651,12 +1024,24 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             )),
         }?;
 
-        match tag {
-            Tag::Byte => visitor.visit_bool(self.input.0.read_i8()? != 0),
-            Tag::Short => visitor.visit_bool(self.input.0.read_i16::<BigEndian>()? != 0),
-            Tag::Int => visitor.visit_bool(self.input.0.read_i32::<BigEndian>()? != 0),
-            Tag::Long => visitor.visit_bool(self.input.0.read_i64::<BigEndian>()? != 0),
-            _ => Err(Error::bespoke(
+        match (tag, self.opts.coercion) {
+            (Tag::Byte, Coercion::Strict) => match self.input.consume_byte()? {
+                0 => visitor.visit_bool(false),
+                1 => visitor.visit_bool(true),
+                b => Err(Error::type_mismatch(format!(
+                    "deserialize bool: expected byte of 0 or 1, found {}",
+                    b
+                ))),
+            },
+            (_, Coercion::Strict) => Err(Error::type_mismatch(format!(
+                "deserialize bool: expected byte, found {}",
+                tag
+            ))),
+            (Tag::Byte, _) => visitor.visit_bool(self.input.consume_byte()? != 0),
+            (Tag::Short, _) => visitor.visit_bool(self.input.consume_short()? != 0),
+            (Tag::Int, _) => visitor.visit_bool(self.input.consume_int()? != 0),
+            (Tag::Long, _) => visitor.visit_bool(self.input.consume_long()? != 0),
+            _ => Err(Error::type_mismatch(
                 "deserialize bool: expected integral value".to_string(),
             )),
         }
@@ -694,7 +1079,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
                 remaining_elements,
                 element_tag,
                 ..
-            } => Err(Error::bespoke(format!(
+            } => Err(Error::type_mismatch(format!(
                 "expected bytes, got [{:?}; {}]",
                 element_tag, remaining_elements
             ))),
@@ -711,23 +1096,13 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
                 let size = self.input.consume_list_size()?;
 
                 match el {
-                    Tag::Byte => {
-                        let bs = self.input.consume_bytes(size)?;
-                        visitor.visit_borrowed_bytes(bs)
-                    }
-                    Tag::Short => {
-                        let bs = self.input.consume_bytes_usize(try_size(size, 2)?)?;
-                        visitor.visit_borrowed_bytes(bs)
-                    }
-                    Tag::Int => {
-                        let bs = self.input.consume_bytes_usize(try_size(size, 4)?)?;
-                        visitor.visit_borrowed_bytes(bs)
-                    }
-                    Tag::Long => {
-                        let bs = self.input.consume_bytes_usize(try_size(size, 8)?)?;
-                        visitor.visit_borrowed_bytes(bs)
+                    Tag::Byte | Tag::Short | Tag::Int | Tag::Long => {
+                        let size = try_size(size, 1)?;
+                        self.input.limits.list(el, size)?;
+                        let bs = self.input.consume_numbers(el, size)?;
+                        visit_cow_bytes(visitor, bs)
                     }
-                    _ => Err(Error::bespoke(format!(
+                    _ => Err(Error::type_mismatch(format!(
                         "expected bytes, got [{:?}; {}]",
                         el, size
                     ))),
@@ -738,26 +1113,29 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
                 ..
             } => match tag {
                 Tag::ByteArray => {
-                    let size = self.input.consume_list_size()?;
-                    let bs = self.input.consume_bytes(size)?;
-                    visitor.visit_borrowed_bytes(bs)
+                    let size = self.input.consume_array_len(Tag::Byte)?;
+                    let bs = self.input.consume_bytes_usize(size)?;
+                    visit_cow_bytes(visitor, bs)
                 }
                 Tag::IntArray => {
-                    let size = self.input.consume_list_size()?;
-                    let bs = self.input.consume_bytes_usize(try_size(size, 4)?)?;
-                    visitor.visit_borrowed_bytes(bs)
+                    let size = self.input.consume_array_len(Tag::Int)?;
+                    let bs = self.input.consume_numbers(Tag::Int, size)?;
+                    visit_cow_bytes(visitor, bs)
                 }
                 // This allows us to borrow blockstates rather than copy them.
                 Tag::LongArray => {
-                    let size = self.input.consume_list_size()?;
-                    let bs = self.input.consume_bytes_usize(try_size(size, 8)?)?;
-                    visitor.visit_borrowed_bytes(bs)
+                    let size = self.input.consume_array_len(Tag::Long)?;
+                    let bs = self.input.consume_numbers(Tag::Long, size)?;
+                    visit_cow_bytes(visitor, bs)
                 }
                 Tag::String => {
                     let s = self.input.consume_size_prefixed_bytes()?;
-                    visitor.visit_borrowed_bytes(s)
+                    visit_cow_bytes(visitor, s)
                 }
-                _ => Err(Error::bespoke(format!("expected bytes, found {:?}", tag))),
+                _ => Err(Error::type_mismatch(format!(
+                    "expected bytes, found {:?}",
+                    tag
+                ))),
             },
         }
     }
@@ -793,7 +1171,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             )),
         }?;
 
-        self.input.ignore_value(*tag)?;
+        self.input.ignore_value(*tag, self.layers.len())?;
         visitor.visit_unit()
     }
 
@@ -814,6 +1192,19 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             crate::BYTE_ARRAY_TOKEN => Tag::ByteArray,
             crate::INT_ARRAY_TOKEN => Tag::IntArray,
             crate::LONG_ARRAY_TOKEN => Tag::LongArray,
+            crate::value::EMPTY_LIST_TOKEN => {
+                // Value asks for the element tag of empty lists with this token.
+                let data_tag = match self.layers.last() {
+                    Some(Layer::Compound { current_tag, .. }) => *current_tag,
+                    Some(Layer::List { element_tag, .. }) => Some(*element_tag),
+                    None => None,
+                };
+                if data_tag == Some(Tag::List) {
+                    self.last_hint = Some(crate::value::EMPTY_LIST_TOKEN);
+                    return consume_value(self, visitor, Tag::List);
+                }
+                return self.deserialize_any(visitor);
+            }
             _ => return visitor.visit_newtype_struct(self),
         };
 
@@ -830,7 +1221,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         if target_tag == data_tag {
             consume_value(self, visitor, target_tag)
         } else {
-            Err(Error::bespoke(format!(
+            Err(Error::type_mismatch(format!(
                 "expected {data_tag}, found {target_tag}"
             )))
         }
@@ -839,13 +1230,23 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     #[inline]
     fn deserialize_tuple_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _len: usize,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == NAMED_ROOT_TOKEN && self.layers.is_empty() {
+            let name = self.consume_root_header()?;
+            self.root_header_read = true;
+            return visitor.visit_seq(NamedRootAccess {
+                de: self,
+                name: Some(name),
+                value_read: false,
+            });
+        }
+
         self.deserialize_seq(visitor)
     }
 
@@ -879,17 +1280,12 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             Layer::Compound {
                 current_tag: Some(tag),
                 stage: Stage::Value,
+                ..
             } => {
-                self.input.ignore_value(*tag)?;
+                self.input.ignore_value(*tag, self.layers.len())?;
             }
-            Layer::Compound {
-                current_tag: _,
-                stage: _,
-            } => unreachable!(), // ???
-            Layer::List {
-                remaining_elements: _,
-                element_tag: _,
-            } => {
+            Layer::Compound { .. } => unreachable!(), // ???
+            Layer::List { .. } => {
                 unreachable!();
             }
         }
@@ -911,23 +1307,46 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 fn try_size(size: i32, multiplier: usize) -> Result<usize> {
     let size: usize = size
         .try_into()
-        .map_err(|_| Error::bespoke("size was negative".to_string()))?;
+        .map_err(|_| Error::size("size was negative".to_string()))?;
 
     size.checked_mul(multiplier)
-        .ok_or_else(|| Error::bespoke("size too large".to_string()))
+        .ok_or_else(|| Error::size("size too large".to_string()))
 }
 
-struct CompoundAccess<'a, 'de> {
-    de: &'a mut Deserializer<'de>,
+/// Get the width of the integral tag `element`, and the size in bytes of
+/// `count` of them.
+fn numbers_size(element: Tag, count: usize) -> Result<(usize, usize)> {
+    let width = match element {
+        Tag::Byte => 1,
+        Tag::Short => 2,
+        Tag::Int => 4,
+        Tag::Long => 8,
+        _ => {
+            return Err(Error::bespoke(format!(
+                "expected integral type, found {element}"
+            )))
+        }
+    };
+
+    let size = count
+        .checked_mul(width)
+        .ok_or_else(|| Error::size("size too large".to_string()))?;
+    Ok((width, size))
 }
 
-impl<'a, 'de> CompoundAccess<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
-        Self { de }
+struct CompoundAccess<'a, In> {
+    de: &'a mut Deserializer<In>,
+    depth: usize,
+}
+
+impl<'a, In> CompoundAccess<'a, In> {
+    fn new(de: &'a mut Deserializer<In>) -> Self {
+        let depth = de.layers.len();
+        Self { de, depth }
     }
 }
 
-impl<'a, 'de> de::MapAccess<'de> for CompoundAccess<'a, 'de> {
+impl<'a, 'de, In: Input<'de>> de::MapAccess<'de> for CompoundAccess<'a, In> {
     type Error = Error;
 
     #[inline]
@@ -935,6 +1354,23 @@ impl<'a, 'de> de::MapAccess<'de> for CompoundAccess<'a, 'de> {
     where
         K: serde::de::DeserializeSeed<'de>,
     {
+        // A visitor can stop early on a list, eg for tuples, leaving its layer
+        // behind. Make sure our compound is the current layer.
+        self.de.layers.truncate(self.depth);
+
+        // Set the current layers next expected type.
+        let (current_tag, stage, current_name) = match self.de.layers.last_mut() {
+            Some(Layer::Compound {
+                current_tag,
+                stage,
+                current_name,
+            }) => (current_tag, stage, current_name),
+            _ => return Err(Error::bespoke("expected to be in compound".to_owned())),
+        };
+
+        // The previous key no longer applies.
+        current_name.clear();
+
         // Need to read the tag of the key.
         let tag = self.de.input.consume_tag()?;
 
@@ -943,13 +1379,8 @@ impl<'a, 'de> de::MapAccess<'de> for CompoundAccess<'a, 'de> {
             return Ok(None);
         }
 
-        // Set the current layers next expected type.
-        // TODO: Can probably do this by mutating top layer rather than pop/push.
-        self.de.layers.pop().unwrap();
-        self.de.layers.push(Layer::Compound {
-            current_tag: Some(tag),
-            stage: Stage::Name,
-        });
+        *current_tag = Some(tag);
+        *stage = Stage::Name;
 
         // Should just be ready to read the name.
         seed.deserialize(&mut *self.de).map(Some)
@@ -964,18 +1395,18 @@ impl<'a, 'de> de::MapAccess<'de> for CompoundAccess<'a, 'de> {
     }
 }
 
-struct ListAccess<'a, 'de> {
-    de: &'a mut Deserializer<'de>,
+struct ListAccess<'a, In> {
+    de: &'a mut Deserializer<In>,
     hint: i32,
 }
 
-impl<'a, 'de> ListAccess<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>, hint: i32) -> Self {
+impl<'a, In> ListAccess<'a, In> {
+    fn new(de: &'a mut Deserializer<In>, hint: i32) -> Self {
         Self { de, hint }
     }
 }
 
-impl<'a, 'de> de::SeqAccess<'de> for ListAccess<'a, 'de> {
+impl<'a, 'de, In: Input<'de>> de::SeqAccess<'de> for ListAccess<'a, In> {
     type Error = Error;
 
     fn size_hint(&self) -> Option<usize> {
@@ -995,8 +1426,7 @@ impl<'a, 'de> de::SeqAccess<'de> for ListAccess<'a, 'de> {
 
         match layer {
             Layer::List {
-                remaining_elements,
-                element_tag: _,
+                remaining_elements, ..
             } => {
                 if *remaining_elements > 0 {
                     *remaining_elements -= 1;
@@ -1007,10 +1437,7 @@ impl<'a, 'de> de::SeqAccess<'de> for ListAccess<'a, 'de> {
                     Ok(None)
                 }
             }
-            Layer::Compound {
-                current_tag,
-                stage: _,
-            } => Err(Error::bespoke(format!(
+            Layer::Compound { current_tag, .. } => Err(Error::bespoke(format!(
                 "expected to be in list, but was in compound {:?}",
                 current_tag
             ))),
@@ -1018,11 +1445,37 @@ impl<'a, 'de> de::SeqAccess<'de> for ListAccess<'a, 'de> {
     }
 }
 
-struct UnitVariantAccess<'a, 'de> {
-    de: &'a mut Deserializer<'de>,
+struct NamedRootAccess<'a, 'de, In> {
+    de: &'a mut Deserializer<In>,
+    name: Option<Cow<'de, str>>,
+    value_read: bool,
+}
+
+impl<'a, 'de, In: Input<'de>> de::SeqAccess<'de> for NamedRootAccess<'a, 'de, In> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        if let Some(name) = self.name.take() {
+            return seed.deserialize(CowStrDeserializer::new(name)).map(Some);
+        }
+
+        if self.value_read {
+            return Ok(None);
+        }
+
+        self.value_read = true;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+struct UnitVariantAccess<'a, In> {
+    de: &'a mut Deserializer<In>,
 }
 
-impl<'a, 'de> de::EnumAccess<'de> for UnitVariantAccess<'a, 'de> {
+impl<'a, 'de, In: Input<'de>> de::EnumAccess<'de> for UnitVariantAccess<'a, In> {
     type Error = Error;
     type Variant = Self;
 
@@ -1035,7 +1488,7 @@ impl<'a, 'de> de::EnumAccess<'de> for UnitVariantAccess<'a, 'de> {
     }
 }
 
-impl<'a, 'de> de::VariantAccess<'de> for UnitVariantAccess<'a, 'de> {
+impl<'a, 'de, In: Input<'de>> de::VariantAccess<'de> for UnitVariantAccess<'a, In> {
     type Error = Error;
 
     fn unit_variant(self) -> Result<()> {
@@ -1,9 +1,13 @@
+use std::borrow::Cow;
+
 use serde::de;
 use serde::de::value::BorrowedBytesDeserializer;
 use serde::de::value::BorrowedStrDeserializer;
+use serde::de::value::BytesDeserializer;
 
-use crate::de::Deserializer;
+use crate::de::{Deserializer, Input};
 use crate::error::{Error, Result};
+use crate::Tag;
 use crate::BYTE_ARRAY_TOKEN;
 use crate::INT_ARRAY_TOKEN;
 use crate::LONG_ARRAY_TOKEN;
@@ -13,49 +17,47 @@ enum State {
     Read,
 }
 
-pub(crate) struct ArrayWrapperAccess<'a, 'de> {
-    de: &'a mut Deserializer<'de>,
+pub(crate) struct ArrayWrapperAccess<'a, In> {
+    de: &'a mut Deserializer<In>,
     token: &'static str,
-    bytes_size: usize,
+    element: Tag,
+    size: usize,
     state: State,
 }
 
-impl<'a, 'de> ArrayWrapperAccess<'a, 'de> {
-    pub(crate) fn bytes(de: &'a mut Deserializer<'de>, size: usize) -> Result<Self> {
-        Ok(Self {
+impl<'a, In> ArrayWrapperAccess<'a, In> {
+    pub(crate) fn bytes(de: &'a mut Deserializer<In>, size: usize) -> Self {
+        Self {
             de,
-            bytes_size: size
-                .checked_mul(1)
-                .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            size,
+            element: Tag::Byte,
             token: BYTE_ARRAY_TOKEN,
             state: State::Unread,
-        })
+        }
     }
 
-    pub(crate) fn ints(de: &'a mut Deserializer<'de>, size: usize) -> Result<Self> {
-        Ok(Self {
+    pub(crate) fn ints(de: &'a mut Deserializer<In>, size: usize) -> Self {
+        Self {
             de,
-            bytes_size: size
-                .checked_mul(4)
-                .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            size,
+            element: Tag::Int,
             token: INT_ARRAY_TOKEN,
             state: State::Unread,
-        })
+        }
     }
 
-    pub(crate) fn longs(de: &'a mut Deserializer<'de>, size: usize) -> Result<Self> {
-        Ok(Self {
+    pub(crate) fn longs(de: &'a mut Deserializer<In>, size: usize) -> Self {
+        Self {
             de,
-            bytes_size: size
-                .checked_mul(8)
-                .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            size,
+            element: Tag::Long,
             token: LONG_ARRAY_TOKEN,
             state: State::Unread,
-        })
+        }
     }
 }
 
-impl<'a, 'de> de::MapAccess<'de> for ArrayWrapperAccess<'a, 'de> {
+impl<'a, 'de, In: Input<'de>> de::MapAccess<'de> for ArrayWrapperAccess<'a, In> {
     type Error = Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
@@ -75,8 +77,10 @@ impl<'a, 'de> de::MapAccess<'de> for ArrayWrapperAccess<'a, 'de> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        let data = self.de.input.consume_bytes_usize(self.bytes_size)?;
-        let dz = BorrowedBytesDeserializer::new(data);
-        seed.deserialize(dz)
+        // Data can only be borrowed if it's already big endian.
+        match self.de.input.consume_numbers(self.element, self.size)? {
+            Cow::Borrowed(data) => seed.deserialize(BorrowedBytesDeserializer::new(data)),
+            Cow::Owned(data) => seed.deserialize(BytesDeserializer::new(&data)),
+        }
     }
 }
@@ -0,0 +1,434 @@
+//! Compare two [`Value`]s with [`diff`], and apply the differences to a value
+//! with [`patch`].
+//!
+//! Each [`Change`] is addressed by a path in the same format as
+//! [`Value::pointer`], eg `/Inventory/3/Count`. This differs from the
+//! `Inventory[3].Count` paths used to describe where an error happened:
+//! those are only for people to read, while these are applied by
+//! [`patch`], so they need to stay unambiguous even for keys that contain
+//! `.` or `[`.
+//!
+//! ```rust
+//! use fastnbt::{diff::Change, nbt};
+//!
+//! let before = nbt!({"Inventory": [{"id": "minecraft:stone", "Count": 3b}], "XpLevel": 10});
+//! let after = nbt!({"Inventory": [{"id": "minecraft:stone", "Count": 1b}]});
+//!
+//! let changes = fastnbt::diff(&before, &after);
+//! assert_eq!(
+//!     changes,
+//!     [
+//!         Change::ValueChanged {
+//!             path: "/Inventory/0/Count".to_owned(),
+//!             from: nbt!(3b),
+//!             to: nbt!(1b),
+//!         },
+//!         Change::Removed {
+//!             path: "/XpLevel".to_owned(),
+//!             value: nbt!(10),
+//!         },
+//!     ]
+//! );
+//!
+//! let mut patched = before.clone();
+//! fastnbt::patch(&mut patched, &changes).unwrap();
+//! assert_eq!(patched, after);
+//! ```
+
+use std::fmt;
+
+use crate::{
+    error::{Error, Result},
+    value::escape_pointer_part,
+    ByteArray, IntArray, LongArray, Tag, Value,
+};
+
+/// One difference between two values. See [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A compound entry or list element that is only in the new value.
+    Added { path: String, value: Value },
+
+    /// A compound entry or list element that is only in the old value.
+    Removed { path: String, value: Value },
+
+    /// A value whose type is different, so it is replaced as a whole. Lists
+    /// whose elements are of a different type count as a different type.
+    TypeChanged {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+
+    /// A number or string that is different.
+    ValueChanged {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+
+    /// Elements of an NBT array that are different. `from` holds the old
+    /// elements starting at `start`, and `to` holds the new elements that
+    /// replace them, which might be a different number of elements. Both are
+    /// arrays of the same type as the array at `path`.
+    ArrayRange {
+        path: String,
+        start: usize,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl Change {
+    /// The path of the changed value, in the format of [`Value::pointer`].
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::TypeChanged { path, .. }
+            | Change::ValueChanged { path, .. }
+            | Change::ArrayRange { path, .. } => path,
+        }
+    }
+}
+
+/// One change per line, eg `~ /Inventory/0/Count: 3b -> 1b`, with values as
+/// SNBT. Added values start with `+` and removed values with `-`.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self.path() {
+            "" => "(root)",
+            path => path,
+        };
+        match self {
+            Change::Added { value, .. } => write!(f, "+ {path}: {value}"),
+            Change::Removed { value, .. } => write!(f, "- {path}: {value}"),
+            Change::TypeChanged { from, to, .. } => write!(
+                f,
+                "~ {path}: {from} -> {to} ({} -> {})",
+                describe(from),
+                describe(to)
+            ),
+            Change::ValueChanged { from, to, .. } => write!(f, "~ {path}: {from} -> {to}"),
+            Change::ArrayRange {
+                start, from, to, ..
+            } => {
+                let end = start + array_len(from);
+                write!(f, "~ {path}/{start}..{end}: {from} -> {to}")
+            }
+        }
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::List(Tag::End, _) => "list".to_owned(),
+        Value::List(tag, _) => format!("list of {tag}"),
+        value => value.tag().to_string(),
+    }
+}
+
+/// Find the differences between `a` and `b`, in an order that [`patch`] can
+/// apply to `a` to get `b`.
+///
+/// Compounds are compared entry by entry, in order of their keys. Lists are
+/// compared element by element, so an element removed from the middle of a
+/// list shows up as changes to every element after it. NBT arrays of the same
+/// length give a change for each run of different elements, and arrays of
+/// different lengths give a single change covering everything between their
+/// common start and end.
+pub fn diff(a: &Value, b: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_into(&mut changes, String::new(), a, b);
+    changes
+}
+
+fn diff_into(changes: &mut Vec<Change>, path: String, a: &Value, b: &Value) {
+    match (a, b) {
+        (Value::Compound(a), Value::Compound(b)) => {
+            let mut keys: Vec<&String> = a
+                .keys()
+                .chain(b.keys().filter(|k| !a.contains_key(*k)))
+                .collect();
+            keys.sort();
+            for key in keys {
+                let path = format!("{}/{}", path, escape_pointer_part(key));
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => diff_into(changes, path, a, b),
+                    (Some(a), None) => changes.push(Change::Removed {
+                        path,
+                        value: a.clone(),
+                    }),
+                    (None, Some(b)) => changes.push(Change::Added {
+                        path,
+                        value: b.clone(),
+                    }),
+                    (None, None) => unreachable!("key came from one of the compounds"),
+                }
+            }
+        }
+        // Empty lists read from NBT usually have the End tag, so changing
+        // that to any other tag while still empty is not a change.
+        (Value::List(a_tag, a), Value::List(b_tag, b))
+            if a_tag == b_tag
+                || (a.is_empty() && b.is_empty() && (*a_tag == Tag::End || *b_tag == Tag::End)) =>
+        {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                diff_into(changes, format!("{path}/{i}"), a, b);
+            }
+            // Removed from the end first, so each index is still valid when
+            // patching.
+            for (i, a) in a.iter().enumerate().skip(b.len()).rev() {
+                changes.push(Change::Removed {
+                    path: format!("{path}/{i}"),
+                    value: a.clone(),
+                });
+            }
+            for (i, b) in b.iter().enumerate().skip(a.len()) {
+                changes.push(Change::Added {
+                    path: format!("{path}/{i}"),
+                    value: b.clone(),
+                });
+            }
+        }
+        (Value::ByteArray(a), Value::ByteArray(b)) => diff_array(changes, &path, a, b, |v| {
+            Value::ByteArray(ByteArray::new(v))
+        }),
+        (Value::IntArray(a), Value::IntArray(b)) => {
+            diff_array(changes, &path, a, b, |v| Value::IntArray(IntArray::new(v)))
+        }
+        (Value::LongArray(a), Value::LongArray(b)) => diff_array(changes, &path, a, b, |v| {
+            Value::LongArray(LongArray::new(v))
+        }),
+        (a, b) if same(a, b) => {}
+        (a, b) if a.tag() == b.tag() && !matches!(a, Value::List(..)) => {
+            changes.push(Change::ValueChanged {
+                path,
+                from: a.clone(),
+                to: b.clone(),
+            })
+        }
+        (a, b) => changes.push(Change::TypeChanged {
+            path,
+            from: a.clone(),
+            to: b.clone(),
+        }),
+    }
+}
+
+/// Whether two values are the same. Unlike `==`, floats and doubles are
+/// compared by their bits, so that NaN is the same as itself.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (Value::Double(a), Value::Double(b)) => a.to_bits() == b.to_bits(),
+        (Value::List(a_tag, a), Value::List(b_tag, b)) => {
+            a_tag == b_tag && a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        }
+        (Value::Compound(a), Value::Compound(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn diff_array<T: PartialEq + Clone>(
+    changes: &mut Vec<Change>,
+    path: &str,
+    a: &[T],
+    b: &[T],
+    array: impl Fn(Vec<T>) -> Value,
+) {
+    let mut range = |start: usize, from: &[T], to: &[T]| {
+        changes.push(Change::ArrayRange {
+            path: path.to_owned(),
+            start,
+            from: array(from.to_vec()),
+            to: array(to.to_vec()),
+        })
+    };
+
+    if a.len() == b.len() {
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] == b[i] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < a.len() && a[i] != b[i] {
+                i += 1;
+            }
+            range(start, &a[start..i], &b[start..i]);
+        }
+        return;
+    }
+
+    let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    range(
+        prefix,
+        &a[prefix..a.len() - suffix],
+        &b[prefix..b.len() - suffix],
+    );
+}
+
+/// Apply changes from [`diff`] to `value`. This can be used to merge the
+/// changes between two snapshots into a third value.
+///
+/// Each change is checked against `value` before it is applied: removed and
+/// replaced values must match what is there, and added values must not
+/// already exist. If any change does not apply, an error is returned and
+/// `value` is left as it was.
+///
+/// ```rust
+/// # use fastnbt::nbt;
+/// let before = nbt!({"Health": 20.0f, "Pos": [I; 0, 64, 0]});
+/// let after = nbt!({"Health": 15.0f, "Pos": [I; 0, 64, 0]});
+/// let changes = fastnbt::diff(&before, &after);
+///
+/// // Apply the same changes to a later snapshot.
+/// let mut later = nbt!({"Health": 20.0f, "Pos": [I; 10, 70, 10]});
+/// fastnbt::patch(&mut later, &changes).unwrap();
+/// assert_eq!(later, nbt!({"Health": 15.0f, "Pos": [I; 10, 70, 10]}));
+///
+/// // But not to a value they don't match.
+/// let mut other = nbt!({"Health": 5.0f});
+/// assert!(fastnbt::patch(&mut other, &changes).is_err());
+/// assert_eq!(other, nbt!({"Health": 5.0f}));
+/// ```
+#[doc(alias = "merge")]
+pub fn patch(value: &mut Value, changes: &[Change]) -> Result<()> {
+    let mut patched = value.clone();
+    for change in changes {
+        apply(&mut patched, change).map_err(|msg| {
+            Error::bespoke(format!(
+                "cannot apply change to {}: {}",
+                match change.path() {
+                    "" => "root",
+                    path => path,
+                },
+                msg
+            ))
+        })?;
+    }
+    *value = patched;
+    Ok(())
+}
+
+fn apply(root: &mut Value, change: &Change) -> std::result::Result<(), String> {
+    match change {
+        Change::Added { path, value } => {
+            let (parent, key) = split(path)?;
+            match root.pointer_mut(parent) {
+                Some(Value::Compound(map)) => {
+                    if map.contains_key(&key) {
+                        return Err("value already exists".to_owned());
+                    }
+                    map.insert(key, value.clone());
+                }
+                Some(Value::List(tag, list)) => {
+                    let index = list_index(&key, list.len() + 1)?;
+                    if list.is_empty() {
+                        *tag = value.tag();
+                    } else if value.tag() != *tag {
+                        return Err(format!("cannot add {} to list of {}", value.tag(), tag));
+                    }
+                    list.insert(index, value.clone());
+                }
+                _ => return Err("no compound or list to add to".to_owned()),
+            }
+        }
+        Change::Removed { path, value } => {
+            let (parent, key) = split(path)?;
+            if !root
+                .pointer(path)
+                .is_some_and(|current| same(current, value))
+            {
+                return Err("value to remove is different or missing".to_owned());
+            }
+            match root.pointer_mut(parent) {
+                Some(Value::List(_, list)) => {
+                    list.remove(list_index(&key, list.len())?);
+                }
+                Some(parent) => {
+                    parent.remove(&key);
+                }
+                None => unreachable!("parent exists as the value does"),
+            }
+        }
+        Change::TypeChanged { path, from, to } | Change::ValueChanged { path, from, to } => {
+            match root.pointer_mut(path) {
+                Some(current) if same(current, from) => *current = to.clone(),
+                _ => return Err("value to replace is different or missing".to_owned()),
+            }
+        }
+        Change::ArrayRange {
+            path,
+            start,
+            from,
+            to,
+        } => match (root.pointer_mut(path), from, to) {
+            (Some(Value::ByteArray(a)), Value::ByteArray(from), Value::ByteArray(to)) => {
+                *a = ByteArray::new(splice(a, *start, from, to)?)
+            }
+            (Some(Value::IntArray(a)), Value::IntArray(from), Value::IntArray(to)) => {
+                *a = IntArray::new(splice(a, *start, from, to)?)
+            }
+            (Some(Value::LongArray(a)), Value::LongArray(from), Value::LongArray(to)) => {
+                *a = LongArray::new(splice(a, *start, from, to)?)
+            }
+            _ => return Err("no array of the right type".to_owned()),
+        },
+    }
+    Ok(())
+}
+
+fn splice<T: PartialEq + Clone>(
+    array: &[T],
+    start: usize,
+    from: &[T],
+    to: &[T],
+) -> std::result::Result<Vec<T>, String> {
+    let end = start + from.len();
+    if array.get(start..end) != Some(from) {
+        return Err("array elements to replace are different or missing".to_owned());
+    }
+    let mut spliced = array.to_vec();
+    spliced.splice(start..end, to.iter().cloned());
+    Ok(spliced)
+}
+
+/// Split a path into the path of its parent and the unescaped last part.
+fn split(path: &str) -> std::result::Result<(&str, String), String> {
+    match path.rfind('/') {
+        Some(i) => Ok((
+            &path[..i],
+            path[i + 1..].replace("~1", "/").replace("~0", "~"),
+        )),
+        None => Err("the root cannot be added or removed".to_owned()),
+    }
+}
+
+fn list_index(key: &str, len: usize) -> std::result::Result<usize, String> {
+    match key.parse() {
+        Ok(index) if index < len => Ok(index),
+        _ => Err(format!("invalid list index {key}")),
+    }
+}
+
+fn array_len(array: &Value) -> usize {
+    match array {
+        Value::ByteArray(a) => a.len(),
+        Value::IntArray(a) => a.len(),
+        Value::LongArray(a) => a.len(),
+        _ => 0,
+    }
+}
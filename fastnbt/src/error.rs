@@ -2,8 +2,44 @@
 use std::fmt::Display;
 
 /// Various errors that can occur during deserialization.
+///
+/// Errors from deserializing NBT data record where in the data the error
+/// happened. See [`Error::offset`] and [`Error::path`].
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Error(String);
+pub struct Error {
+    msg: String,
+    kind: ErrorKind,
+    offset: Option<u64>,
+    path: Option<String>,
+}
+
+/// The kind of an [`Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A tag was not a valid NBT tag.
+    InvalidTag,
+
+    /// The input ended part way through the NBT.
+    UnexpectedEof,
+
+    /// A size or length in the input was invalid, eg negative or too large.
+    InvalidSize,
+
+    /// A string was not valid for the encoding expected.
+    Nonunicode,
+
+    /// The NBT did not have the type required by what was being deserialized.
+    TypeMismatch,
+
+    /// An IO error other than unexpected EOF.
+    Io,
+
+    /// Any other error, including custom errors from serde implementations.
+    /// Users should not match on this variant and should instead use a
+    /// wildcard `_`. Errors in this category may be moved to new variants.
+    Custom,
+}
 
 /// Convenience type for Result.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -12,13 +48,24 @@ impl std::error::Error for Error {}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        f.write_str(&self.msg)?;
+
+        match (self.path(), self.offset) {
+            (Some(path), Some(offset)) => write!(f, " (at {}, byte offset {})", path, offset),
+            (Some(path), None) => write!(f, " (at {})", path),
+            (None, Some(offset)) => write!(f, " (at byte offset {})", offset),
+            (None, None) => Ok(()),
+        }
     }
 }
 
 impl serde::de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
-        Error(msg.to_string())
+        Error::new(ErrorKind::Custom, msg.to_string())
+    }
+
+    fn invalid_type(unexp: serde::de::Unexpected, exp: &dyn serde::de::Expected) -> Self {
+        Error::type_mismatch(format!("invalid type: {}, expected {}", unexp, exp))
     }
 }
 
@@ -28,41 +75,111 @@ impl serde::ser::Error for Error {
     where
         T: Display,
     {
-        Error(msg.to_string())
+        Error::new(ErrorKind::Custom, msg.to_string())
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
-        Error(format!("io error: {}", e))
+        match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::unexpected_eof(),
+            _ => Error::new(ErrorKind::Io, format!("io error: {}", e)),
+        }
     }
 }
 
 impl Error {
+    /// Get the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The offset in bytes into the input where the error occurred, if known.
+    /// For SNBT this is the offset into the string.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// The path of compound keys and list indices that had been reached in
+    /// the NBT when the error occurred, eg `sections[3].block_states`. This is
+    /// `None` if the error happened at the root compound or the path is not
+    /// known.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref().filter(|p| !p.is_empty())
+    }
+
+    /// Record where in the input the error occurred. Locations already
+    /// recorded are kept, as they are closer to the source of the error.
+    pub(crate) fn at(mut self, offset: u64, path: Option<String>) -> Error {
+        self.offset = self.offset.or(Some(offset));
+        self.path = self.path.or(path);
+        self
+    }
+
+    /// Record the path where the error occurred, for errors that are not
+    /// about some input bytes.
+    #[cfg(feature = "json")]
+    pub(crate) fn at_path(mut self, path: String) -> Error {
+        self.path = self.path.or(Some(path));
+        self
+    }
+
+    fn new(kind: ErrorKind, msg: String) -> Error {
+        Error {
+            msg,
+            kind,
+            offset: None,
+            path: None,
+        }
+    }
+
     pub(crate) fn invalid_tag(tag: u8) -> Error {
-        Error(format!("invalid nbt tag value: {}", tag))
+        Error::new(
+            ErrorKind::InvalidTag,
+            format!("invalid nbt tag value: {}", tag),
+        )
     }
 
     pub(crate) fn invalid_size(size: i32) -> Error {
-        Error(format!("invalid nbt list/array size: {}", size))
+        Error::new(
+            ErrorKind::InvalidSize,
+            format!("invalid nbt list/array size: {}", size),
+        )
+    }
+
+    pub(crate) fn size(msg: String) -> Error {
+        Error::new(ErrorKind::InvalidSize, msg)
     }
 
     pub(crate) fn no_root_compound() -> Error {
-        Error("invalid nbt: no root compound".to_owned())
+        Error::new(
+            ErrorKind::TypeMismatch,
+            "invalid nbt: no root compound".to_owned(),
+        )
     }
 
     pub(crate) fn nonunicode_string(data: &[u8]) -> Error {
-        Error(format!(
-            "invalid nbt string: nonunicode: {}",
-            String::from_utf8_lossy(data)
-        ))
+        Error::new(
+            ErrorKind::Nonunicode,
+            format!(
+                "invalid nbt string: nonunicode: {}",
+                String::from_utf8_lossy(data)
+            ),
+        )
     }
 
     pub(crate) fn unexpected_eof() -> Error {
-        Error("eof: unexpectedly ran out of input".to_owned())
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            "eof: unexpectedly ran out of input".to_owned(),
+        )
+    }
+
+    pub(crate) fn type_mismatch(msg: String) -> Error {
+        Error::new(ErrorKind::TypeMismatch, msg)
     }
 
     pub(crate) fn bespoke(msg: String) -> Error {
-        Error(msg)
+        Error::new(ErrorKind::Custom, msg)
     }
 }
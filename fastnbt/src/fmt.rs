@@ -0,0 +1,563 @@
+//! Pretty printing of NBT for people to read, either as an indented tree or
+//! as [SNBT](crate::snbt).
+//!
+//! Both [`Value`]s and raw NBT bytes can be printed. Raw bytes are read with
+//! the [`stream::Parser`], so nothing is built up in memory and even large or
+//! truncated files print as far as they can be read.
+//!
+//! ```rust
+//! use fastnbt::{fmt::{self, FmtOpts, Style}, nbt};
+//!
+//! let value = nbt!({
+//!     "Data": {
+//!         "SpawnX": 10,
+//!         "Name": "Steve",
+//!         "Heightmap": [L; 1, 2, 3],
+//!         "Items": [{"id": "minecraft:stone", "Count": 1b}],
+//!     },
+//! });
+//!
+//! let opts = FmtOpts::new()
+//!     .style(Style::Tree)
+//!     .indent(2)
+//!     .types(false)
+//!     .truncate_arrays(2);
+//! let text = fmt::to_string(&value, opts);
+//! println!("{}", text);
+//! # assert!(text.starts_with("Data:\n"));
+//! # assert!(text.contains("\n  Heightmap: [L; 3 longs]"));
+//! # assert!(text.contains("\n  Items: 1 entry\n    [0]:\n"));
+//! ```
+//!
+//! prints something like
+//!
+//! ```text
+//! Data:
+//!   SpawnX: 10
+//!   Name: "Steve"
+//!   Heightmap: [L; 3 longs]
+//!   Items: 1 entry
+//!     [0]:
+//!       id: "minecraft:stone"
+//!       Count: 1
+//! ```
+//!
+//! [`Value`] also implements `Display` using the SNBT style. `{}` gives
+//! compact SNBT on one line, and `{:#}` gives indented SNBT.
+//!
+//! ```rust
+//! # use fastnbt::nbt;
+//! let value = nbt!({"Pos": [1.5, 2.0]});
+//! assert_eq!(value.to_string(), "{Pos:[1.5d,2.0d]}");
+//! assert_eq!(format!("{:#}", value), "{\n    Pos: [1.5d, 2.0d]\n}");
+//! ```
+
+use std::fmt::{self, Write as _};
+use std::io::{self, Read};
+
+use crate::snbt::{quote, quote_key_if_needed};
+use crate::stream::{self, Parser};
+use crate::{Tag, Value};
+
+/// How values are laid out. See the [module][`crate::fmt`] documentation for
+/// examples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// One value per line, with the contents of compounds and lists indented
+    /// below them.
+    Tree,
+
+    /// SNBT, as used in commands. This can be read back with
+    /// [`snbt::from_str`][`crate::snbt::from_str`] as long as no arrays were
+    /// truncated and types were included. SNBT has no way to write NaN or
+    /// infinite floats, so they are printed like `NaNf` and read back as
+    /// strings. Use [`snbt::to_string`][`crate::snbt::to_string`] to get an
+    /// error for them instead.
+    Snbt,
+}
+
+/// Options for customizing pretty printing.
+#[derive(Debug, Clone)]
+pub struct FmtOpts {
+    /// The layout to use.
+    style: Style,
+
+    /// Number of spaces to indent each level by.
+    indent: usize,
+
+    /// Whether to show the type of each value.
+    types: bool,
+
+    /// Arrays longer than this are summarized rather than printed.
+    truncate_arrays: usize,
+}
+
+impl FmtOpts {
+    /// Create new options. This object follows a builder pattern.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the layout to use. See [`Style`].
+    pub fn style(mut self, value: Style) -> Self {
+        self.style = value;
+        self
+    }
+
+    /// Set the number of spaces to indent each level of nesting by. The
+    /// default is 4. For the SNBT style, 0 prints everything on one line with
+    /// no whitespace at all.
+    pub fn indent(mut self, value: usize) -> Self {
+        self.indent = value;
+        self
+    }
+
+    /// Set whether to show the type of each value, which is on by default.
+    /// The tree style names the type next to each value, eg `SpawnX (int):
+    /// 10`, and the SNBT style uses number suffixes, eg `1b`.
+    pub fn types(mut self, value: bool) -> Self {
+        self.types = value;
+        self
+    }
+
+    /// Summarize byte, int and long arrays with more than `len` elements as
+    /// eg `[L; 4096 longs]`, rather than printing every element. Arrays are
+    /// printed in full by default.
+    pub fn truncate_arrays(mut self, len: usize) -> Self {
+        self.truncate_arrays = len;
+        self
+    }
+}
+
+impl Default for FmtOpts {
+    fn default() -> Self {
+        Self {
+            style: Style::Snbt,
+            indent: 4,
+            types: true,
+            truncate_arrays: usize::MAX,
+        }
+    }
+}
+
+/// Pretty print a [`Value`] to a string.
+pub fn to_string(value: &Value, opts: FmtOpts) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, &opts).expect("writing to a string cannot fail");
+    out
+}
+
+/// Pretty print a [`Value`] to `writer`.
+pub fn to_writer<W: io::Write>(writer: W, value: &Value, opts: FmtOpts) -> io::Result<()> {
+    let mut out = IoWriter::new(writer);
+    write_value(&mut out, value, &opts).map_err(|_| out.error())
+}
+
+/// Pretty print uncompressed NBT bytes to a string.
+///
+/// ```rust
+/// use fastnbt::fmt::{self, FmtOpts};
+///
+/// let bytes = fastnbt::to_bytes(&fastnbt::nbt!({"a": [1, 2]})).unwrap();
+/// let text = fmt::bytes_to_string(&bytes, FmtOpts::new().indent(0)).unwrap();
+/// assert_eq!(text, "{a:[1,2]}");
+/// ```
+pub fn bytes_to_string(input: &[u8], opts: FmtOpts) -> stream::Result<String> {
+    let mut out = String::new();
+    write_stream(&mut out, input, &opts).map_err(|e| match e {
+        StreamError::Parse(e) => e,
+        StreamError::Write => unreachable!("writing to a string cannot fail"),
+    })?;
+    Ok(out)
+}
+
+/// Pretty print the uncompressed NBT read from `reader` to `writer`, one
+/// value at a time. If the NBT turns out to be invalid part way through,
+/// everything before the problem will have been written.
+pub fn reader_to_writer<R: Read, W: io::Write>(
+    reader: R,
+    writer: W,
+    opts: FmtOpts,
+) -> stream::Result<()> {
+    let mut out = IoWriter::new(writer);
+    write_stream(&mut out, reader, &opts).map_err(|e| match e {
+        StreamError::Parse(e) => e,
+        StreamError::Write => out.error().into(),
+    })
+}
+
+/// Used by the `Display` implementation of [`Value`].
+pub(crate) fn display(value: &Value, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let indent = if f.alternate() { 4 } else { 0 };
+    write_value(f, value, &FmtOpts::new().indent(indent))
+}
+
+fn write_value<W: fmt::Write>(out: W, value: &Value, opts: &FmtOpts) -> fmt::Result {
+    let mut printer = Printer::new(out, opts);
+    printer.value(None, value)
+}
+
+enum StreamError {
+    Parse(stream::Error),
+    Write,
+}
+
+fn write_stream<W: fmt::Write, R: Read>(
+    out: W,
+    reader: R,
+    opts: &FmtOpts,
+) -> Result<(), StreamError> {
+    let mut parser = Parser::new(reader);
+    let mut printer = Printer::new(out, opts);
+    loop {
+        match parser.next() {
+            Ok(event) => printer.event(&event).map_err(|_| StreamError::Write)?,
+            Err(e) if e.is_eof() => return Ok(()),
+            Err(e) => return Err(StreamError::Parse(e)),
+        }
+    }
+}
+
+/// Adapts an `io::Write` for the printer, keeping hold of the error that
+/// `fmt::Error` cannot carry.
+struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W> IoWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    fn error(&mut self) -> io::Error {
+        self.error
+            .take()
+            .unwrap_or_else(|| io::Error::other("formatting failed"))
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// A value that is not a list or compound.
+enum Leaf<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(&'a str),
+    ByteArray(&'a [i8]),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+}
+
+impl Leaf<'_> {
+    fn tag(&self) -> Tag {
+        match self {
+            Leaf::Byte(_) => Tag::Byte,
+            Leaf::Short(_) => Tag::Short,
+            Leaf::Int(_) => Tag::Int,
+            Leaf::Long(_) => Tag::Long,
+            Leaf::Float(_) => Tag::Float,
+            Leaf::Double(_) => Tag::Double,
+            Leaf::String(_) => Tag::String,
+            Leaf::ByteArray(_) => Tag::ByteArray,
+            Leaf::IntArray(_) => Tag::IntArray,
+            Leaf::LongArray(_) => Tag::LongArray,
+        }
+    }
+}
+
+/// A compound or list that is being printed.
+struct Layer {
+    list: bool,
+
+    /// Number of values printed inside so far.
+    count: usize,
+
+    /// Whether each value inside goes on its own line, for the SNBT style.
+    multiline: bool,
+
+    /// Indentation level of the values inside.
+    depth: usize,
+}
+
+/// Prints values given to it one at a time, in the same shape as the events
+/// from the [`stream::Parser`].
+struct Printer<'o, W> {
+    out: W,
+    opts: &'o FmtOpts,
+    layers: Vec<Layer>,
+
+    /// Whether anything has been printed yet.
+    started: bool,
+}
+
+impl<'o, W: fmt::Write> Printer<'o, W> {
+    fn new(out: W, opts: &'o FmtOpts) -> Self {
+        Self {
+            out,
+            opts,
+            layers: Vec::new(),
+            started: false,
+        }
+    }
+
+    fn value(&mut self, name: Option<&str>, value: &Value) -> fmt::Result {
+        match value {
+            Value::Compound(map) => {
+                self.open_compound(name)?;
+                for (key, value) in map {
+                    self.value(Some(key), value)?;
+                }
+                self.close('}')
+            }
+            Value::List(tag, values) => {
+                self.open_list(name, *tag, values.len())?;
+                for value in values {
+                    self.value(None, value)?;
+                }
+                self.close(']')
+            }
+            Value::Byte(v) => self.leaf(name, Leaf::Byte(*v)),
+            Value::Short(v) => self.leaf(name, Leaf::Short(*v)),
+            Value::Int(v) => self.leaf(name, Leaf::Int(*v)),
+            Value::Long(v) => self.leaf(name, Leaf::Long(*v)),
+            Value::Float(v) => self.leaf(name, Leaf::Float(*v)),
+            Value::Double(v) => self.leaf(name, Leaf::Double(*v)),
+            Value::String(v) => self.leaf(name, Leaf::String(v)),
+            Value::ByteArray(v) => self.leaf(name, Leaf::ByteArray(v)),
+            Value::IntArray(v) => self.leaf(name, Leaf::IntArray(v)),
+            Value::LongArray(v) => self.leaf(name, Leaf::LongArray(v)),
+        }
+    }
+
+    fn event(&mut self, event: &stream::Value) -> fmt::Result {
+        use stream::Value as V;
+        match event {
+            V::Compound(name) => self.open_compound(name.as_deref()),
+            V::CompoundEnd => self.close('}'),
+            V::List(name, tag, len) => self.open_list(name.as_deref(), *tag, *len as usize),
+            V::ListEnd => self.close(']'),
+            V::Byte(name, v) => self.leaf(name.as_deref(), Leaf::Byte(*v)),
+            V::Short(name, v) => self.leaf(name.as_deref(), Leaf::Short(*v)),
+            V::Int(name, v) => self.leaf(name.as_deref(), Leaf::Int(*v)),
+            V::Long(name, v) => self.leaf(name.as_deref(), Leaf::Long(*v)),
+            V::Float(name, v) => self.leaf(name.as_deref(), Leaf::Float(*v)),
+            V::Double(name, v) => self.leaf(name.as_deref(), Leaf::Double(*v)),
+            V::String(name, v) => self.leaf(name.as_deref(), Leaf::String(v)),
+            V::ByteArray(name, v) => self.leaf(name.as_deref(), Leaf::ByteArray(v)),
+            V::IntArray(name, v) => self.leaf(name.as_deref(), Leaf::IntArray(v)),
+            V::LongArray(name, v) => self.leaf(name.as_deref(), Leaf::LongArray(v)),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.depth)
+    }
+
+    fn newline(&mut self, depth: usize) -> fmt::Result {
+        write!(self.out, "\n{:1$}", "", depth * self.opts.indent)
+    }
+
+    /// Start a new value, writing whatever comes before it: separators,
+    /// indentation and its name. Returns whether a label was written for the
+    /// tree style, which then needs a colon.
+    fn begin(&mut self, name: Option<&str>, tag: &dyn fmt::Display) -> Result<bool, fmt::Error> {
+        let depth = self.depth();
+        let started = std::mem::replace(&mut self.started, true);
+        let (in_list, index) = match self.layers.last_mut() {
+            Some(layer) => {
+                layer.count += 1;
+                (layer.list, Some(layer.count - 1))
+            }
+            None => (false, None),
+        };
+
+        let mut label = match (in_list, index) {
+            (true, Some(index)) => format!("[{index}]"),
+            // Only the root can be unnamed or have an empty name.
+            _ => name
+                .filter(|name| index.is_some() || !name.is_empty())
+                .map(quote_key_if_needed)
+                .unwrap_or_default(),
+        };
+
+        match self.opts.style {
+            Style::Tree => {
+                if started {
+                    self.newline(depth)?;
+                } else {
+                    write!(self.out, "{:1$}", "", depth * self.opts.indent)?;
+                }
+                if self.opts.types {
+                    if !label.is_empty() {
+                        label.push(' ');
+                    }
+                    write!(label, "({tag})")?;
+                }
+                self.out.write_str(&label)?;
+                Ok(!label.is_empty())
+            }
+            Style::Snbt => {
+                let pretty = self.opts.indent > 0;
+                match self.layers.last() {
+                    None if started => self.out.write_char('\n')?,
+                    None => {}
+                    Some(layer) => {
+                        if layer.count > 1 {
+                            self.out.write_char(',')?;
+                        }
+                        if layer.multiline {
+                            self.newline(depth)?;
+                        } else if layer.count > 1 && pretty {
+                            self.out.write_char(' ')?;
+                        }
+                    }
+                }
+                if !in_list && index.is_some() {
+                    write!(self.out, "{}:{}", label, if pretty { " " } else { "" })?;
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn open_compound(&mut self, name: Option<&str>) -> fmt::Result {
+        let depth = self.depth();
+        let multiline = self.opts.indent > 0;
+
+        // The usual unnamed root compound gets no line of its own in a tree,
+        // which would only add a level of indentation to everything.
+        let is_plain_root = self.layers.is_empty() && name.unwrap_or_default().is_empty();
+        if self.opts.style == Style::Tree && is_plain_root {
+            self.layers.push(Layer {
+                list: false,
+                count: 0,
+                multiline,
+                depth,
+            });
+            return Ok(());
+        }
+
+        let labelled = self.begin(name, &Tag::Compound)?;
+        match self.opts.style {
+            Style::Tree if labelled => self.out.write_char(':')?,
+            Style::Tree => {}
+            Style::Snbt => self.out.write_char('{')?,
+        }
+        self.layers.push(Layer {
+            list: false,
+            count: 0,
+            multiline,
+            depth: depth + 1,
+        });
+        Ok(())
+    }
+
+    fn open_list(&mut self, name: Option<&str>, tag: Tag, len: usize) -> fmt::Result {
+        let depth = self.depth();
+        let description = match tag {
+            Tag::End => "list".to_owned(),
+            tag => format!("list of {tag}"),
+        };
+        let labelled = self.begin(name, &description)?;
+        match self.opts.style {
+            Style::Tree => {
+                if labelled {
+                    self.out.write_str(": ")?;
+                }
+                write!(self.out, "{}", plural(len, "entry", "entries"))?;
+            }
+            Style::Snbt => self.out.write_char('[')?,
+        }
+        self.layers.push(Layer {
+            list: true,
+            count: 0,
+            // Lists of numbers and strings read better on one line.
+            multiline: self.opts.indent > 0 && matches!(tag, Tag::Compound | Tag::List),
+            depth: depth + 1,
+        });
+        Ok(())
+    }
+
+    fn close(&mut self, bracket: char) -> fmt::Result {
+        let layer = self.layers.pop().expect("close without open");
+        if self.opts.style == Style::Snbt {
+            if layer.multiline && layer.count > 0 {
+                self.newline(layer.depth - 1)?;
+            }
+            self.out.write_char(bracket)?;
+        }
+        Ok(())
+    }
+
+    fn leaf(&mut self, name: Option<&str>, leaf: Leaf) -> fmt::Result {
+        let tag = leaf.tag();
+        if self.begin(name, &tag)? {
+            self.out.write_str(": ")?;
+        }
+
+        let suffixes = self.opts.style == Style::Snbt && self.opts.types;
+        let suffix = |s: &'static str| if suffixes { s } else { "" };
+        match leaf {
+            Leaf::Byte(v) => write!(self.out, "{v}{}", suffix("b")),
+            Leaf::Short(v) => write!(self.out, "{v}{}", suffix("s")),
+            Leaf::Int(v) => write!(self.out, "{v}"),
+            Leaf::Long(v) => write!(self.out, "{v}{}", suffix("L")),
+            Leaf::Float(v) => write!(self.out, "{v:?}{}", suffix("f")),
+            Leaf::Double(v) => write!(self.out, "{v:?}{}", suffix("d")),
+            Leaf::String(v) => self.out.write_str(&quote(v)),
+            Leaf::ByteArray(v) => self.array("B", v, suffix("b"), "byte"),
+            Leaf::IntArray(v) => self.array("I", v, "", "int"),
+            Leaf::LongArray(v) => self.array("L", v, suffix("L"), "long"),
+        }
+    }
+
+    fn array<T: fmt::Display>(
+        &mut self,
+        kind: &str,
+        values: &[T],
+        suffix: &str,
+        element: &str,
+    ) -> fmt::Result {
+        let pretty = self.opts.style == Style::Tree || self.opts.indent > 0;
+        let space = if pretty { " " } else { "" };
+
+        if values.len() > self.opts.truncate_arrays {
+            let plural_element = format!("{element}s");
+            let summary = plural(values.len(), element, &plural_element);
+            return write!(self.out, "[{kind};{space}{summary}]");
+        }
+
+        write!(self.out, "[{kind};")?;
+        for (i, v) in values.iter().enumerate() {
+            let separator = if i == 0 {
+                space
+            } else if pretty {
+                ", "
+            } else {
+                ","
+            };
+            write!(self.out, "{separator}{v}{suffix}")?;
+        }
+        self.out.write_char(']')
+    }
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    if n == 1 {
+        format!("{n} {one}")
+    } else {
+        format!("{n} {many}")
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    borrow::Cow,
+    io::{self, Read},
+};
+
+use crate::error::{Error, Result};
+
+mod private {
+    // Only this crate can implement Input, so we are free to change it.
+    pub trait Sealed {}
+}
+
+/// A source of NBT data for the [`Deserializer`][`crate::de::Deserializer`].
+/// This is either a [`Slice`] of bytes or a [`Reader`]. Only slices allow
+/// borrowing from the input.
+pub trait Input<'de>: Read + private::Sealed {
+    /// Consume the next `n` bytes, borrowing them from the input if possible.
+    #[doc(hidden)]
+    fn consume_bytes(&mut self, n: usize) -> Result<Cow<'de, [u8]>>;
+
+    /// Skip over the next `n` bytes.
+    #[doc(hidden)]
+    fn ignore_bytes(&mut self, n: usize) -> Result<()>;
+
+    /// The number of bytes consumed so far.
+    #[doc(hidden)]
+    fn position(&self) -> u64;
+}
+
+/// NBT input from a slice of bytes.
+pub struct Slice<'de> {
+    data: &'de [u8],
+    len: usize,
+}
+
+impl<'de> Slice<'de> {
+    /// Create input from a slice of bytes.
+    pub fn new(data: &'de [u8]) -> Self {
+        Self {
+            data,
+            len: data.len(),
+        }
+    }
+}
+
+impl<'de> private::Sealed for Slice<'de> {}
+
+impl<'de> Read for Slice<'de> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.data.read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.data.read_exact(buf)
+    }
+}
+
+impl<'de> Input<'de> for Slice<'de> {
+    fn consume_bytes(&mut self, n: usize) -> Result<Cow<'de, [u8]>> {
+        if n > self.data.len() {
+            return Err(Error::unexpected_eof());
+        }
+
+        let (bs, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(Cow::Borrowed(bs))
+    }
+
+    fn ignore_bytes(&mut self, n: usize) -> Result<()> {
+        self.consume_bytes(n)?;
+        Ok(())
+    }
+
+    fn position(&self) -> u64 {
+        (self.len - self.data.len()) as u64
+    }
+}
+
+/// NBT input from an [`io::Read`]. Nothing can be borrowed from this input,
+/// so this is typically used to deserialize types that implement
+/// [`DeserializeOwned`][`serde::de::DeserializeOwned`].
+pub struct Reader<R: Read> {
+    reader: R,
+    pos: u64,
+}
+
+impl<R: Read> Reader<R> {
+    /// Create input from a reader. Reads are not buffered, so consider using
+    /// a [`BufReader`][`std::io::BufReader`] for things like files.
+    pub fn new(reader: R) -> Self {
+        Self { reader, pos: 0 }
+    }
+}
+
+impl<R: Read> private::Sealed for Reader<R> {}
+
+impl<R: Read> Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<'de, R: Read> Input<'de> for Reader<R> {
+    fn consume_bytes(&mut self, n: usize) -> Result<Cow<'de, [u8]>> {
+        // Don't allocate all `n` bytes upfront. The size comes from the input
+        // and could be huge, so only grow the buffer as data actually arrives.
+        let mut bs = Vec::new();
+        (&mut self.reader).take(n as u64).read_to_end(&mut bs)?;
+        self.pos += bs.len() as u64;
+
+        if bs.len() != n {
+            return Err(Error::unexpected_eof());
+        }
+
+        Ok(Cow::Owned(bs))
+    }
+
+    fn ignore_bytes(&mut self, n: usize) -> Result<()> {
+        let copied = io::copy(&mut (&mut self.reader).take(n as u64), &mut io::sink())?;
+        self.pos += copied;
+
+        if copied != n as u64 {
+            return Err(Error::unexpected_eof());
+        }
+
+        Ok(())
+    }
+
+    fn position(&self) -> u64 {
+        self.pos
+    }
+}
@@ -0,0 +1,400 @@
+//! Convert between [`Value`] and JSON. This module requires the `json`
+//! feature.
+//!
+//! Serializing a `Value` with `serde_json` directly loses information: every
+//! number looks the same, and arrays and lists are both JSON arrays. This
+//! module has two encodings:
+//!
+//! * The *typed* encoding keeps everything, so that it can be converted back
+//!   into exactly the same `Value`. See [`to_typed`] and [`from_typed`].
+//! * The *plain* encoding is the JSON you would write by hand. The types of
+//!   numbers and lists are guessed when converting back. See [`to_plain`] and
+//!   [`from_plain`].
+//!
+//! ```
+//! # use fastnbt::{json, nbt};
+//! let v = nbt!({ "DataVersion": 3465, "Heightmap": [L; 1, 2] });
+//!
+//! assert_eq!(
+//!     json::to_plain(&v),
+//!     serde_json::json!({ "DataVersion": 3465, "Heightmap": [1, 2] }),
+//! );
+//!
+//! let typed = json::to_typed(&v);
+//! assert_eq!(
+//!     typed["value"]["Heightmap"],
+//!     serde_json::json!({ "type": "long_array", "value": [1, 2] }),
+//! );
+//! assert_eq!(json::from_typed(&typed).unwrap(), v);
+//! ```
+
+use serde_json::{Map, Number, Value as Json};
+
+use crate::error::{Error, Result};
+use crate::{ByteArray, CompoundMap, IntArray, LongArray, Tag, Value};
+
+/// Convert a value to the typed JSON encoding.
+///
+/// Every value becomes an object with its `type` and its `value`, eg
+/// `{"type": "short", "value": 5}`. The types are `byte`, `short`, `int`,
+/// `long`, `float`, `double`, `string`, `byte_array`, `int_array`,
+/// `long_array`, `list` and `compound`.
+///
+/// * Numbers are JSON numbers, except for floats and doubles that are NaN or
+///   infinite, which are the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+/// * Arrays are JSON arrays of numbers.
+/// * Compounds are JSON objects of typed values.
+/// * Lists are JSON arrays of typed values, with an extra `element` field for
+///   the type of their elements, eg
+///   `{"type": "list", "element": "int", "value": [{"type": "int", "value": 1}]}`.
+///   This keeps the type of empty lists.
+///
+/// The only thing not kept is the bit pattern of NaNs.
+pub fn to_typed(value: &Value) -> Json {
+    let inner = match value {
+        Value::Byte(v) => Json::from(*v),
+        Value::Short(v) => Json::from(*v),
+        Value::Int(v) => Json::from(*v),
+        Value::Long(v) => Json::from(*v),
+        Value::Float(v) => typed_float(*v as f64),
+        Value::Double(v) => typed_float(*v),
+        Value::String(v) => Json::from(v.as_str()),
+        Value::ByteArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::IntArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::LongArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::List(tag, elements) => {
+            let element = elements.first().map_or(*tag, Value::tag);
+            let mut obj = Map::new();
+            obj.insert("type".into(), Json::from(tag_name(Tag::List)));
+            obj.insert("element".into(), Json::from(tag_name(element)));
+            obj.insert(
+                "value".into(),
+                Json::Array(elements.iter().map(to_typed).collect()),
+            );
+            return Json::Object(obj);
+        }
+        Value::Compound(map) => {
+            Json::Object(map.iter().map(|(k, v)| (k.clone(), to_typed(v))).collect())
+        }
+    };
+
+    let mut obj = Map::new();
+    obj.insert("type".into(), Json::from(tag_name(value.tag())));
+    obj.insert("value".into(), inner);
+    Json::Object(obj)
+}
+
+/// Convert JSON in the typed encoding back into a value. See [`to_typed`] for
+/// the encoding. Errors record the path to the value that could not be
+/// converted, see [`Error::path`].
+pub fn from_typed(json: &Json) -> Result<Value> {
+    Converter::default().typed(json)
+}
+
+/// Convert a value to plain JSON, as you might write by hand. Numbers are JSON
+/// numbers, strings are JSON strings, arrays and lists are JSON arrays and
+/// compounds are JSON objects. Floats and doubles that are NaN or infinite
+/// become `null`.
+pub fn to_plain(value: &Value) -> Json {
+    match value {
+        Value::Byte(v) => Json::from(*v),
+        Value::Short(v) => Json::from(*v),
+        Value::Int(v) => Json::from(*v),
+        Value::Long(v) => Json::from(*v),
+        Value::Float(v) => Json::from(*v),
+        Value::Double(v) => Json::from(*v),
+        Value::String(v) => Json::from(v.as_str()),
+        Value::ByteArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::IntArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::LongArray(v) => Json::from(v.iter().copied().collect::<Vec<_>>()),
+        Value::List(_, elements) => Json::Array(elements.iter().map(to_plain).collect()),
+        Value::Compound(map) => {
+            Json::Object(map.iter().map(|(k, v)| (k.clone(), to_plain(v))).collect())
+        }
+    }
+}
+
+/// Convert plain JSON into a value, guessing the NBT types.
+///
+/// * Integers become an Int, or a Long if they do not fit in an Int.
+/// * Other numbers become a Double.
+/// * Booleans become a Byte of 0 or 1.
+/// * Strings become a String.
+/// * Arrays become a List. The elements must all have the same type, except
+///   that Ints, Longs and Doubles are widened to the widest one present. An
+///   empty array becomes an empty list of type End.
+/// * Objects become a Compound.
+///
+/// JSON's `null` has no NBT equivalent and is an error. Arrays never become
+/// NBT arrays, use [`from_typed`] to keep those.
+pub fn from_plain(json: &Json) -> Result<Value> {
+    Converter::default().plain(json)
+}
+
+fn typed_float(v: f64) -> Json {
+    match Number::from_f64(v) {
+        Some(n) => Json::Number(n),
+        None if v.is_nan() => Json::from("NaN"),
+        None if v > 0.0 => Json::from("Infinity"),
+        None => Json::from("-Infinity"),
+    }
+}
+
+fn tag_name(tag: Tag) -> &'static str {
+    match tag {
+        Tag::End => "end",
+        Tag::Byte => "byte",
+        Tag::Short => "short",
+        Tag::Int => "int",
+        Tag::Long => "long",
+        Tag::Float => "float",
+        Tag::Double => "double",
+        Tag::ByteArray => "byte_array",
+        Tag::String => "string",
+        Tag::List => "list",
+        Tag::Compound => "compound",
+        Tag::IntArray => "int_array",
+        Tag::LongArray => "long_array",
+    }
+}
+
+fn tag_from_name(name: &str) -> Option<Tag> {
+    Some(match name {
+        "end" => Tag::End,
+        "byte" => Tag::Byte,
+        "short" => Tag::Short,
+        "int" => Tag::Int,
+        "long" => Tag::Long,
+        "float" => Tag::Float,
+        "double" => Tag::Double,
+        "byte_array" => Tag::ByteArray,
+        "string" => Tag::String,
+        "list" => Tag::List,
+        "compound" => Tag::Compound,
+        "int_array" => Tag::IntArray,
+        "long_array" => Tag::LongArray,
+        _ => return None,
+    })
+}
+
+/// Keeps track of the path to the current value, for errors.
+#[derive(Default)]
+struct Converter {
+    path: String,
+}
+
+impl Converter {
+    fn error(&self, msg: String) -> Error {
+        Error::type_mismatch(msg).at_path(self.path.clone())
+    }
+
+    /// Run `f` on a compound entry or list element, with the path extended
+    /// to it.
+    fn within<T>(&mut self, segment: Segment, f: impl FnOnce(&mut Self) -> T) -> T {
+        let len = self.path.len();
+        match segment {
+            Segment::Key(key) if len == 0 => self.path.push_str(key),
+            Segment::Key(key) => {
+                self.path.push('.');
+                self.path.push_str(key);
+            }
+            Segment::Index(i) => {
+                self.path.push('[');
+                self.path.push_str(&i.to_string());
+                self.path.push(']');
+            }
+        }
+        let res = f(self);
+        self.path.truncate(len);
+        res
+    }
+
+    fn typed(&mut self, json: &Json) -> Result<Value> {
+        let obj = json
+            .as_object()
+            .ok_or_else(|| self.error(format!("expected typed value object, found {}", json)))?;
+        let field = |name: &str| {
+            obj.get(name)
+                .ok_or_else(|| self.error(format!("typed value is missing \"{}\"", name)))
+        };
+        let type_name = field("type")?;
+        let tag = type_name
+            .as_str()
+            .and_then(tag_from_name)
+            .filter(|tag| *tag != Tag::End)
+            .ok_or_else(|| self.error(format!("unknown type {}", type_name)))?;
+        let value = field("value")?;
+
+        Ok(match tag {
+            Tag::Byte => Value::Byte(self.typed_int(value, tag)?),
+            Tag::Short => Value::Short(self.typed_int(value, tag)?),
+            Tag::Int => Value::Int(self.typed_int(value, tag)?),
+            Tag::Long => Value::Long(self.typed_int(value, tag)?),
+            Tag::Float => Value::Float(self.typed_float(value, tag)? as f32),
+            Tag::Double => Value::Double(self.typed_float(value, tag)?),
+            Tag::String => Value::String(
+                value
+                    .as_str()
+                    .ok_or_else(|| self.error(format!("expected string, found {}", value)))?
+                    .to_owned(),
+            ),
+            Tag::ByteArray => Value::ByteArray(ByteArray::new(self.typed_array(value, Tag::Byte)?)),
+            Tag::IntArray => Value::IntArray(IntArray::new(self.typed_array(value, Tag::Int)?)),
+            Tag::LongArray => Value::LongArray(LongArray::new(self.typed_array(value, Tag::Long)?)),
+            Tag::List => {
+                let element_name = field("element")?;
+                let element = element_name
+                    .as_str()
+                    .and_then(tag_from_name)
+                    .ok_or_else(|| self.error(format!("unknown element type {}", element_name)))?;
+                let elements = value.as_array().ok_or_else(|| {
+                    self.error(format!("expected array for list, found {}", value))
+                })?;
+
+                let mut list = Vec::with_capacity(elements.len());
+                for (i, el) in elements.iter().enumerate() {
+                    let v = self.within(Segment::Index(i), |c| {
+                        let v = c.typed(el)?;
+                        if v.tag() != element {
+                            return Err(c.error(format!(
+                                "expected {} in list of {}, found {}",
+                                element,
+                                element,
+                                v.tag()
+                            )));
+                        }
+                        Ok(v)
+                    })?;
+                    list.push(v);
+                }
+                Value::List(element, list)
+            }
+            Tag::Compound => {
+                let entries = value.as_object().ok_or_else(|| {
+                    self.error(format!("expected object for compound, found {}", value))
+                })?;
+
+                let mut map = CompoundMap::new();
+                for (k, v) in entries {
+                    let v = self.within(Segment::Key(k), |c| c.typed(v))?;
+                    map.insert(k.clone(), v);
+                }
+                Value::Compound(map)
+            }
+            Tag::End => unreachable!("end is rejected above"),
+        })
+    }
+
+    fn typed_int<T: TryFrom<i64>>(&self, json: &Json, tag: Tag) -> Result<T> {
+        json.as_i64()
+            .and_then(|v| T::try_from(v).ok())
+            .ok_or_else(|| self.error(format!("expected {}, found {}", tag, json)))
+    }
+
+    fn typed_float(&self, json: &Json, tag: Tag) -> Result<f64> {
+        match json {
+            Json::Number(n) => n.as_f64(),
+            Json::String(s) => match s.as_str() {
+                "NaN" => Some(f64::NAN),
+                "Infinity" => Some(f64::INFINITY),
+                "-Infinity" => Some(f64::NEG_INFINITY),
+                _ => None,
+            },
+            _ => None,
+        }
+        .ok_or_else(|| self.error(format!("expected {}, found {}", tag, json)))
+    }
+
+    fn typed_array<T: TryFrom<i64>>(&mut self, json: &Json, element: Tag) -> Result<Vec<T>> {
+        let elements = json
+            .as_array()
+            .ok_or_else(|| self.error(format!("expected array, found {}", json)))?;
+
+        elements
+            .iter()
+            .enumerate()
+            .map(|(i, el)| self.within(Segment::Index(i), |c| c.typed_int(el, element)))
+            .collect()
+    }
+
+    fn plain(&mut self, json: &Json) -> Result<Value> {
+        Ok(match json {
+            Json::Null => return Err(self.error("null has no NBT equivalent".to_string())),
+            Json::Bool(b) => Value::Byte(*b as i8),
+            Json::Number(n) => {
+                if let Some(v) = n.as_i64() {
+                    match i32::try_from(v) {
+                        Ok(v) => Value::Int(v),
+                        Err(_) => Value::Long(v),
+                    }
+                } else if n.is_u64() {
+                    return Err(self.error(format!("{} is too large for a long", n)));
+                } else {
+                    // Only non-integers are left, which are always f64.
+                    Value::Double(n.as_f64().unwrap_or_default())
+                }
+            }
+            Json::String(s) => Value::String(s.clone()),
+            Json::Array(elements) => {
+                let mut list = Vec::with_capacity(elements.len());
+                for (i, el) in elements.iter().enumerate() {
+                    list.push(self.within(Segment::Index(i), |c| c.plain(el))?);
+                }
+                self.unify(list)?
+            }
+            Json::Object(entries) => {
+                let mut map = CompoundMap::new();
+                for (k, v) in entries {
+                    let v = self.within(Segment::Key(k), |c| c.plain(v))?;
+                    map.insert(k.clone(), v);
+                }
+                Value::Compound(map)
+            }
+        })
+    }
+
+    /// Make a list out of plain values, widening numbers so that they all
+    /// have the same type.
+    fn unify(&self, mut list: Vec<Value>) -> Result<Value> {
+        let rank = |tag| match tag {
+            Tag::Int => Some(0),
+            Tag::Long => Some(1),
+            Tag::Double => Some(2),
+            _ => None,
+        };
+
+        let mut element = match list.first() {
+            Some(v) => v.tag(),
+            None => return Ok(Value::List(Tag::End, list)),
+        };
+        for v in &list[1..] {
+            let tag = v.tag();
+            element = match (rank(element), rank(tag)) {
+                _ if tag == element => element,
+                (Some(a), Some(b)) if b > a => tag,
+                (Some(_), Some(_)) => element,
+                _ => {
+                    return Err(self.error(format!(
+                        "array mixes {} and {}, which cannot be in the same list",
+                        element, tag
+                    )))
+                }
+            };
+        }
+
+        for v in &mut list {
+            *v = match (element, &*v) {
+                (Tag::Long, Value::Int(i)) => Value::Long(*i as i64),
+                (Tag::Double, Value::Int(i)) => Value::Double(*i as f64),
+                (Tag::Double, Value::Long(i)) => Value::Double(*i as f64),
+                _ => continue,
+            };
+        }
+        Ok(Value::List(element, list))
+    }
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
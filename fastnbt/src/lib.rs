@@ -4,10 +4,30 @@
 //!
 //! * For documentation and examples of serde (de)serialization, see [`ser`] and
 //!   [`de`].
-//! * For a `serde_json`-like `Value` type see [`Value`].
+//! * For a `serde_json`-like `Value` type see [`Value`]. Enable the
+//!   `preserve_order` feature to keep the order of keys in compounds.
 //! * To easily create values, see the [`nbt`] macro.
 //! * For NBT array types see [`ByteArray`], [`IntArray`], and [`LongArray`].
-//! * For zero-copy NBT array types see [`borrow`].
+//! * For zero-copy NBT array types and a borrowing `Value`, see [`borrow`].
+//! * For the stringified NBT used in commands see [`snbt`].
+//! * To print NBT for people to read, as a tree or indented SNBT, see
+//!   [`fmt`].
+//! * To compare two values and apply the differences elsewhere, see [`diff`]
+//!   and [`patch`].
+//! * To check NBT has the fields and types you expect, see [`schema`].
+//! * To keep the name of the root compound see [`NamedRoot`].
+//! * For Bedrock Edition's little endian NBT see [`Flavour`].
+//! * To find how many bytes some NBT will take up see [`serialized_size`].
+//! * For booleans stored as bytes, UUIDs and block positions, enable the
+//!   `serde_helpers` feature and see `serde_helpers`. Enable the `uuid`
+//!   feature for the UUID helpers.
+//! * For GZip and Zlib compressed NBT enable the `compression` feature and see
+//!   `compression`.
+//! * To convert `Value` to and from JSON without losing the NBT types, enable
+//!   the `json` feature and see `json`.
+//! * To store a `Vec` as an NBT array, a `bool` as a Byte, or keep the element
+//!   tag of empty lists without wrapper types, enable the `derive` feature and
+//!   see `Nbt`.
 //!
 //! Both this and related crates are under one [fastnbt Github
 //! repository](https://github.com/owengage/fastnbt).
@@ -117,30 +137,85 @@
 //!# }
 //! ```
 //!
-//! # `Read` based parser
+//! # `Read` based deserializing
+//!
+//! [`from_reader`] deserializes from anything implementing `Read`, such as a
+//! `GzDecoder`, without first reading everything into a buffer. Nothing can be
+//! borrowed from a reader, so the result must own its data.
 //!
 //! A lower level parser also exists in the `stream` module that only requires
 //! the `Read` trait on the input. This parser however doesn't support
 //! deserializing to Rust objects directly.
 //!
+//! # Round trips
+//!
+//! Java Edition NBT deserialized into a [`NamedRoot<Value>`][`NamedRoot`] and
+//! serialized again with [`to_bytes`] gives back exactly the same bytes. This
+//! keeps:
+//!
+//! * the name of the root compound,
+//! * the order of keys in compounds, with the `preserve_order` feature,
+//! * the element tag of empty lists, see [`Value::List`],
+//! * the bit patterns of floats and doubles, including NaNs.
+//!
+//! Deserializing into a plain [`Value`] keeps everything but the root name.
+//! Input that is not in the form fastnbt writes, such as a compound with
+//! duplicate keys or data after the root compound, will not round trip. Use
+//! [`roundtrip_check`] to find where some NBT differs after a round trip.
+//!
 
 use ser::{Serializer, State};
 use serde::{de as serde_de, Deserialize, Serialize};
 
 pub mod borrow;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub mod de;
+pub mod diff;
 pub mod error;
+pub mod fmt;
+#[cfg(feature = "json")]
+pub mod json;
+#[doc(hidden)]
+#[macro_use]
+pub mod macros;
+pub mod schema;
 pub mod ser;
+#[cfg(feature = "serde_helpers")]
+pub mod serde_helpers;
+pub mod snbt;
 pub mod stream;
 pub mod value;
 
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+
 mod arrays;
 mod de_arrays;
-#[macro_use]
-mod macros;
+mod input;
+mod limits;
+mod named_root;
+mod roundtrip;
+mod varint;
 
 pub use arrays::*;
-pub use value::{from_value, to_value, Value};
+#[cfg(feature = "compression")]
+pub use compression::{
+    from_compressed_bytes, from_compressed_bytes_with_opts, from_compressed_reader,
+    from_compressed_reader_with_opts, to_compressed_writer_with_opts, to_gzip_writer,
+    to_zlib_writer,
+};
+pub use diff::{diff, patch};
+#[cfg(feature = "derive")]
+pub use fastnbt_derive::Nbt;
+pub use named_root::NamedRoot;
+pub use roundtrip::roundtrip_check;
+pub use value::{from_value, to_value, CompoundMap, Value};
+
+// Code from `#[derive(Nbt)]` refers to `::fastnbt`, so let our own tests use it.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as fastnbt;
 
 #[cfg(test)]
 mod test;
@@ -149,7 +224,11 @@ use crate::{
     de::Deserializer,
     error::{Error, Result},
 };
-use std::{convert::TryFrom, fmt::Display, io::Write};
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    io::{Read, Write},
+};
 
 /// An NBT tag. This does not carry the value or the name of the data.
 #[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
@@ -252,33 +331,136 @@ impl Display for Tag {
     }
 }
 
+/// The binary flavour of NBT to read or write. Java Edition uses big endian
+/// NBT everywhere. Bedrock Edition uses little endian NBT on disk, eg in
+/// `.mcstructure` files and level.dat, and a variant using variable length
+/// integers in network packets.
+///
+/// Bedrock Edition strings are UTF-8 rather than Java's modified UTF-8. Data
+/// in the Bedrock flavours cannot be borrowed for the NBT array types, so use
+/// the owned array types such as [`LongArray`] rather than those in
+/// [`borrow`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Flavour {
+    /// Big endian NBT, as used by Java Edition. This is the default.
+    #[default]
+    JavaBigEndian,
+
+    /// Little endian NBT, as used by Bedrock Edition on disk.
+    BedrockLittleEndian,
+
+    /// Bedrock Edition's network NBT. Ints and longs, along with list, array
+    /// and string lengths, are written as variable length integers. Other
+    /// values are little endian.
+    BedrockNetwork,
+}
+
 /// Serialize some `T` into NBT data. See the [`ser`] module for more
 /// information.
 pub fn to_bytes<T: Serialize>(v: &T) -> Result<Vec<u8>> {
-    let mut result = vec![];
-    let mut serializer = Serializer {
-        writer: &mut result,
-        state: State::Compound {
-            current_field: String::new(),
-        },
-    };
-    v.serialize(&mut serializer)?;
-    Ok(result)
+    to_bytes_with_opts(v, Default::default())
 }
 
 /// Serialize some `T` into NBT data. See the [`ser`] module for more
 /// information.
 pub fn to_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
+    to_writer_with_opts(writer, v, Default::default())
+}
+
+/// Options for customizing serialization.
+pub struct SerOpts {
+    flavour: Flavour,
+
+    /// Whether to omit the name of the root compound.
+    network_nbt: bool,
+
+    /// The name of the root compound.
+    root_name: Option<String>,
+}
+
+impl SerOpts {
+    /// Create new options. This object follows a builder pattern.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the flavour of NBT to write, eg to write little endian NBT for
+    /// Bedrock Edition. See [`Flavour`].
+    pub fn flavour(mut self, value: Flavour) -> Self {
+        self.flavour = value;
+        self
+    }
+
+    /// Write "network NBT", where the root compound has no name. This is what
+    /// Java Edition's protocol uses since 1.20.2.
+    pub fn network_nbt(mut self, value: bool) -> Self {
+        self.network_nbt = value;
+        self
+    }
+
+    /// Set the name of the root compound, which is empty by default. A
+    /// [`NamedRoot`] being serialized uses its own name instead. The name is
+    /// not written for network NBT.
+    pub fn root_name(mut self, value: impl Into<String>) -> Self {
+        self.root_name = Some(value.into());
+        self
+    }
+}
+
+impl Default for SerOpts {
+    fn default() -> Self {
+        Self {
+            flavour: Flavour::JavaBigEndian,
+            network_nbt: false,
+            root_name: None,
+        }
+    }
+}
+
+/// Similar to [`to_bytes`] but with options.
+pub fn to_bytes_with_opts<T: Serialize>(v: &T, opts: SerOpts) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    to_writer_with_opts(&mut result, v, opts)?;
+    Ok(result)
+}
+
+/// Similar to [`to_writer`] but with options.
+pub fn to_writer_with_opts<T: Serialize, W: Write>(
+    writer: W,
+    v: &T,
+    mut opts: SerOpts,
+) -> Result<()> {
     let mut serializer = Serializer {
         writer,
-        state: State::Compound {
-            current_field: String::new(),
+        state: State::Root {
+            name: opts.root_name.take(),
         },
+        opts,
     };
     v.serialize(&mut serializer)?;
     Ok(())
 }
 
+/// Get the number of bytes `v` takes up when serialized to NBT, without
+/// writing it anywhere. This is useful to check that some NBT fits somewhere,
+/// such as a packet, before writing it.
+///
+/// ```
+/// # use fastnbt::{nbt, serialized_size, to_bytes};
+/// let v = nbt!({ "name": "Steve", "health": 20.0_f32 });
+/// assert_eq!(serialized_size(&v).unwrap(), to_bytes(&v).unwrap().len());
+/// ```
+pub fn serialized_size<T: Serialize>(v: &T) -> Result<usize> {
+    serialized_size_with_opts(v, Default::default())
+}
+
+/// Similar to [`serialized_size`] but with options.
+pub fn serialized_size_with_opts<T: Serialize>(v: &T, opts: SerOpts) -> Result<usize> {
+    let mut counter = ser::SizeCounter::default();
+    to_writer_with_opts(&mut counter, v, opts)?;
+    Ok(counter.0)
+}
+
 /// Deserialize into a `T` from some NBT data. See the [`de`] module for more
 /// information.
 ///
@@ -307,10 +489,64 @@ where
     from_bytes_with_opts(input, Default::default())
 }
 
+/// How strictly numbers in NBT must match the Rust types they are deserialized
+/// into. See [`DeOpts::coercion`].
+///
+/// This applies when deserializing into primitive numbers and `bool`. Types
+/// that deserialize through `deserialize_any`, such as [`Value`] and serde's
+/// untagged enums, always see the number as it is in the NBT.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Coercion {
+    /// Hand the number to the type being deserialized and let it decide. The
+    /// primitive types accept any number that fits in them, and integers can
+    /// become floats. A `bool` can be any integer, where non-zero is `true`.
+    /// This is the default.
+    #[default]
+    Visitor,
+
+    /// The NBT tag must exactly match the Rust type: Byte for `i8` and `u8`,
+    /// Short for `i16` and `u16`, Int for `i32` and `u32`, Long for `i64` and
+    /// `u64`, Float for `f32` and Double for `f64`. A `bool` must be a Byte
+    /// of 0 or 1.
+    ///
+    /// This suits validating data.
+    Strict,
+
+    /// Any integer tag can be deserialized into any integer type, as long as
+    /// the value fits, and into the float types. Float and Double can be
+    /// deserialized into either float type. A `bool` can be any integer, where
+    /// non-zero is `true`.
+    ///
+    /// This suits reading data written by other tools, such as mods that write
+    /// an Int where Minecraft writes a Short.
+    Lenient,
+}
+
 /// Options for customozing deserialization.
 pub struct DeOpts {
     /// Maximum number of bytes a list or array can be.
     max_seq_len: usize,
+
+    /// The flavour of NBT to read.
+    flavour: Flavour,
+
+    /// Whether the root compound is expected to have no name.
+    network_nbt: bool,
+
+    /// How numbers are converted into the types being deserialized.
+    coercion: Coercion,
+
+    /// Maximum number of compounds and lists that can be nested.
+    max_depth: usize,
+
+    /// Maximum number of elements an NBT array can have.
+    max_array_len: usize,
+
+    /// Maximum number of bytes a string can be.
+    max_string_len: usize,
+
+    /// Maximum total size of all strings, arrays and lists.
+    max_alloc: usize,
 }
 
 impl DeOpts {
@@ -320,18 +556,116 @@ impl DeOpts {
     }
 
     /// Set the maximum length any given sequence can be, eg lists. This does
-    /// not apply to NBT array types. This can help prevent panics on malformed
-    /// data.
+    /// not apply to NBT array types, see [`max_array_len`][Self::max_array_len].
+    /// This can help prevent panics on malformed data.
     pub fn max_seq_len(mut self, value: usize) -> Self {
         self.max_seq_len = value;
         self
     }
+
+    /// Set the flavour of NBT to read, eg to read little endian NBT from
+    /// Bedrock Edition. See [`Flavour`].
+    pub fn flavour(mut self, value: Flavour) -> Self {
+        self.flavour = value;
+        self
+    }
+
+    /// Read "network NBT", where the root compound has no name. This is what
+    /// Java Edition's protocol uses since 1.20.2.
+    pub fn network_nbt(mut self, value: bool) -> Self {
+        self.network_nbt = value;
+        self
+    }
+
+    /// Set how strictly numbers must match the types they are deserialized
+    /// into. See [`Coercion`].
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, to_bytes, from_bytes_with_opts, Coercion, DeOpts};
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Item {
+    ///     count: i8,
+    /// }
+    ///
+    /// // Written by a mod that uses an Int rather than a Byte.
+    /// let bytes = to_bytes(&nbt!({ "count": 5 })).unwrap();
+    ///
+    /// let strict = DeOpts::new().coercion(Coercion::Strict);
+    /// assert!(from_bytes_with_opts::<Item>(&bytes, strict).is_err());
+    ///
+    /// let lenient = DeOpts::new().coercion(Coercion::Lenient);
+    /// let item: Item = from_bytes_with_opts(&bytes, lenient).unwrap();
+    /// assert_eq!(item.count, 5);
+    /// ```
+    pub fn coercion(mut self, value: Coercion) -> Self {
+        self.coercion = value;
+        self
+    }
+
+    /// Set the maximum number of compounds and lists that can be nested
+    /// inside each other, counting the root compound. There is no limit by
+    /// default. Deserializing recurses for each level, so set this for input
+    /// from untrusted sources to stop deeply nested input from overflowing
+    /// the stack. Normal Minecraft data is far shallower than 64 levels.
+    pub fn max_depth(mut self, value: usize) -> Self {
+        self.max_depth = value;
+        self
+    }
+
+    /// Set the maximum number of elements a ByteArray, IntArray or LongArray
+    /// can have. There is no limit by default.
+    pub fn max_array_len(mut self, value: usize) -> Self {
+        self.max_array_len = value;
+        self
+    }
+
+    /// Set the maximum length of strings in bytes, including the names of
+    /// values in compounds. There is no limit by default.
+    pub fn max_string_len(mut self, value: usize) -> Self {
+        self.max_string_len = value;
+        self
+    }
+
+    /// Set the maximum total size in bytes of all the strings, arrays and
+    /// lists in the input. Sizes are checked as they are read from the input,
+    /// before anything is allocated for them. Each list element counts as the
+    /// smallest size its tag can take up in NBT, eg one byte for a compound,
+    /// plus anything inside it. There is no limit by default.
+    ///
+    /// This is useful with input from untrusted sources, along with the other
+    /// limits:
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, to_bytes, from_bytes_with_opts, DeOpts, Value};
+    /// let bytes = to_bytes(&nbt!({ "pages": ["a".repeat(1000)] })).unwrap();
+    ///
+    /// let opts = DeOpts::new()
+    ///     .max_depth(16)
+    ///     .max_array_len(1024)
+    ///     .max_string_len(256)
+    ///     .max_alloc(64 * 1024);
+    ///
+    /// let err = from_bytes_with_opts::<Value>(&bytes, opts).unwrap_err();
+    /// assert!(err.to_string().contains("max string length"));
+    /// ```
+    pub fn max_alloc(mut self, value: usize) -> Self {
+        self.max_alloc = value;
+        self
+    }
 }
 
 impl Default for DeOpts {
     fn default() -> Self {
         Self {
             max_seq_len: 100_000,
+            flavour: Flavour::JavaBigEndian,
+            network_nbt: false,
+            coercion: Coercion::Visitor,
+            max_depth: usize::MAX,
+            max_array_len: usize::MAX,
+            max_string_len: usize::MAX,
+            max_alloc: usize::MAX,
         }
     }
 }
@@ -347,11 +681,47 @@ where
     // `from_bytes`. This would be invalid starting data for NBT anyway.
     if input.starts_with(&GZIP_MAGIC_BYTES) {
         return Err(Error::bespoke(
-            "from_bytes expects raw NBT, but input appears to be gzipped".to_string(),
+            "from_bytes expects raw NBT, but input appears to be gzipped (see the compression feature)"
+                .to_string(),
         ));
     }
 
     let mut des = Deserializer::from_bytes(input, opts);
-    let t = T::deserialize(&mut des)?;
-    Ok(t)
+    T::deserialize(&mut des).map_err(|e| des.locate(e))
+}
+
+/// Deserialize into a `T` from a reader of NBT data. Unlike [`from_bytes`],
+/// nothing can be borrowed from the input, so `T` must own its data. See the
+/// [`de`] module for more information.
+///
+/// ```no_run
+/// # use fastnbt::Value;
+/// # use flate2::read::GzDecoder;
+/// # use std::io;
+/// # use fastnbt::error::Result;
+/// # fn main() -> Result<()> {
+/// # let some_reader = io::stdin();
+/// let decoder = GzDecoder::new(some_reader);
+/// let val: Value = fastnbt::from_reader(decoder)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`de`]: ./index.html
+pub fn from_reader<R, T>(reader: R) -> Result<T>
+where
+    R: Read,
+    T: serde_de::DeserializeOwned,
+{
+    from_reader_with_opts(reader, Default::default())
+}
+
+/// Similar to [`from_reader`] but with options.
+pub fn from_reader_with_opts<R, T>(reader: R, opts: DeOpts) -> Result<T>
+where
+    R: Read,
+    T: serde_de::DeserializeOwned,
+{
+    let mut des = Deserializer::from_reader(reader, opts);
+    T::deserialize(&mut des).map_err(|e| des.locate(e))
 }
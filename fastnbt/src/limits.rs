@@ -0,0 +1,110 @@
+//! Resource limits applied while reading NBT, configured with [`DeOpts`].
+
+use crate::error::{Error, Result};
+use crate::{DeOpts, Tag};
+
+/// The limits from [`DeOpts`], along with how much of the allocation budget
+/// has been used so far.
+#[derive(Debug, Clone)]
+pub(crate) struct Limits {
+    max_depth: usize,
+    max_array_len: usize,
+    max_string_len: usize,
+    max_alloc: usize,
+    allocated: usize,
+}
+
+impl Limits {
+    pub(crate) fn new(opts: &DeOpts) -> Self {
+        Self {
+            max_depth: opts.max_depth,
+            max_array_len: opts.max_array_len,
+            max_string_len: opts.max_string_len,
+            max_alloc: opts.max_alloc,
+            allocated: 0,
+        }
+    }
+
+    /// Check entering a compound or list that is `depth` levels deep, where
+    /// the root compound is at depth 1.
+    pub(crate) fn depth(&self, depth: usize) -> Result<()> {
+        if depth > self.max_depth {
+            return Err(Error::size(format!(
+                "nesting depth ({}) greater than max depth ({})",
+                depth, self.max_depth
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a string of `len` bytes, including names.
+    pub(crate) fn string(&mut self, len: usize) -> Result<()> {
+        if len > self.max_string_len {
+            return Err(Error::size(format!(
+                "string length ({}) greater than max string length ({})",
+                len, self.max_string_len
+            )));
+        }
+        self.alloc(len)
+    }
+
+    /// Check an NBT array of `len` elements of the `element` tag.
+    pub(crate) fn array(&mut self, element: Tag, len: usize) -> Result<()> {
+        self.check_array(element, len)?;
+        self.alloc(len.saturating_mul(payload_size(element)))
+    }
+
+    /// Check an NBT array like [`Limits::array`], without counting it towards
+    /// the allocation budget.
+    pub(crate) fn check_array(&self, element: Tag, len: usize) -> Result<()> {
+        if len > self.max_array_len {
+            return Err(Error::size(format!(
+                "array length ({}) greater than max array length ({})",
+                len, self.max_array_len
+            )));
+        }
+        let size = len.saturating_mul(payload_size(element));
+        if self.allocated.saturating_add(size) > self.max_alloc {
+            return Err(self.over_budget(self.allocated.saturating_add(size)));
+        }
+        Ok(())
+    }
+
+    /// Check a list of `len` elements of the `element` tag.
+    pub(crate) fn list(&mut self, element: Tag, len: usize) -> Result<()> {
+        self.alloc(len.saturating_mul(payload_size(element)))
+    }
+
+    fn alloc(&mut self, size: usize) -> Result<()> {
+        self.allocated = self.allocated.saturating_add(size);
+        if self.allocated > self.max_alloc {
+            return Err(self.over_budget(self.allocated));
+        }
+        Ok(())
+    }
+
+    fn over_budget(&self, allocated: usize) -> Error {
+        Error::size(format!(
+            "total size of strings, arrays and lists ({}) greater than max allocation ({})",
+            allocated, self.max_alloc
+        ))
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new(&DeOpts::default())
+    }
+}
+
+/// The smallest number of bytes the payload of a `tag` takes up.
+fn payload_size(tag: Tag) -> usize {
+    match tag {
+        Tag::End => 0,
+        Tag::Byte | Tag::Compound => 1,
+        Tag::Short | Tag::String => 2,
+        Tag::Int | Tag::Float | Tag::ByteArray | Tag::IntArray | Tag::LongArray => 4,
+        Tag::List => 5,
+        Tag::Long | Tag::Double => 8,
+    }
+}
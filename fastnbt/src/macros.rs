@@ -41,8 +41,64 @@
 /// });
 /// ```
 ///
-/// Unlike SNBT, key/field names for compounds need quoted strings. `"key1"`
-/// above could not be simplified to just `key1`.
+/// As in serde_json's `json!`, names in compounds are expressions, so a
+/// variable can be used as a name. Unlike SNBT, this means plain names need
+/// to be quoted: `"key1"` above could not be simplified to just `key1`, which
+/// would use the value of a variable called `key1`.
+///
+/// ```rust
+/// # use fastnbt::nbt;
+/// let name = "Count";
+/// assert_eq!(nbt!({"id": "minecraft:stone", name: 1}), nbt!({"id": "minecraft:stone", "Count": 1}));
+/// ```
+///
+/// Numbers are typed the same way as in Rust, so `1` is an Int and `1_i8` is
+/// a Byte. SNBT suffixes work too, as does an explicit `byte!`, `short!`,
+/// `int!`, `long!`, `float!` or `double!` around any expression. Rust reads
+/// `0b` as the start of a binary number, so a zero byte has to be written as
+/// `0B` instead:
+///
+/// ```rust
+/// # use fastnbt::{nbt, Value};
+/// let count = 3;
+/// let item = nbt!({
+///     "Count": 1b,
+///     "Damage": 10s,
+///     "Slot": byte!(count),
+///     "Scale": 0.5f,
+/// });
+/// assert_eq!(item["Count"], Value::Byte(1));
+/// assert_eq!(item["Damage"], Value::Short(10));
+/// assert_eq!(item["Slot"], Value::Byte(3));
+/// assert_eq!(item["Scale"], Value::Float(0.5));
+/// ```
+///
+/// Literals are checked when compiling, so one that doesn't fit its type is
+/// an error, as it is in Rust:
+///
+/// ```compile_fail
+/// # use fastnbt::nbt;
+/// let _ = nbt!({"Count": 300b});
+/// ```
+///
+/// ```compile_fail
+/// # use fastnbt::nbt;
+/// let _ = nbt!([B; 1, 2, 300]);
+/// ```
+///
+/// The explicit forms like `byte!` can take any expression, so they are
+/// checked when run instead, and panic if the value doesn't fit.
+///
+/// Any other expression is converted with [`to_value`][`crate::to_value`], so
+/// existing [`Value`][`crate::Value`]s and anything that implements
+/// `Serialize` can be placed inside:
+///
+/// ```rust
+/// # use fastnbt::nbt;
+/// let item = nbt!({"id": "minecraft:stone", "Count": 1b});
+/// let pos = vec![1.0, 64.0, 1.0];
+/// let _ = nbt!({"Items": [item.clone(), item], "Pos": pos});
+/// ```
 ///
 /// NBT Arrays are supported with
 /// [SNBT](https://minecraft.fandom.com/wiki/NBT_format#SNBT_format) syntax:
@@ -87,6 +143,42 @@ macro_rules! nbt_internal {
         nbt_internal!(@array [$($elems,)* nbt_internal!({$($map)*})] $($rest)*)
     };
 
+    // Next element is a typed number or a macro call, followed by comma.
+    (@array [$($elems:expr,)*] $mac:ident ! ($($args:tt)*), $($rest:tt)*) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(@call $mac ($($args)*)),] $($rest)*)
+    };
+
+    // Last element is a typed number or a macro call.
+    (@array [$($elems:expr,)*] $mac:ident ! ($($args:tt)*)) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(@call $mac ($($args)*))])
+    };
+
+    // Next element is a negative literal followed by comma.
+    (@array [$($elems:expr,)*] - $next:literal, $($rest:tt)*) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(- $next),] $($rest)*)
+    };
+
+    // Last element is a negative literal with no trailing comma.
+    (@array [$($elems:expr,)*] - $last:literal) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(- $last)])
+    };
+
+    // Next element is a negated expression, which must not reach the literal
+    // rules below as they would fail to parse it.
+    (@array [$($elems:expr,)*] - $($rest:tt)*) => {
+        nbt_internal!(@array_expr [$($elems,)*] - $($rest)*)
+    };
+
+    // Next element is a literal followed by comma.
+    (@array [$($elems:expr,)*] $next:literal, $($rest:tt)*) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(@literal ::core::stringify!($next)),] $($rest)*)
+    };
+
+    // Last element is a literal with no trailing comma.
+    (@array [$($elems:expr,)*] $last:literal) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!(@literal ::core::stringify!($last))])
+    };
+
     // Next element is an expression followed by comma.
     (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
         nbt_internal!(@array [$($elems,)* nbt_internal!($next),] $($rest)*)
@@ -97,6 +189,15 @@ macro_rules! nbt_internal {
         nbt_internal!(@array [$($elems,)* nbt_internal!($last)])
     };
 
+    // Same as the two rules above, for negated expressions.
+    (@array_expr [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!($next),] $($rest)*)
+    };
+
+    (@array_expr [$($elems:expr,)*] $last:expr) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!($last)])
+    };
+
     // Comma after the most recent element.
     (@array [$($elems:expr),*] , $($rest:tt)*) => {
         nbt_internal!(@array [$($elems,)*] $($rest)*)
@@ -110,32 +211,66 @@ macro_rules! nbt_internal {
     /* ------------ IntArray types ------------ */
 
     // Done with trailing comma.
-    (@int_array [$($elems:expr,)*]) => {
+    (@int_array $t:ident [$($elems:expr,)*]) => {
         nbt_internal_vec![$($elems,)*]
     };
 
     // Done without trailing comma.
-    (@int_array [$($elems:expr),*]) => {
+    (@int_array $t:ident [$($elems:expr),*]) => {
         nbt_internal_vec![$($elems),*]
     };
 
+    // Next element is a negative literal followed by comma.
+    (@int_array $t:ident [$($elems:expr,)*] - $next:literal, $($rest:tt)*) => {
+        nbt_internal!(@int_array $t [$($elems,)* nbt_internal!(@element $t ::core::concat!("-", ::core::stringify!($next))),] $($rest)*)
+    };
+
+    // Last element is a negative literal with no trailing comma.
+    (@int_array $t:ident [$($elems:expr,)*] - $last:literal) => {
+        nbt_internal!(@int_array $t [$($elems,)* nbt_internal!(@element $t ::core::concat!("-", ::core::stringify!($last)))])
+    };
+
+    // Next element is a negated expression.
+    (@int_array $t:ident [$($elems:expr,)*] - $($rest:tt)*) => {
+        nbt_internal!(@int_array_expr $t [$($elems,)*] - $($rest)*)
+    };
+
+    // Next element is a literal followed by comma.
+    (@int_array $t:ident [$($elems:expr,)*] $next:literal, $($rest:tt)*) => {
+        nbt_internal!(@int_array $t [$($elems,)* nbt_internal!(@element $t ::core::stringify!($next)),] $($rest)*)
+    };
+
+    // Last element is a literal with no trailing comma.
+    (@int_array $t:ident [$($elems:expr,)*] $last:literal) => {
+        nbt_internal!(@int_array $t [$($elems,)* nbt_internal!(@element $t ::core::stringify!($last))])
+    };
+
     // Next element is an expression followed by comma.
-    (@int_array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
-        nbt_internal!(@int_array [$($elems,)* $next,] $($rest)*)
+    (@int_array $t:ident [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        nbt_internal!(@int_array $t [$($elems,)* $next,] $($rest)*)
     };
 
     // Last element is an expression with no trailing comma.
-    (@int_array [$($elems:expr,)*] $last:expr) => {
-        nbt_internal!(@int_array [$($elems,)* $last])
+    (@int_array $t:ident [$($elems:expr,)*] $last:expr) => {
+        nbt_internal!(@int_array $t [$($elems,)* $last])
+    };
+
+    // Same as the two rules above, for negated expressions.
+    (@int_array_expr $t:ident [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        nbt_internal!(@int_array $t [$($elems,)* $next,] $($rest)*)
+    };
+
+    (@int_array_expr $t:ident [$($elems:expr,)*] $last:expr) => {
+        nbt_internal!(@int_array $t [$($elems,)* $last])
     };
 
     // Comma after the most recent element.
-    (@int_array [$($elems:expr),*] , $($rest:tt)*) => {
-        nbt_internal!(@int_array [$($elems,)*] $($rest)*)
+    (@int_array $t:ident [$($elems:expr),*] , $($rest:tt)*) => {
+        nbt_internal!(@int_array $t [$($elems,)*] $($rest)*)
     };
 
     // Unexpected token after most recent element.
-    (@int_array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+    (@int_array $t:ident [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
         nbt_unexpected!($unexpected)
     };
 
@@ -178,6 +313,41 @@ macro_rules! nbt_internal {
         nbt_internal!(@object $object [$($key)+] (nbt_internal!({$($map)*})) $($rest)*);
     };
 
+    // Next value is a typed number or a macro call, followed by comma.
+    (@object $object:ident ($($key:tt)+) (: $mac:ident ! ($($args:tt)*) , $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(@call $mac ($($args)*))) , $($rest)*);
+    };
+
+    // Last value is a typed number or a macro call.
+    (@object $object:ident ($($key:tt)+) (: $mac:ident ! ($($args:tt)*)) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(@call $mac ($($args)*))));
+    };
+
+    // Next value is a negative literal followed by comma.
+    (@object $object:ident ($($key:tt)+) (: - $value:literal , $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(- $value)) , $($rest)*);
+    };
+
+    // Last value is a negative literal with no trailing comma.
+    (@object $object:ident ($($key:tt)+) (: - $value:literal) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(- $value)));
+    };
+
+    // Next value is a negated expression.
+    (@object $object:ident ($($key:tt)+) (: - $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object_expr $object ($($key)+) (: - $($rest)*) $copy);
+    };
+
+    // Next value is a literal followed by comma.
+    (@object $object:ident ($($key:tt)+) (: $value:literal , $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(@literal ::core::stringify!($value))) , $($rest)*);
+    };
+
+    // Last value is a literal with no trailing comma.
+    (@object $object:ident ($($key:tt)+) (: $value:literal) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!(@literal ::core::stringify!($value))));
+    };
+
     // Next value is an expression followed by comma.
     (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
         nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)) , $($rest)*);
@@ -188,6 +358,15 @@ macro_rules! nbt_internal {
         nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)));
     };
 
+    // Same as the two rules above, for negated expressions.
+    (@object_expr $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)) , $($rest)*);
+    };
+
+    (@object_expr $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)));
+    };
+
     // Missing value for last entry. Trigger a reasonable error message.
     (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
         // "unexpected end of macro invocation"
@@ -248,37 +427,98 @@ macro_rules! nbt_internal {
     };
 
     ([]) => {
-        $crate::Value::List(nbt_internal_vec![])
+        $crate::Value::List($crate::Tag::End, nbt_internal_vec![])
     };
 
     ([B; $($tt:tt)+ ]) => {
-        $crate::Value::ByteArray($crate::ByteArray::new(nbt_internal!(@int_array [] $($tt)+)))
+        $crate::Value::ByteArray($crate::ByteArray::new(nbt_internal!(@int_array i8 [] $($tt)+)))
     };
 
     ([I; $($tt:tt)+ ]) => {
-        $crate::Value::IntArray($crate::IntArray::new(nbt_internal!(@int_array [] $($tt)+)))
+        $crate::Value::IntArray($crate::IntArray::new(nbt_internal!(@int_array i32 [] $($tt)+)))
     };
 
     ([L; $($tt:tt)+ ]) => {
-        $crate::Value::LongArray($crate::LongArray::new(nbt_internal!(@int_array [] $($tt)+)))
+        $crate::Value::LongArray($crate::LongArray::new(nbt_internal!(@int_array i64 [] $($tt)+)))
     };
 
     ([ $($tt:tt)+ ]) => {
-        $crate::Value::List(nbt_internal!(@array [] $($tt)+))
+        $crate::Value::from(nbt_internal!(@array [] $($tt)+))
     };
 
     ({}) => {
-        $crate::Value::Compound(std::collections::HashMap::new())
+        $crate::Value::Compound($crate::value::CompoundMap::new())
     };
 
     ({ $($tt:tt)+ }) => {
         $crate::Value::Compound({
-            let mut object = std::collections::HashMap::new();
+            let mut object = $crate::value::CompoundMap::new();
             nbt_internal!(@object object () ($($tt)+) ($($tt)+));
             object
         })
     };
 
+    // Literals are parsed in a const, so that a bad one fails to compile.
+    (@literal $token:expr) => {
+        $crate::macros::literal({
+            const LITERAL: $crate::macros::Literal = $crate::macros::Literal::parse($token);
+            LITERAL
+        })
+    };
+
+    (@element $t:ident $token:expr) => {{
+        const ELEMENT: i64 = $crate::macros::array_element($token, $t::MIN as i64, $t::MAX as i64);
+        ELEMENT as $t
+    }};
+
+    // Explicitly typed numbers.
+    (@call byte ($value:expr)) => {
+        $crate::Value::Byte($crate::macros::integer($value, "byte"))
+    };
+
+    (@call short ($value:expr)) => {
+        $crate::Value::Short($crate::macros::integer($value, "short"))
+    };
+
+    (@call int ($value:expr)) => {
+        $crate::Value::Int($crate::macros::integer($value, "int"))
+    };
+
+    (@call long ($value:expr)) => {
+        $crate::Value::Long($crate::macros::integer($value, "long"))
+    };
+
+    (@call float ($value:expr)) => {
+        $crate::Value::Float($value as f32)
+    };
+
+    (@call double ($value:expr)) => {
+        $crate::Value::Double($value as f64)
+    };
+
+    // Any other macro, eg format!(...).
+    (@call $mac:ident ($($args:tt)*)) => {
+        $crate::to_value(&$mac!($($args)*)).unwrap()
+    };
+
+    ($mac:ident ! ($($args:tt)*)) => {
+        nbt_internal!(@call $mac ($($args)*))
+    };
+
+    (- $value:literal) => {
+        nbt_internal!(@literal ::core::concat!("-", ::core::stringify!($value)))
+    };
+
+    // A negated expression, kept away from the literal rule below.
+    (- $($tt:tt)+) => {
+        $crate::to_value(&(- $($tt)+)).unwrap()
+    };
+
+    // Numbers with Rust or SNBT suffixes, strings, chars and bools.
+    ($value:literal) => {
+        nbt_internal!(@literal ::core::stringify!($value))
+    };
+
     // Any Serialize type: numbers, strings, struct literals, variables etc.
     ($other:expr) => {
         $crate::to_value(&$other).unwrap()
@@ -307,3 +547,447 @@ macro_rules! nbt_internal_vec {
         vec![$($content)*]
     };
 }
+
+// Support for the macros above. macro_rules cannot look inside a literal, and
+// SNBT suffixes like `5b` are not valid Rust, so literals are passed through
+// stringify! and parsed here instead. Numbers are parsed in a const fn so that
+// a bad literal is a compile error, like it would be in Rust.
+
+use std::fmt::Display;
+
+use crate::Value;
+
+/// A literal from the source of an `nbt!` call, parsed at compile time.
+/// Floats and text can't be finished in a const fn, so they keep their source
+/// text, which has been checked, and are finished by [`literal`].
+#[derive(Debug, Clone, Copy)]
+pub enum Literal {
+    Bool(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    I128(i128),
+    U128(u128),
+    Float(bool, &'static str),
+    Double(bool, &'static str),
+    Text(&'static str),
+}
+
+impl Literal {
+    /// Parse the source text of a literal. Rust literals get the same value
+    /// that [`to_value`][`crate::to_value`] would give them, and numbers can
+    /// also have an SNBT suffix: `b`, `s`, `l`, `f` or `d`.
+    ///
+    /// Panics if the literal is not valid or is out of range, which is a
+    /// compile error when called in a const.
+    pub const fn parse(token: &'static str) -> Self {
+        match parse_literal(token) {
+            Ok(literal) => literal,
+            Err(msg) => panic!("{}", msg),
+        }
+    }
+}
+
+/// Convert a parsed literal into a [`Value`].
+pub fn literal(literal: Literal) -> Value {
+    match literal {
+        Literal::Bool(v) => Value::Byte(v as i8),
+        Literal::Byte(v) => Value::Byte(v),
+        Literal::Short(v) => Value::Short(v),
+        Literal::Int(v) => Value::Int(v),
+        Literal::Long(v) => Value::Long(v),
+        Literal::I128(v) => crate::to_value(v).unwrap(),
+        Literal::U128(v) => crate::to_value(v).unwrap(),
+        // Parse floats at their own precision, since going through f64 would
+        // round twice.
+        Literal::Float(negative, body) => {
+            let value: f32 = body.replace('_', "").parse().unwrap();
+            Value::Float(if negative { -value } else { value })
+        }
+        Literal::Double(negative, body) => {
+            let value: f64 = body.replace('_', "").parse().unwrap();
+            Value::Double(if negative { -value } else { value })
+        }
+        Literal::Text(token) => match parse_text_literal(token) {
+            Ok(value) => value,
+            Err(msg) => panic!("invalid literal `{token}` in nbt!: {msg}"),
+        },
+    }
+}
+
+/// Parse the source text of a literal as an element of an NBT array, which
+/// must be an integer between `min` and `max`.
+pub const fn array_element(token: &'static str, min: i64, max: i64) -> i64 {
+    let value = match Literal::parse(token) {
+        Literal::Byte(v) => v as i64,
+        Literal::Short(v) => v as i64,
+        Literal::Int(v) => v as i64,
+        Literal::Long(v) => v,
+        _ => panic!("invalid literal in nbt!: expected an integer in array"),
+    };
+    if value < min || value > max {
+        panic!("invalid literal in nbt!: out of range for array element");
+    }
+    value
+}
+
+/// Convert the argument of `byte!` and friends, checking that it fits.
+pub fn integer<T: TryFrom<U>, U: Display + Copy>(value: U, tag: &str) -> T {
+    T::try_from(value).unwrap_or_else(|_| panic!("{value} out of range for {tag} in nbt!"))
+}
+
+const fn parse_literal(token: &'static str) -> Result<Literal, &'static str> {
+    let bytes = token.as_bytes();
+    if eq(bytes, b"true") {
+        return Ok(Literal::Bool(true));
+    }
+    if eq(bytes, b"false") {
+        return Ok(Literal::Bool(false));
+    }
+
+    // Strings and chars, possibly raw or bytes. These were checked by the
+    // Rust lexer, so are left until runtime.
+    let text = match bytes {
+        [b'b', rest @ ..] => rest,
+        _ => bytes,
+    };
+    if let [b'"' | b'\'' | b'r', ..] = text {
+        return Ok(Literal::Text(token));
+    }
+
+    parse_number(bytes)
+}
+
+/// Parse a string, char, byte string or byte literal.
+fn parse_text_literal(token: &str) -> Result<Value, String> {
+    if let Some(rest) = token.strip_prefix('b') {
+        if rest.starts_with(['"', '\'', 'r']) {
+            return match parse_text(rest)? {
+                Text::Str(s) => Ok(Value::List(
+                    crate::Tag::Byte,
+                    // Escapes in byte strings are bytes, not chars.
+                    s.chars().map(|c| Value::Byte(c as u8 as i8)).collect(),
+                )),
+                Text::Char(c) => Ok(Value::Byte(c as u8 as i8)),
+            };
+        }
+    }
+
+    match parse_text(token)? {
+        Text::Str(s) => Ok(Value::String(s)),
+        // Matches how chars serialize.
+        Text::Char(c) => Ok(Value::Int(c as i32)),
+    }
+}
+
+enum Text {
+    Str(String),
+    Char(char),
+}
+
+/// Parse a string or char literal, possibly raw, without any `b` prefix.
+fn parse_text(token: &str) -> Result<Text, String> {
+    if let Some(raw) = token.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..]
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix(&raw[..hashes]))
+            .and_then(|s| s.strip_suffix('"'))
+            .ok_or("unterminated raw string")?;
+        return Ok(Text::Str(body.to_owned()));
+    }
+
+    if let Some(body) = token.strip_prefix('\'') {
+        let body = body.strip_suffix('\'').ok_or("unterminated char")?;
+        let body = unescape(body)?;
+        let mut chars = body.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Text::Char(c)),
+            _ => Err("char must be one character".to_owned()),
+        };
+    }
+
+    let body = token
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or("unsupported literal")?;
+    Ok(Text::Str(unescape(body)?))
+}
+
+/// Resolve the escapes in the body of a Rust string or char literal.
+fn unescape(body: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('0') => out.push('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => out.push(c),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16).map_err(|_| "invalid \\x escape")?;
+                out.push(byte as char);
+            }
+            Some('u') => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or("invalid \\u escape")?;
+                let hex = rest[..end].trim_start_matches('{').replace('_', "");
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or("invalid \\u escape")?;
+                out.push(c);
+                chars = rest[end + 1..].chars();
+            }
+            // A backslash at the end of a line skips the following whitespace.
+            Some('\n') => chars = chars.as_str().trim_start().chars(),
+            _ => return Err("invalid escape".to_owned()),
+        }
+    }
+    Ok(out)
+}
+
+/// Rust suffixes, longest first so that eg `i16` is not mistaken for `i1`
+/// followed by junk.
+const RUST_SUFFIXES: &[&str] = &[
+    "i128", "u128", "isize", "usize", "i16", "i32", "i64", "u16", "u32", "u64", "f32", "f64", "i8",
+    "u8",
+];
+
+const fn parse_number(token: &'static [u8]) -> Result<Literal, &'static str> {
+    // A negative literal is stringified with a space after the minus.
+    let (negative, mut digits) = match token {
+        [b'-', rest @ ..] => (true, rest),
+        _ => (false, token),
+    };
+    while let [b' ', rest @ ..] = digits {
+        digits = rest;
+    }
+
+    let (radix, digits) = match digits {
+        [b'0', b'x', rest @ ..] => (16, rest),
+        [b'0', b'o', rest @ ..] => (8, rest),
+        [b'0', b'b', rest @ ..] => (2, rest),
+        _ => (10, digits),
+    };
+
+    let suffix = suffix(digits, radix);
+    let body = digits.split_at(digits.len() - suffix.len()).0;
+
+    let is_float = radix == 10
+        && (matches!(suffix, b"f32" | b"f64" | b"f" | b"F" | b"d" | b"D")
+            || (suffix.is_empty() && contains_float_marker(body)));
+    if is_float {
+        if !is_float_body(body) {
+            return Err("invalid literal in nbt!: invalid float");
+        }
+        let body = match std::str::from_utf8(body) {
+            Ok(body) => body,
+            Err(_) => return Err("invalid literal in nbt!: invalid float"),
+        };
+        return Ok(match suffix {
+            b"f32" | b"f" | b"F" => Literal::Float(negative, body),
+            _ => Literal::Double(negative, body),
+        });
+    }
+
+    let mut value: u128 = 0;
+    let mut any = false;
+    let mut i = 0;
+    while i < body.len() {
+        let c = body[i];
+        i += 1;
+        if c == b'_' {
+            continue;
+        }
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return Err("invalid literal in nbt!: invalid integer"),
+        };
+        if digit as u128 >= radix {
+            return Err("invalid literal in nbt!: invalid integer");
+        }
+        value = match value.checked_mul(radix) {
+            Some(v) => match v.checked_add(digit as u128) {
+                Some(v) => v,
+                None => return Err(out_of_range(suffix)),
+            },
+            None => return Err(out_of_range(suffix)),
+        };
+        any = true;
+    }
+    if !any {
+        return Err("invalid literal in nbt!: invalid integer");
+    }
+
+    if negative && matches!(suffix, [b'u', ..]) {
+        return Err("invalid literal in nbt!: unsigned literal cannot be negative");
+    }
+    if let b"u128" = suffix {
+        return Ok(Literal::U128(value));
+    }
+
+    let value = if value <= i128::MAX as u128 {
+        if negative {
+            -(value as i128)
+        } else {
+            value as i128
+        }
+    } else if negative && value == i128::MIN.unsigned_abs() {
+        i128::MIN
+    } else {
+        return Err(out_of_range(suffix));
+    };
+
+    let (min, max) = match suffix {
+        b"" | b"i32" => (i32::MIN as i128, i32::MAX as i128),
+        b"b" | b"B" | b"i8" => (i8::MIN as i128, i8::MAX as i128),
+        b"s" | b"S" | b"i16" => (i16::MIN as i128, i16::MAX as i128),
+        b"l" | b"L" | b"i64" | b"isize" => (i64::MIN as i128, i64::MAX as i128),
+        b"u8" => (0, u8::MAX as i128),
+        b"u16" => (0, u16::MAX as i128),
+        b"u32" => (0, u32::MAX as i128),
+        b"u64" | b"usize" => (0, u64::MAX as i128),
+        _ => (i128::MIN, i128::MAX),
+    };
+    if value < min || value > max {
+        return Err(out_of_range(suffix));
+    }
+
+    // Unsigned values keep their bits, like they do when serialized.
+    Ok(match suffix {
+        b"" | b"i32" => Literal::Int(value as i32),
+        b"b" | b"B" | b"i8" => Literal::Byte(value as i8),
+        b"s" | b"S" | b"i16" => Literal::Short(value as i16),
+        b"l" | b"L" | b"i64" | b"isize" => Literal::Long(value as i64),
+        b"u8" => Literal::Byte(value as u8 as i8),
+        b"u16" => Literal::Short(value as u16 as i16),
+        b"u32" => Literal::Int(value as u32 as i32),
+        b"u64" | b"usize" => Literal::Long(value as u64 as i64),
+        _ => Literal::I128(value),
+    })
+}
+
+/// Find the suffix of a number, without any radix prefix.
+const fn suffix(digits: &[u8], radix: u128) -> &'static [u8] {
+    let mut i = 0;
+    while i < RUST_SUFFIXES.len() {
+        let suffix = RUST_SUFFIXES[i].as_bytes();
+        // Hex digits can end in what looks like a float suffix, eg 0x1f32.
+        let float = matches!(suffix, b"f32" | b"f64");
+        if ends_with(digits, suffix) && !(float && radix != 10) {
+            return suffix;
+        }
+        i += 1;
+    }
+
+    // SNBT suffixes. Hex digits include some of these letters.
+    if radix == 10 {
+        if let [.., last @ (b'b' | b'B' | b's' | b'S' | b'l' | b'L' | b'f' | b'F' | b'd' | b'D')] =
+            digits
+        {
+            return match last {
+                b'b' => b"b",
+                b'B' => b"B",
+                b's' => b"s",
+                b'S' => b"S",
+                b'l' => b"l",
+                b'L' => b"L",
+                b'f' => b"f",
+                b'F' => b"F",
+                b'd' => b"d",
+                _ => b"D",
+            };
+        }
+    }
+    b""
+}
+
+const fn out_of_range(suffix: &[u8]) -> &'static str {
+    match suffix {
+        b"" | b"i32" => "invalid literal in nbt!: out of range for i32",
+        b"b" | b"B" | b"i8" => "invalid literal in nbt!: out of range for i8",
+        b"s" | b"S" | b"i16" => "invalid literal in nbt!: out of range for i16",
+        b"l" | b"L" | b"i64" | b"isize" => "invalid literal in nbt!: out of range for i64",
+        b"u8" => "invalid literal in nbt!: out of range for u8",
+        b"u16" => "invalid literal in nbt!: out of range for u16",
+        b"u32" => "invalid literal in nbt!: out of range for u32",
+        b"u64" | b"usize" => "invalid literal in nbt!: out of range for u64",
+        _ => "invalid literal in nbt!: out of range for i128",
+    }
+}
+
+/// Whether an unsuffixed number is a float.
+const fn contains_float_marker(body: &[u8]) -> bool {
+    let mut i = 0;
+    while i < body.len() {
+        if matches!(body[i], b'.' | b'e' | b'E') {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Whether the body of a float looks like `1_000.5e-3`, which Rust's float
+/// parsing will accept once the underscores are removed.
+const fn is_float_body(body: &[u8]) -> bool {
+    let mut i = 0;
+    let mut digits = 0;
+    while i < body.len() && matches!(body[i], b'0'..=b'9' | b'_') {
+        digits += (body[i] != b'_') as usize;
+        i += 1;
+    }
+    if digits == 0 {
+        return false;
+    }
+    if i < body.len() && body[i] == b'.' {
+        i += 1;
+        while i < body.len() && matches!(body[i], b'0'..=b'9' | b'_') {
+            i += 1;
+        }
+    }
+    if i < body.len() && matches!(body[i], b'e' | b'E') {
+        i += 1;
+        if i < body.len() && matches!(body[i], b'+' | b'-') {
+            i += 1;
+        }
+        let mut exponent = 0;
+        while i < body.len() && matches!(body[i], b'0'..=b'9' | b'_') {
+            exponent += (body[i] != b'_') as usize;
+            i += 1;
+        }
+        if exponent == 0 {
+            return false;
+        }
+    }
+    i == body.len()
+}
+
+const fn ends_with(s: &[u8], suffix: &[u8]) -> bool {
+    if suffix.len() > s.len() {
+        return false;
+    }
+    let start = s.len() - suffix.len();
+    let mut i = 0;
+    while i < suffix.len() {
+        if s[start + i] != suffix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && ends_with(a, b)
+}
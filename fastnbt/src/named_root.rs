@@ -0,0 +1,110 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeTupleStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub(crate) const NAMED_ROOT_TOKEN: &str = "__fastnbt_named_root";
+
+/// Some NBT along with the name of its root compound.
+///
+/// The root compound of NBT has a name, but it is usually the empty string and
+/// is ignored when deserializing. Some files do have a meaningful root name,
+/// such as older schematics. Wrapping the type you want in `NamedRoot`
+/// captures the name when deserializing and writes it back when serializing.
+/// This works for [`Value`][`crate::Value`] as well as your own types.
+///
+/// ```
+/// # use fastnbt::{NamedRoot, Value, nbt};
+/// let nbt = NamedRoot::new("Schematic", nbt!({ "Width": 16_i16 }));
+/// let bytes = fastnbt::to_bytes(&nbt).unwrap();
+///
+/// let back: NamedRoot<Value> = fastnbt::from_bytes(&bytes).unwrap();
+/// assert_eq!(back.name, "Schematic");
+/// assert_eq!(back.value, nbt.value);
+/// ```
+///
+/// Only the binary NBT format has root names. Converting a `NamedRoot` to a
+/// [`Value`][`crate::Value`] drops the name, and converting from a `Value`
+/// gives the empty name. `NamedRoot` can only be used as the outer most type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NamedRoot<T> {
+    /// The name of the root compound.
+    pub name: String,
+    /// The root compound.
+    pub value: T,
+}
+
+impl<T> NamedRoot<T> {
+    /// Create a new root with the given name.
+    pub fn new(name: impl Into<String>, value: T) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Discard the name, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Serialize> Serialize for NamedRoot<T> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut root = serializer.serialize_tuple_struct(NAMED_ROOT_TOKEN, 2)?;
+        root.serialize_field(&self.name)?;
+        root.serialize_field(&self.value)?;
+        root.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NamedRoot<T> {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct InnerVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for InnerVisitor<T> {
+            type Value = NamedRoot<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a named root compound")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let name = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let value = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+                Ok(NamedRoot { name, value })
+            }
+
+            // Formats without root names, such as Value, just give us the
+            // compound.
+            fn visit_map<A>(self, map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                Ok(NamedRoot {
+                    name: String::new(),
+                    value: T::deserialize(MapAccessDeserializer::new(map))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_tuple_struct(NAMED_ROOT_TOKEN, 2, InnerVisitor(PhantomData))
+    }
+}
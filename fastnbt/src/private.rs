@@ -0,0 +1,202 @@
+//! Support for the code generated by `#[derive(Nbt)]` in the `fastnbt-derive`
+//! crate. Nothing in here is part of the public API.
+
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Visitor},
+    ser::SerializeTupleStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    arrays::{i32s_as_bytes, i64s_as_bytes, i8s_as_bytes, serialize_array},
+    value::empty_list_token,
+    ByteArray, IntArray, LongArray, Tag, BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN,
+};
+
+pub use serde;
+
+/// Serializes an `i8` slice as a ByteArray.
+pub struct ByteArrayRef<'a>(pub &'a [i8]);
+
+impl Serialize for ByteArrayRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(BYTE_ARRAY_TOKEN, i8s_as_bytes(self.0), serializer)
+    }
+}
+
+/// Serializes an `i32` slice as an IntArray.
+pub struct IntArrayRef<'a>(pub &'a [i32]);
+
+impl Serialize for IntArrayRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(INT_ARRAY_TOKEN, i32s_as_bytes(self.0), serializer)
+    }
+}
+
+/// Serializes an `i64` slice as a LongArray.
+pub struct LongArrayRef<'a>(pub &'a [i64]);
+
+impl Serialize for LongArrayRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(LONG_ARRAY_TOKEN, i64s_as_bytes(self.0), serializer)
+    }
+}
+
+/// Deserializes a ByteArray into a `Vec`.
+pub struct ByteArrayBuf(pub Vec<i8>);
+
+impl<'de> Deserialize<'de> for ByteArrayBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ByteArray::deserialize(deserializer).map(|a| Self(a.into_inner()))
+    }
+}
+
+/// Deserializes an IntArray into a `Vec`.
+pub struct IntArrayBuf(pub Vec<i32>);
+
+impl<'de> Deserialize<'de> for IntArrayBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IntArray::deserialize(deserializer).map(|a| Self(a.into_inner()))
+    }
+}
+
+/// Deserializes a LongArray into a `Vec`.
+pub struct LongArrayBuf(pub Vec<i64>);
+
+impl<'de> Deserialize<'de> for LongArrayBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        LongArray::deserialize(deserializer).map(|a| Self(a.into_inner()))
+    }
+}
+
+/// A `bool` stored as a Byte, as `serde_helpers::bool_as_byte` does.
+pub struct ByteBool(pub bool);
+
+impl Serialize for ByteBool {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i8(self.0 as i8)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBool {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_byte_bool(deserializer).map(ByteBool)
+    }
+}
+
+/// Deserialize a `bool` stored as a Byte, accepting any integer and treating
+/// non-zero as `true`, or a real `bool`.
+pub(crate) fn deserialize_byte_bool<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    struct InnerVisitor;
+
+    impl<'de> Visitor<'de> for InnerVisitor {
+        type Value = bool;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a byte or bool")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(v != 0)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(v != 0)
+        }
+    }
+
+    // The binary NBT deserializer knows the tag, so asking for a bool lets it
+    // apply its Coercion option. Self-describing formats such as JSON would
+    // reject the integer we serialize as a bool, so let them give us what
+    // they have.
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(InnerVisitor)
+    } else {
+        deserializer.deserialize_bool(InnerVisitor)
+    }
+}
+
+/// Serializes a slice as a List with the given element tag, so that the tag
+/// is kept even when the list is empty.
+pub struct ListOf<'a, T>(pub Tag, pub &'a [T]);
+
+impl<T: Serialize> Serialize for ListOf<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.1.is_empty() {
+            serializer
+                .serialize_tuple_struct(empty_list_token(self.0), 0)?
+                .end()
+        } else {
+            self.1.serialize(serializer)
+        }
+    }
+}
+
+/// The key of a compound, borrowed from the input when possible.
+pub struct Key<'de>(pub Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for Key<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct InnerVisitor;
+
+        impl<'de> Visitor<'de> for InnerVisitor {
+            type Value = Key<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a field name")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Owned(v.to_owned())))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(InnerVisitor)
+    }
+}
+
+/// Get the value of a field missing from a compound. This is `None` for
+/// `Option` fields and an error for anything else, like serde's own derive.
+pub fn missing_field<'de, V, E>(field: &'static str) -> Result<V, E>
+where
+    V: Deserialize<'de>,
+    E: de::Error,
+{
+    struct MissingField<E>(&'static str, PhantomData<E>);
+
+    impl<'de, E: de::Error> Deserializer<'de> for MissingField<E> {
+        type Error = E;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, E> {
+            Err(E::missing_field(self.0))
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+            visitor.visit_none()
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    V::deserialize(MissingField(field, PhantomData))
+}
@@ -0,0 +1,151 @@
+use std::io::Cursor;
+
+use crate::{
+    error::{Error, Result},
+    from_bytes,
+    stream::{self, Parser},
+    to_bytes, NamedRoot, Value,
+};
+
+/// Check that some NBT comes back byte for byte the same after deserializing
+/// it into a [`Value`] and serializing it again.
+///
+/// The input is read as a [`NamedRoot<Value>`][`NamedRoot`] so that the name
+/// of the root compound is kept. If the output differs, the error says what
+/// was read and what was written at the first difference. Its
+/// [`path`][`Error::path`] is the NBT path of that value, eg
+/// `sections[3].block_states`, and its [`offset`][`Error::offset`] is where
+/// the value starts in the input. Errors from invalid input are returned as
+/// they are from [`from_bytes`].
+///
+/// See the [crate documentation](crate#round-trips) for what is guaranteed to
+/// round trip. This only supports Java Edition NBT.
+///
+/// ```
+/// # use fastnbt::{nbt, to_bytes};
+/// let bytes = to_bytes(&nbt!({ "Inventory": [] })).unwrap();
+/// assert!(fastnbt::roundtrip_check(&bytes).is_ok());
+///
+/// // Trailing data after the root compound is not kept.
+/// let mut bytes = bytes;
+/// bytes.push(0);
+/// let err = fastnbt::roundtrip_check(&bytes).unwrap_err();
+/// assert_eq!(err.offset(), Some(bytes.len() as u64 - 1));
+/// ```
+pub fn roundtrip_check(input: &[u8]) -> Result<()> {
+    let value: NamedRoot<Value> = from_bytes(input)?;
+    let output = to_bytes(&value)?;
+
+    if input == output.as_slice() {
+        Ok(())
+    } else {
+        Err(first_difference(input, &output))
+    }
+}
+
+/// Walk the input and output NBT side by side, and describe the first value
+/// whose bytes differ.
+fn first_difference(input: &[u8], output: &[u8]) -> Error {
+    let mut read = Parser::new(Cursor::new(input));
+    let mut written = Parser::new(Cursor::new(output));
+
+    // The path segment of each open compound and list, and for lists the
+    // index of the next element.
+    let mut layers: Vec<(String, Option<usize>)> = vec![];
+
+    loop {
+        let read_start = read.get_ref().position();
+        let written_start = written.get_ref().position();
+
+        let (r, w) = match (read.next(), written.next()) {
+            (Ok(r), Ok(w)) => (r, w),
+            (Err(e), _) | (_, Err(e)) => {
+                return Error::bespoke(format!("round trip: could not compare NBT: {}", e))
+                    .at(read_start, Some(join_path(&layers, None)))
+            }
+        };
+
+        let is_end = matches!(r, stream::Value::CompoundEnd | stream::Value::ListEnd);
+        let segment = match layers.last_mut() {
+            Some((_, Some(index))) if !is_end => {
+                *index += 1;
+                Some(format!("[{}]", *index - 1))
+            }
+            Some((_, None)) if !is_end => r.name().cloned().flatten(),
+            _ => None,
+        };
+
+        let read_bytes = &input[read_start as usize..read.get_ref().position() as usize];
+        let written_bytes = &output[written_start as usize..written.get_ref().position() as usize];
+
+        if read_bytes != written_bytes {
+            return Error::bespoke(format!(
+                "round trip changed the NBT: read {}, wrote {}",
+                describe(&r),
+                describe(&w)
+            ))
+            .at(read_start, Some(join_path(&layers, segment.as_deref())));
+        }
+
+        match r {
+            stream::Value::Compound(_) => layers.push((segment.unwrap_or_default(), None)),
+            stream::Value::List(..) => layers.push((segment.unwrap_or_default(), Some(0))),
+            stream::Value::CompoundEnd | stream::Value::ListEnd => {
+                layers.pop();
+                if layers.is_empty() {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let end = read.get_ref().position();
+    Error::bespoke(format!(
+        "round trip dropped {} bytes after the root compound",
+        input.len() as u64 - end
+    ))
+    .at(end, None)
+}
+
+fn join_path(layers: &[(String, Option<usize>)], last: Option<&str>) -> String {
+    let mut path = String::new();
+
+    // The first layer is the root compound, whose name is not part of paths.
+    let segments = layers.iter().skip(1).map(|(s, _)| s.as_str()).chain(last);
+    for segment in segments {
+        if !segment.starts_with('[') && !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(segment);
+    }
+
+    path
+}
+
+fn describe(v: &stream::Value) -> String {
+    use stream::Value::*;
+
+    let desc = match v {
+        CompoundEnd => return "end of compound".to_string(),
+        ListEnd => return "end of list".to_string(),
+        Byte(_, v) => format!("byte {}", v),
+        Short(_, v) => format!("short {}", v),
+        Int(_, v) => format!("int {}", v),
+        Long(_, v) => format!("long {}", v),
+        // Show the bits, as NaNs with different payloads all print as NaN.
+        Float(_, v) => format!("float {} ({:#010x})", v, v.to_bits()),
+        Double(_, v) => format!("double {} ({:#018x})", v, v.to_bits()),
+        ByteArray(_, v) => format!("byte-array of length {}", v.len()),
+        String(_, v) => format!("string {:?}", v),
+        List(_, tag, len) => format!("list of {} {}", len, tag),
+        Compound(_) => "compound".to_string(),
+        IntArray(_, v) => format!("int-array of length {}", v.len()),
+        LongArray(_, v) => format!("long-array of length {}", v.len()),
+    };
+
+    match v.name().cloned().flatten() {
+        Some(name) => format!("{} named {:?}", desc, name),
+        None => desc,
+    }
+}
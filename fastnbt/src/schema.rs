@@ -0,0 +1,565 @@
+//! Check that NBT has the shape you expect before using it, eg to reject
+//! malformed item or entity NBT.
+//!
+//! A [`Schema`] describes a value: its type, and depending on the type, the
+//! range of numbers allowed, the schema of list elements, and which fields a
+//! compound has. Validating gives every [`Violation`] found rather than
+//! stopping at the first, each with the path to the offending value in the
+//! format of [`Value::pointer`].
+//!
+//! ```rust
+//! use fastnbt::{nbt, schema::{Schema, ViolationKind}};
+//!
+//! let item = Schema::compound()
+//!     .required("id", Schema::string())
+//!     .required("Count", Schema::byte().range(1, 64))
+//!     .optional("tag", Schema::compound());
+//! let inventory = Schema::compound()
+//!     .required("Inventory", Schema::list(item));
+//!
+//! let player = nbt!({"Inventory": [{"id": "minecraft:stone", "Count": 100b}, {"Count": 1b}]});
+//! let violations = inventory.validate(&player);
+//!
+//! assert_eq!(violations.len(), 2);
+//! assert_eq!(violations[0].path(), "/Inventory/0/Count");
+//! assert_eq!(violations[0].kind(), ViolationKind::OutOfRange);
+//! assert_eq!(violations[1].to_string(), "/Inventory/1/id: missing required string");
+//! ```
+//!
+//! Raw NBT can be validated without deserializing it first, using
+//! [`Schema::validate_bytes`] or [`Schema::validate_reader`]. For untrusted
+//! input, use [`Schema::validate_reader_with_opts`] to limit how much is
+//! read.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+
+use crate::{
+    stream::{self, Parser},
+    value::escape_pointer_part,
+    DeOpts, Tag, Value,
+};
+
+/// The expected shape of a value. See the [module][`crate::schema`]
+/// documentation.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    /// The type required, or `None` for any type.
+    tag: Option<Tag>,
+
+    /// Inclusive range for numbers, or for the elements of NBT arrays.
+    range: Option<(f64, f64)>,
+
+    /// Schema for the elements of a list.
+    element: Option<Box<Schema>>,
+
+    /// Known fields of a compound.
+    fields: BTreeMap<String, Field>,
+
+    /// Whether fields not in `fields` are violations.
+    deny_unknown_fields: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    schema: Schema,
+    required: bool,
+}
+
+impl Schema {
+    /// A value of any type. Nothing inside it is checked.
+    pub fn any() -> Self {
+        Self {
+            tag: None,
+            range: None,
+            element: None,
+            fields: BTreeMap::new(),
+            deny_unknown_fields: false,
+        }
+    }
+
+    /// A value with the given tag. The methods for each tag such as
+    /// [`Schema::int`] are usually clearer.
+    pub fn of(tag: Tag) -> Self {
+        Self {
+            tag: Some(tag),
+            ..Self::any()
+        }
+    }
+
+    /// A Byte.
+    pub fn byte() -> Self {
+        Self::of(Tag::Byte)
+    }
+
+    /// A Short.
+    pub fn short() -> Self {
+        Self::of(Tag::Short)
+    }
+
+    /// An Int.
+    pub fn int() -> Self {
+        Self::of(Tag::Int)
+    }
+
+    /// A Long.
+    pub fn long() -> Self {
+        Self::of(Tag::Long)
+    }
+
+    /// A Float.
+    pub fn float() -> Self {
+        Self::of(Tag::Float)
+    }
+
+    /// A Double.
+    pub fn double() -> Self {
+        Self::of(Tag::Double)
+    }
+
+    /// A String.
+    pub fn string() -> Self {
+        Self::of(Tag::String)
+    }
+
+    /// A ByteArray.
+    pub fn byte_array() -> Self {
+        Self::of(Tag::ByteArray)
+    }
+
+    /// An IntArray.
+    pub fn int_array() -> Self {
+        Self::of(Tag::IntArray)
+    }
+
+    /// A LongArray.
+    pub fn long_array() -> Self {
+        Self::of(Tag::LongArray)
+    }
+
+    /// A List whose elements all match `element`. If `element` has a type,
+    /// a non-empty list of some other type is a single violation rather than
+    /// one for every element.
+    pub fn list(element: Schema) -> Self {
+        Self {
+            element: Some(Box::new(element)),
+            ..Self::of(Tag::List)
+        }
+    }
+
+    /// A Compound. Add fields with [`Schema::required`] and
+    /// [`Schema::optional`]. Other fields are allowed unless
+    /// [`Schema::deny_unknown_fields`] is used.
+    pub fn compound() -> Self {
+        Self::of(Tag::Compound)
+    }
+
+    /// Only allow numbers from `min` to `max` inclusive. For NBT arrays this
+    /// applies to each element. Longs are compared as `f64`, so bounds beyond
+    /// 2<sup>53</sup> are not exact.
+    pub fn range(mut self, min: impl Into<f64>, max: impl Into<f64>) -> Self {
+        self.range = Some((min.into(), max.into()));
+        self
+    }
+
+    /// Add a field that a compound must have.
+    pub fn required(mut self, name: impl Into<String>, schema: Schema) -> Self {
+        let field = Field {
+            schema,
+            required: true,
+        };
+        self.fields.insert(name.into(), field);
+        self
+    }
+
+    /// Add a field that a compound may have.
+    pub fn optional(mut self, name: impl Into<String>, schema: Schema) -> Self {
+        let field = Field {
+            schema,
+            required: false,
+        };
+        self.fields.insert(name.into(), field);
+        self
+    }
+
+    /// Treat fields of a compound that were not added with
+    /// [`Schema::required`] or [`Schema::optional`] as violations.
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.deny_unknown_fields = true;
+        self
+    }
+
+    /// Check a [`Value`], returning every violation found. The value is valid
+    /// if there are none. Fields of compounds are checked in order of their
+    /// names, and missing fields are reported after the others.
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_value(value, "", &mut violations);
+        violations
+    }
+
+    /// Check uncompressed NBT bytes, returning every violation found. Errors
+    /// are only returned for NBT that cannot be parsed.
+    ///
+    /// ```rust
+    /// use fastnbt::schema::Schema;
+    ///
+    /// let bytes = fastnbt::to_bytes(&fastnbt::nbt!({"DataVersion": "3465"})).unwrap();
+    /// let schema = Schema::compound().required("DataVersion", Schema::int());
+    /// let violations = schema.validate_bytes(&bytes).unwrap();
+    /// assert_eq!(violations[0].to_string(), "/DataVersion: expected int, found string");
+    /// ```
+    pub fn validate_bytes(&self, input: &[u8]) -> stream::Result<Vec<Violation>> {
+        self.validate_reader(input)
+    }
+
+    /// Check the uncompressed NBT read from `reader` with a
+    /// [`stream::Parser`], without building up a [`Value`]. Only the first
+    /// root compound is read. Violations are in the order they appear in the
+    /// document, rather than with compound keys sorted like
+    /// [`Schema::validate`].
+    ///
+    /// The elements of NBT arrays are only read when the schema has a range
+    /// for them, and strings are never read, so that they are skipped past
+    /// without being stored. The input is not checked against any limits,
+    /// use [`Schema::validate_reader_with_opts`] for that.
+    pub fn validate_reader<R: Read>(&self, reader: R) -> stream::Result<Vec<Violation>> {
+        self.validate_reader_with_opts(reader, DeOpts::default())
+    }
+
+    /// Similar to [`Schema::validate_reader`], but checks the input against
+    /// the limits in `opts`, as [`Parser::with_opts`] does. Input over a
+    /// limit is an error with the kind
+    /// [`LimitExceeded`][stream::ErrorKind::LimitExceeded], rather than a
+    /// violation.
+    ///
+    /// ```rust
+    /// use fastnbt::{nbt, schema::Schema, stream::ErrorKind, DeOpts};
+    ///
+    /// let bytes = fastnbt::to_bytes(&nbt!({"a": {"b": {"c": {}}}})).unwrap();
+    /// let opts = DeOpts::new().max_depth(3);
+    /// let err = Schema::compound()
+    ///     .validate_reader_with_opts(bytes.as_slice(), opts)
+    ///     .unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::LimitExceeded));
+    /// ```
+    pub fn validate_reader_with_opts<R: Read>(
+        &self,
+        reader: R,
+        opts: DeOpts,
+    ) -> stream::Result<Vec<Violation>> {
+        let mut parser = Parser::with_opts(reader, opts);
+        let mut violations = Vec::new();
+        let mut layers: Vec<Layer> = Vec::new();
+
+        loop {
+            let skip = match layers.last() {
+                Some(Layer {
+                    schema: Some(schema),
+                    list,
+                    ..
+                }) => !schema.reads_arrays(*list),
+                _ => true,
+            };
+            parser.set_skip_payloads(skip);
+            let event = parser.next()?;
+
+            let Some(tag) = event.tag() else {
+                let layer = layers.pop().expect("parser checks ends match starts");
+                if let (Some(schema), false) = (layer.schema, layer.list) {
+                    schema.check_missing(
+                        |name| layer.seen.iter().any(|s| s == name),
+                        &layer.path,
+                        &mut violations,
+                    );
+                }
+                if layers.is_empty() {
+                    return Ok(violations);
+                }
+                continue;
+            };
+
+            let (schema, path) = match layers.last_mut() {
+                None => (Some(self), String::new()),
+                Some(layer) if layer.list => {
+                    layer.len += 1;
+                    let path = format!("{}/{}", layer.path, layer.len - 1);
+                    (layer.schema.and_then(|s| s.element.as_deref()), path)
+                }
+                Some(layer) => {
+                    let name = event.name().cloned().flatten().unwrap_or_default();
+                    let path = format!("{}/{}", layer.path, escape_pointer_part(&name));
+                    let schema = layer
+                        .schema
+                        .and_then(|s| s.field(&name, &path, &mut violations));
+                    layer.seen.push(name);
+                    (schema, path)
+                }
+            };
+
+            let list_element = match &event {
+                stream::Value::List(_, element, len) if *len > 0 => Some(*element),
+                _ => None,
+            };
+            let schema = schema.filter(|s| s.check_type(tag, list_element, &path, &mut violations));
+
+            let mut numbers = |values: &mut dyn Iterator<Item = f64>| {
+                if let Some(schema) = schema {
+                    for (i, v) in values.enumerate() {
+                        schema.check_number(v, || format!("{path}/{i}"), &mut violations);
+                    }
+                }
+            };
+
+            match &event {
+                stream::Value::Compound(_) | stream::Value::List(..) => {
+                    layers.push(Layer {
+                        schema,
+                        path,
+                        list: matches!(event, stream::Value::List(..)),
+                        len: 0,
+                        seen: Vec::new(),
+                    });
+                    continue;
+                }
+                stream::Value::ByteArray(_, a) => numbers(&mut a.iter().map(|v| *v as f64)),
+                stream::Value::IntArray(_, a) => numbers(&mut a.iter().map(|v| *v as f64)),
+                stream::Value::LongArray(_, a) => numbers(&mut a.iter().map(|v| *v as f64)),
+                _ => {}
+            }
+
+            if let (Some(schema), Some(v)) = (schema, number(&event)) {
+                schema.check_number(v, || path.clone(), &mut violations);
+            }
+        }
+    }
+
+    /// Whether checking the contents of this list or compound needs the
+    /// elements of NBT arrays in it.
+    fn reads_arrays(&self, list: bool) -> bool {
+        let has_range = |s: &Schema| {
+            s.range.is_some()
+                && matches!(
+                    s.tag,
+                    None | Some(Tag::ByteArray | Tag::IntArray | Tag::LongArray)
+                )
+        };
+
+        if list {
+            self.element.as_deref().is_some_and(has_range)
+        } else {
+            self.fields.values().any(|f| has_range(&f.schema))
+        }
+    }
+
+    fn validate_value(&self, value: &Value, path: &str, violations: &mut Vec<Violation>) {
+        let list_element = match value {
+            Value::List(element, values) if !values.is_empty() => Some(*element),
+            _ => None,
+        };
+        if !self.check_type(value.tag(), list_element, path, violations) {
+            return;
+        }
+
+        let mut numbers = |values: &mut dyn Iterator<Item = f64>| {
+            for (i, v) in values.enumerate() {
+                self.check_number(v, || format!("{path}/{i}"), violations);
+            }
+        };
+
+        match value {
+            Value::Byte(v) => self.check_number(*v as f64, || path.to_owned(), violations),
+            Value::Short(v) => self.check_number(*v as f64, || path.to_owned(), violations),
+            Value::Int(v) => self.check_number(*v as f64, || path.to_owned(), violations),
+            Value::Long(v) => self.check_number(*v as f64, || path.to_owned(), violations),
+            Value::Float(v) => self.check_number(*v as f64, || path.to_owned(), violations),
+            Value::Double(v) => self.check_number(*v, || path.to_owned(), violations),
+            Value::ByteArray(a) => numbers(&mut a.iter().map(|v| *v as f64)),
+            Value::IntArray(a) => numbers(&mut a.iter().map(|v| *v as f64)),
+            Value::LongArray(a) => numbers(&mut a.iter().map(|v| *v as f64)),
+            Value::String(_) => {}
+            Value::List(_, values) => {
+                if let Some(element) = &self.element {
+                    for (i, v) in values.iter().enumerate() {
+                        element.validate_value(v, &format!("{path}/{i}"), violations);
+                    }
+                }
+            }
+            Value::Compound(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                for (name, v) in entries {
+                    let path = format!("{}/{}", path, escape_pointer_part(name));
+                    if let Some(schema) = self.field(name, &path, violations) {
+                        schema.validate_value(v, &path, violations);
+                    }
+                }
+                self.check_missing(|name| map.contains_key(name), path, violations);
+            }
+        }
+    }
+
+    /// Check the type of a value, and the type of its elements if it is a
+    /// non-empty list. Returns whether to carry on checking inside it.
+    fn check_type(
+        &self,
+        tag: Tag,
+        list_element: Option<Tag>,
+        path: &str,
+        violations: &mut Vec<Violation>,
+    ) -> bool {
+        if let Some(expected) = self.tag {
+            if expected != tag {
+                violations.push(Violation::new(
+                    path.to_owned(),
+                    ViolationKind::WrongType,
+                    format!("expected {expected}, found {tag}"),
+                ));
+                return false;
+            }
+        }
+
+        let expected_element = self.element.as_ref().and_then(|e| e.tag);
+        if let (Some(expected), Some(found)) = (expected_element, list_element) {
+            if expected != found {
+                violations.push(Violation::new(
+                    path.to_owned(),
+                    ViolationKind::WrongType,
+                    format!("expected list of {expected}, found list of {found}"),
+                ));
+                return false;
+            }
+        }
+        true
+    }
+
+    fn check_number(&self, v: f64, path: impl FnOnce() -> String, violations: &mut Vec<Violation>) {
+        if let Some((min, max)) = self.range {
+            // Written this way so that NaN is out of range.
+            if !(v >= min && v <= max) {
+                violations.push(Violation::new(
+                    path(),
+                    ViolationKind::OutOfRange,
+                    format!("{v} is not between {min} and {max}"),
+                ));
+            }
+        }
+    }
+
+    /// Get the schema for a field of a compound, if it is known.
+    fn field(&self, name: &str, path: &str, violations: &mut Vec<Violation>) -> Option<&Schema> {
+        match self.fields.get(name) {
+            Some(field) => Some(&field.schema),
+            None => {
+                if self.deny_unknown_fields {
+                    violations.push(Violation::new(
+                        path.to_owned(),
+                        ViolationKind::Unknown,
+                        "unknown field".to_owned(),
+                    ));
+                }
+                None
+            }
+        }
+    }
+
+    fn check_missing(
+        &self,
+        present: impl Fn(&str) -> bool,
+        path: &str,
+        violations: &mut Vec<Violation>,
+    ) {
+        for (name, field) in &self.fields {
+            if field.required && !present(name) {
+                let expected = match field.schema.tag {
+                    Some(tag) => tag.to_string(),
+                    None => "field".to_owned(),
+                };
+                violations.push(Violation::new(
+                    format!("{}/{}", path, escape_pointer_part(name)),
+                    ViolationKind::Missing,
+                    format!("missing required {expected}"),
+                ));
+            }
+        }
+    }
+}
+
+/// A compound or list being validated by [`Schema::validate_reader`].
+struct Layer<'s> {
+    /// `None` if the contents are not checked.
+    schema: Option<&'s Schema>,
+    path: String,
+    list: bool,
+
+    /// Number of elements seen so far, for lists.
+    len: usize,
+
+    /// Names of the fields seen so far, for compounds.
+    seen: Vec<String>,
+}
+
+fn number(event: &stream::Value) -> Option<f64> {
+    Some(match event {
+        stream::Value::Byte(_, v) => *v as f64,
+        stream::Value::Short(_, v) => *v as f64,
+        stream::Value::Int(_, v) => *v as f64,
+        stream::Value::Long(_, v) => *v as f64,
+        stream::Value::Float(_, v) => *v as f64,
+        stream::Value::Double(_, v) => *v,
+        _ => return None,
+    })
+}
+
+/// The kind of a [`Violation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// A required field of a compound is missing.
+    Missing,
+
+    /// A compound has a field that is not in the schema, when unknown fields
+    /// are denied.
+    Unknown,
+
+    /// A value, or the elements of a list, are the wrong type.
+    WrongType,
+
+    /// A number is outside of the range allowed.
+    OutOfRange,
+}
+
+/// A way in which a value does not match a [`Schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    path: String,
+    kind: ViolationKind,
+    msg: String,
+}
+
+impl Violation {
+    fn new(path: String, kind: ViolationKind, msg: String) -> Self {
+        Self { path, kind, msg }
+    }
+
+    /// The path of the value, in the format of [`Value::pointer`]. This is
+    /// empty for the root value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The kind of violation.
+    pub fn kind(&self) -> ViolationKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.as_str() {
+            "" => write!(f, "(root): {}", self.msg),
+            path => write!(f, "{}: {}", path, self.msg),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use byteorder::{BigEndian, ByteOrder, NativeEndian, WriteBytesExt};
+use byteorder::{ByteOrder, NativeEndian};
 use serde::ser::Impossible;
 
 use crate::{error::Error, Tag};
@@ -80,27 +80,27 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
         match self.tag {
             Tag::ByteArray => {
-                self.ser.writer.write_len(v.len())?;
+                self.ser.writer.write_len(v.len(), self.ser.opts.flavour)?;
                 self.ser.writer.write_all(v)?;
             }
             Tag::IntArray => {
                 let stride = 4;
                 let len = v.len() / stride;
-                self.ser.writer.write_len(len)?;
+                self.ser.writer.write_len(len, self.ser.opts.flavour)?;
 
                 for chunk in v.chunks(stride) {
                     let el = NativeEndian::read_i32(chunk);
-                    self.ser.writer.write_i32::<BigEndian>(el)?;
+                    self.ser.writer.write_nbt_int(el, self.ser.opts.flavour)?;
                 }
             }
             Tag::LongArray => {
                 let stride = 8;
                 let len = v.len() / stride;
-                self.ser.writer.write_len(len)?;
+                self.ser.writer.write_len(len, self.ser.opts.flavour)?;
 
                 for chunk in v.chunks(stride) {
                     let el = NativeEndian::read_i64(chunk);
-                    self.ser.writer.write_i64::<BigEndian>(el)?;
+                    self.ser.writer.write_nbt_long(el, self.ser.opts.flavour)?;
                 }
             }
             _ => panic!(),
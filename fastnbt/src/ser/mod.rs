@@ -15,8 +15,9 @@
 //! as big endian i.e. the most significant bit (and int) is first.
 //!
 mod array_serializer;
-mod name_serializer;
+pub(crate) mod name_serializer;
 mod serializer;
 mod write_nbt;
 
 pub use serializer::*;
+pub(crate) use write_nbt::SizeCounter;
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::WriteBytesExt;
 use serde::{
     ser::{self, Impossible, SerializeTuple},
     serde_if_integer128, Serialize,
@@ -8,7 +8,9 @@ use serde::{
 
 use crate::{
     error::{Error, Result},
-    IntArray, Tag,
+    named_root::NAMED_ROOT_TOKEN,
+    value::empty_list_tag,
+    IntArray, SerOpts, Tag,
 };
 
 use super::{
@@ -17,6 +19,7 @@ use super::{
 
 #[derive(Debug)]
 pub(crate) enum State {
+    Root { name: Option<String> },
     ListStart { len: usize },
     ListRest,
     Compound { current_field: String },
@@ -26,25 +29,37 @@ pub(crate) enum State {
 pub(crate) enum TupleState {
     Start { len: usize },
     Rest,
+    RootName,
+    RootValue,
 }
 
 pub struct Serializer<W: Write> {
     pub(crate) writer: W,
     pub(crate) state: State,
+    pub(crate) opts: SerOpts,
 }
 
 impl<'a, W: Write> Serializer<W> {
     fn try_write_header(&mut self, tag: Tag) -> Result<()> {
         match &mut self.state {
+            State::Root { name } => {
+                self.writer.write_tag(tag)?;
+                if !self.opts.network_nbt {
+                    let name = name.as_deref().unwrap_or("");
+                    self.writer
+                        .write_size_prefixed_str(name, self.opts.flavour)?;
+                }
+            }
             State::ListStart { len } => {
                 self.writer.write_tag(tag)?;
-                self.writer.write_len(*len)?;
+                self.writer.write_len(*len, self.opts.flavour)?;
                 self.state = State::ListRest;
             }
             State::ListRest => {}
             State::Compound { current_field } => {
                 self.writer.write_tag(tag)?;
-                self.writer.write_size_prefixed_str(current_field)?;
+                self.writer
+                    .write_size_prefixed_str(current_field, self.opts.flavour)?;
             }
         }
         Ok(())
@@ -76,19 +91,19 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_i16(self, v: i16) -> Result<()> {
         self.try_write_header(Tag::Short)?;
-        self.writer.write_i16::<BigEndian>(v)?;
+        self.writer.write_nbt_short(v, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         self.try_write_header(Tag::Int)?;
-        self.writer.write_i32::<BigEndian>(v)?;
+        self.writer.write_nbt_int(v, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
         self.try_write_header(Tag::Long)?;
-        self.writer.write_i64::<BigEndian>(v)?;
+        self.writer.write_nbt_long(v, self.opts.flavour)?;
         Ok(())
     }
 
@@ -115,50 +130,50 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_u16(self, v: u16) -> Result<()> {
         self.try_write_header(Tag::Short)?;
-        self.writer.write_u16::<BigEndian>(v)?;
+        self.writer.write_nbt_short(v as i16, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_u32(self, v: u32) -> Result<()> {
         self.try_write_header(Tag::Int)?;
-        self.writer.write_u32::<BigEndian>(v)?;
+        self.writer.write_nbt_int(v as i32, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
         self.try_write_header(Tag::Long)?;
-        self.writer.write_u64::<BigEndian>(v)?;
+        self.writer.write_nbt_long(v as i64, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         self.try_write_header(Tag::Float)?;
-        self.writer.write_f32::<BigEndian>(v)?;
+        self.writer.write_nbt_float(v, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
         self.try_write_header(Tag::Double)?;
-        self.writer.write_f64::<BigEndian>(v)?;
+        self.writer.write_nbt_double(v, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
         self.try_write_header(Tag::Int)?;
-        self.writer.write_u32::<BigEndian>(v as u32)?;
+        self.writer.write_nbt_int(v as i32, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
         self.try_write_header(Tag::String)?;
-        self.writer.write_size_prefixed_str(v)?;
+        self.writer.write_size_prefixed_str(v, self.opts.flavour)?;
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         self.try_write_header(Tag::List)?;
         self.writer.write_tag(Tag::Byte)?;
-        self.writer.write_len(v.len())?;
+        self.writer.write_len(v.len(), self.opts.flavour)?;
         self.writer.write_all(v)?;
         Ok(())
     }
@@ -190,7 +205,8 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
         variant: &'static str,
     ) -> Result<()> {
         self.try_write_header(Tag::String)?;
-        self.writer.write_size_prefixed_str(variant)
+        self.writer
+            .write_size_prefixed_str(variant, self.opts.flavour)
     }
 
     fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
@@ -257,7 +273,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
             // A list of end tags seems to be the way to go.
 
             self.writer.write_tag(Tag::End)?;
-            self.writer.write_u32::<BigEndian>(0)?; // ie len
+            self.writer.write_len(0, self.opts.flavour)?;
         }
 
         Ok(SerializerTuple {
@@ -268,9 +284,33 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
+        if name == NAMED_ROOT_TOKEN {
+            return match self.state {
+                State::Root { .. } => Ok(SerializerTuple {
+                    ser: self,
+                    state: TupleState::RootName,
+                }),
+                _ => Err(Error::bespoke(
+                    "NamedRoot can only be used for the root compound".to_string(),
+                )),
+            };
+        }
+
+        if let Some(tag) = empty_list_tag(name) {
+            // An empty Value::List that knows its element type.
+            self.try_write_header(Tag::List)?;
+            self.writer.write_tag(tag)?;
+            self.writer.write_len(0, self.opts.flavour)?;
+
+            return Ok(SerializerTuple {
+                ser: self,
+                state: TupleState::Start { len: 0 },
+            });
+        }
+
         self.serialize_tuple(len)
     }
 
@@ -407,6 +447,21 @@ impl<'a, W: 'a + Write> serde::ser::SerializeTuple for SerializerTuple<'a, W> {
                 self.ser.state = State::ListRest;
                 value.serialize(&mut *self.ser)
             }
+            TupleState::RootName => {
+                let mut name = Vec::new();
+                value.serialize(&mut NameSerializer { name: &mut name })?;
+
+                self.ser.state = State::Root {
+                    name: Some(
+                        cesu8::from_java_cesu8(&name)
+                            .map_err(|_| Error::bespoke("root name was invalid cesu8".to_string()))?
+                            .to_string(),
+                    ),
+                };
+                self.state = TupleState::RootValue;
+                Ok(())
+            }
+            TupleState::RootValue => value.serialize(&mut *self.ser),
         }
     }
 
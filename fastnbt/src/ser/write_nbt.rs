@@ -1,10 +1,10 @@
 use std::convert::TryInto;
 use std::io::Write;
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 
 use crate::error::{Error, Result};
-use crate::Tag;
+use crate::{varint, Flavour, Tag};
 
 pub(crate) trait WriteNbt: Write {
     fn write_tag(&mut self, tag: Tag) -> Result<()> {
@@ -12,22 +12,107 @@ pub(crate) trait WriteNbt: Write {
         Ok(())
     }
 
-    fn write_size_prefixed_str(&mut self, key: &str) -> Result<()> {
-        let key = cesu8::to_java_cesu8(key);
-        let len_bytes = key.len() as u16;
-        self.write_u16::<BigEndian>(len_bytes)?;
-        self.write_all(&key)?;
+    fn write_size_prefixed_str(&mut self, key: &str, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => {
+                let key = cesu8::to_java_cesu8(key);
+                let len_bytes = str_len(key.len())?;
+                self.write_u16::<BigEndian>(len_bytes)?;
+                self.write_all(&key)?;
+            }
+            Flavour::BedrockLittleEndian => {
+                let len_bytes = str_len(key.len())?;
+                self.write_u16::<LittleEndian>(len_bytes)?;
+                self.write_all(key.as_bytes())?;
+            }
+            Flavour::BedrockNetwork => {
+                varint::write_var_u32(
+                    self,
+                    key.len()
+                        .try_into()
+                        .map_err(|_| Error::bespoke("string too long".to_owned()))?,
+                )?;
+                self.write_all(key.as_bytes())?;
+            }
+        }
         Ok(())
     }
 
-    fn write_len(&mut self, len: usize) -> Result<()> {
-        self.write_u32::<BigEndian>(
-            len.try_into()
-                .map_err(|_| Error::bespoke("len too large".to_owned()))?,
-        )?;
+    fn write_len(&mut self, len: usize, flavour: Flavour) -> Result<()> {
+        let len: i32 = len
+            .try_into()
+            .map_err(|_| Error::bespoke("len too large".to_owned()))?;
 
+        self.write_nbt_int(len, flavour)
+    }
+
+    fn write_nbt_short(&mut self, v: i16, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => self.write_i16::<BigEndian>(v)?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.write_i16::<LittleEndian>(v)?
+            }
+        }
+        Ok(())
+    }
+
+    fn write_nbt_int(&mut self, v: i32, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => self.write_i32::<BigEndian>(v)?,
+            Flavour::BedrockLittleEndian => self.write_i32::<LittleEndian>(v)?,
+            Flavour::BedrockNetwork => varint::write_var_i32(self, v)?,
+        }
+        Ok(())
+    }
+
+    fn write_nbt_long(&mut self, v: i64, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => self.write_i64::<BigEndian>(v)?,
+            Flavour::BedrockLittleEndian => self.write_i64::<LittleEndian>(v)?,
+            Flavour::BedrockNetwork => varint::write_var_i64(self, v)?,
+        }
+        Ok(())
+    }
+
+    fn write_nbt_float(&mut self, v: f32, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => self.write_f32::<BigEndian>(v)?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.write_f32::<LittleEndian>(v)?
+            }
+        }
         Ok(())
     }
+
+    fn write_nbt_double(&mut self, v: f64, flavour: Flavour) -> Result<()> {
+        match flavour {
+            Flavour::JavaBigEndian => self.write_f64::<BigEndian>(v)?,
+            Flavour::BedrockLittleEndian | Flavour::BedrockNetwork => {
+                self.write_f64::<LittleEndian>(v)?
+            }
+        }
+        Ok(())
+    }
+}
+
+fn str_len(len: usize) -> Result<u16> {
+    len.try_into()
+        .map_err(|_| Error::bespoke("string too long".to_owned()))
 }
 
 impl<T> WriteNbt for T where T: Write {}
+
+/// A writer that only counts the bytes written to it.
+#[derive(Debug, Default)]
+pub(crate) struct SizeCounter(pub(crate) usize);
+
+impl std::io::Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
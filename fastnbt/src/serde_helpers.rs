@@ -0,0 +1,191 @@
+//! Helpers for the ways Minecraft encodes some types in NBT, for use with
+//! serde's `with` attribute. This module requires the `serde_helpers` feature.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Beehive {
+//!     #[serde(with = "fastnbt::serde_helpers::bool_as_byte")]
+//!     on_ground: bool,
+//!     #[serde(with = "fastnbt::serde_helpers::position")]
+//!     flower_pos: [i32; 3],
+//! }
+//! ```
+//!
+//! Helpers for [`uuid::Uuid`][::uuid::Uuid] are in the [`uuid`] module, which
+//! requires the `uuid` feature.
+
+/// (De)serialize a `bool` as a Byte, which is how Minecraft stores booleans.
+/// Serializes to 0 or 1. Deserializing accepts any integer, treating non-zero
+/// as `true`, as well as a real `bool` such as `true` in SNBT. With
+/// [`Coercion::Strict`][crate::Coercion::Strict] it must be a Byte of 0 or 1.
+pub mod bool_as_byte {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S>(v: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i8(*v as i8)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::__private::deserialize_byte_bool(deserializer)
+    }
+}
+
+/// (De)serialize a block position `[i32; 3]` of x, y and z as an IntArray,
+/// which is how Minecraft has stored positions since 1.20.5.
+pub mod position {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::IntArray;
+
+    pub fn serialize<S>(v: &[i32; 3], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        IntArray::new(v.to_vec()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<[i32; 3], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let arr = IntArray::deserialize(deserializer)?;
+        <[i32; 3]>::try_from(&arr[..]).map_err(|_| {
+            D::Error::custom(format!(
+                "expected int array of length 3 for position, found length {}",
+                arr.len()
+            ))
+        })
+    }
+}
+
+/// Helpers for [`uuid::Uuid`][::uuid::Uuid]. Requires the `uuid` feature.
+///
+/// Since 1.16 Minecraft stores UUIDs as an IntArray of four ints, see
+/// [`int_array`]. Before that they were stored as two Longs with keys ending
+/// in `UUIDMost` and `UUIDLeast`, see [`most_least`]. Some places, such as
+/// player names caches, use the hyphenated string form, see [`hyphenated`].
+#[cfg(feature = "uuid")]
+pub mod uuid {
+    /// (De)serialize a `Uuid` as an IntArray of four ints, most significant
+    /// first.
+    ///
+    /// ```
+    /// # use serde::{Deserialize, Serialize};
+    /// # use uuid::Uuid;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Entity {
+    ///     #[serde(rename = "UUID", with = "fastnbt::serde_helpers::uuid::int_array")]
+    ///     uuid: Uuid,
+    /// }
+    /// ```
+    pub mod int_array {
+        use ::uuid::Uuid;
+        use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+        use crate::IntArray;
+
+        pub fn serialize<S>(v: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let n = v.as_u128();
+            let ints = (0..4).map(|i| (n >> (96 - 32 * i)) as u32 as i32).collect();
+            IntArray::new(ints).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let arr = IntArray::deserialize(deserializer)?;
+            if arr.len() != 4 {
+                return Err(D::Error::custom(format!(
+                    "expected int array of length 4 for UUID, found length {}",
+                    arr.len()
+                )));
+            }
+
+            let n = arr.iter().fold(0u128, |n, i| n << 32 | *i as u32 as u128);
+            Ok(Uuid::from_u128(n))
+        }
+    }
+
+    /// (De)serialize a `Uuid` as the two Longs `UUIDMost` and `UUIDLeast`, as
+    /// Minecraft did before 1.16. These sit alongside the other fields of the
+    /// compound, so use this with serde's `flatten` attribute.
+    ///
+    /// ```
+    /// # use serde::{Deserialize, Serialize};
+    /// # use uuid::Uuid;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Entity {
+    ///     #[serde(flatten, with = "fastnbt::serde_helpers::uuid::most_least")]
+    ///     uuid: Uuid,
+    /// }
+    /// ```
+    pub mod most_least {
+        use ::uuid::Uuid;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Serialize, Deserialize)]
+        struct MostLeast {
+            #[serde(rename = "UUIDMost")]
+            most: i64,
+            #[serde(rename = "UUIDLeast")]
+            least: i64,
+        }
+
+        pub fn serialize<S>(v: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let (most, least) = v.as_u64_pair();
+            MostLeast {
+                most: most as i64,
+                least: least as i64,
+            }
+            .serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let MostLeast { most, least } = MostLeast::deserialize(deserializer)?;
+            Ok(Uuid::from_u64_pair(most as u64, least as u64))
+        }
+    }
+
+    /// (De)serialize a `Uuid` as a hyphenated String, eg
+    /// `"f81d4fae-7dec-11d0-a765-00a0c91e6bf6"`. Deserializing also accepts
+    /// the other forms that [`Uuid::parse_str`][::uuid::Uuid::parse_str] does.
+    pub mod hyphenated {
+        use std::borrow::Cow;
+
+        use ::uuid::Uuid;
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S>(v: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(&v.hyphenated())
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let s = Cow::<str>::deserialize(deserializer)?;
+            Uuid::parse_str(&s).map_err(D::Error::custom)
+        }
+    }
+}
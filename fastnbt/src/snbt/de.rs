@@ -0,0 +1,610 @@
+use std::borrow::Cow;
+
+use serde::{
+    de::{
+        self,
+        value::{BorrowedStrDeserializer, BytesDeserializer, SeqDeserializer, StringDeserializer},
+        IntoDeserializer,
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{
+    error::{Error, Result},
+    named_root::NAMED_ROOT_TOKEN,
+    Tag,
+};
+
+/// Deserializer for SNBT text. See the [`snbt`][`crate::snbt`] module for
+/// more information.
+pub struct Deserializer<'de> {
+    input: &'de str,
+    pos: usize,
+    depth: usize,
+}
+
+/// How deeply lists and compounds can be nested, the same limit Minecraft
+/// uses when parsing SNBT. Without it, input like `[[[[...` would overflow
+/// the stack.
+const MAX_DEPTH: usize = 512;
+
+/// A value that is not a list, array or compound.
+enum Scalar<'de> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(Cow<'de, str>),
+}
+
+impl<'de> Deserializer<'de> {
+    /// Create Deserializer for a `T` from some SNBT text.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &'de str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    /// Check that only whitespace remains in the input. This should be called
+    /// after deserializing a value.
+    pub fn end(&mut self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("trailing characters")),
+        }
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::bespoke(msg.to_owned()).at(self.pos as u64, None)
+    }
+
+    /// Enter a list or compound, failing if it is nested too deeply. Pair
+    /// with `self.depth -= 1` once it has been read.
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::size(format!(
+                "nesting depth ({}) greater than max depth ({})",
+                self.depth, MAX_DEPTH
+            ))
+            .at(self.pos as u64, None));
+        }
+        Ok(())
+    }
+
+    fn rest(&self) -> &'de str {
+        &self.input[self.pos..]
+    }
+
+    /// Skip whitespace and look at the next character.
+    fn peek(&mut self) -> Option<char> {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+        self.rest().chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        match self.peek() {
+            Some(found) if found == c => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(found) => Err(self.error(&format!("expected '{c}', found '{found}'"))),
+            None => Err(self.error(&format!("expected '{c}', found end of input"))),
+        }
+    }
+
+    /// If the next thing in the input is the start of an NBT array eg `[L;`,
+    /// consume the start and return the type of array.
+    fn consume_array_start(&mut self) -> Option<Tag> {
+        if self.peek() != Some('[') {
+            return None;
+        }
+
+        let tag = match self.rest().get(1..3) {
+            Some("B;") => Tag::ByteArray,
+            Some("I;") => Tag::IntArray,
+            Some("L;") => Tag::LongArray,
+            _ => return None,
+        };
+
+        self.pos += 3;
+        Some(tag)
+    }
+
+    fn consume_unquoted(&mut self) -> &'de str {
+        let rest = self.rest();
+        let len = rest.find(|c| !is_unquoted_char(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn consume_quoted(&mut self, quote: char) -> Result<Cow<'de, str>> {
+        self.pos += quote.len_utf8();
+        let s = self.rest();
+
+        // Only allocate if we come across an escape sequence.
+        let mut owned = String::new();
+        let mut escaped = false;
+        let mut copied = 0;
+
+        let mut chars = s.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                self.pos += i + quote.len_utf8();
+                return Ok(if escaped {
+                    owned.push_str(&s[copied..i]);
+                    Cow::Owned(owned)
+                } else {
+                    Cow::Borrowed(&s[..i])
+                });
+            }
+
+            if c == '\\' {
+                escaped = true;
+                owned.push_str(&s[copied..i]);
+
+                match chars.next().map(|(_, c)| c) {
+                    Some(c @ ('\\' | '\'' | '"')) => owned.push(c),
+                    Some('n') => owned.push('\n'),
+                    Some('r') => owned.push('\r'),
+                    Some('t') => owned.push('\t'),
+                    Some('u') => {
+                        let start = s.len() - chars.as_str().len();
+                        let c = s
+                            .get(start..start + 4)
+                            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("invalid unicode escape"))?;
+                        owned.push(c);
+                        chars.nth(3);
+                    }
+                    _ => {
+                        self.pos += i;
+                        return Err(self.error("invalid escape sequence"));
+                    }
+                }
+
+                copied = s.len() - chars.as_str().len();
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    /// Consume a string, quoted or not. Used for keys and enum variants.
+    fn consume_string(&mut self) -> Result<Cow<'de, str>> {
+        match self.peek() {
+            Some(c @ ('"' | '\'')) => self.consume_quoted(c),
+            _ => match self.consume_unquoted() {
+                "" => Err(self.error("expected string")),
+                s => Ok(Cow::Borrowed(s)),
+            },
+        }
+    }
+
+    fn consume_scalar(&mut self) -> Result<Scalar<'de>> {
+        match self.peek() {
+            Some(c @ ('"' | '\'')) => Ok(Scalar::String(self.consume_quoted(c)?)),
+            _ => match self.consume_unquoted() {
+                "" => Err(self.error("expected value")),
+                "true" => Ok(Scalar::Byte(1)),
+                "false" => Ok(Scalar::Byte(0)),
+                s => Ok(parse_number(s).unwrap_or(Scalar::String(Cow::Borrowed(s)))),
+            },
+        }
+    }
+
+    /// Consume the elements of an NBT array, after the `[L;` has been
+    /// consumed. Values are checked to fit in the element type.
+    fn consume_array_elements(&mut self, tag: Tag) -> Result<Vec<i64>> {
+        let mut values = vec![];
+
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(values);
+        }
+
+        loop {
+            let value = match self.consume_scalar()? {
+                Scalar::Byte(v) => v as i64,
+                Scalar::Short(v) => v as i64,
+                Scalar::Int(v) => v as i64,
+                Scalar::Long(v) => v,
+                _ => return Err(self.error(&format!("expected integer in {tag}"))),
+            };
+
+            let fits = match tag {
+                Tag::ByteArray => i8::try_from(value).is_ok(),
+                Tag::IntArray => i32::try_from(value).is_ok(),
+                _ => true,
+            };
+            if !fits {
+                return Err(self.error(&format!("{value} out of range for {tag}")));
+            }
+            values.push(value);
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(values);
+                }
+                _ => return Err(self.error(&format!("expected ',' or ']' in {tag}"))),
+            }
+        }
+    }
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+/// Parse an unquoted token as a number, using the suffix to decide the type.
+/// Returns `None` if the token is not a number, in which case the game treats
+/// it as a string.
+fn parse_number(token: &str) -> Option<Scalar<'static>> {
+    let is_numeric = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E'))
+    };
+
+    if is_numeric(token) {
+        // No suffix, so either an Int or a Double if it has a decimal point.
+        return match token.parse() {
+            Ok(v) => Some(Scalar::Int(v)),
+            Err(_) if token.contains('.') => token.parse().ok().map(Scalar::Double),
+            Err(_) => None,
+        };
+    }
+
+    // Unquoted tokens are ASCII, so this can't split a character.
+    let (body, suffix) = token.split_at(token.len() - 1);
+    if !is_numeric(body) {
+        return None;
+    }
+
+    match suffix {
+        "b" | "B" => body.parse().ok().map(Scalar::Byte),
+        "s" | "S" => body.parse().ok().map(Scalar::Short),
+        "l" | "L" => body.parse().ok().map(Scalar::Long),
+        "f" | "F" => body.parse().ok().map(Scalar::Float),
+        "d" | "D" => body.parse().ok().map(Scalar::Double),
+        _ => None,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    forward_to_deserialize_any!(i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string map struct identifier);
+
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if let Some(tag) = self.consume_array_start() {
+            return visitor.visit_map(ArrayAccess {
+                de: self,
+                tag,
+                token_given: false,
+            });
+        }
+
+        match self.peek() {
+            Some('{') => {
+                self.enter()?;
+                self.pos += 1;
+                let value = visitor.visit_map(CompoundAccess {
+                    de: self,
+                    first: true,
+                })?;
+                self.expect('}')?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            Some('[') => {
+                self.enter()?;
+                self.pos += 1;
+                let value = visitor.visit_seq(ListAccess {
+                    de: self,
+                    first: true,
+                })?;
+                self.expect(']')?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            _ => match self.consume_scalar()? {
+                Scalar::Byte(v) => visitor.visit_i8(v),
+                Scalar::Short(v) => visitor.visit_i16(v),
+                Scalar::Int(v) => visitor.visit_i32(v),
+                Scalar::Long(v) => visitor.visit_i64(v),
+                Scalar::Float(v) => visitor.visit_f32(v),
+                Scalar::Double(v) => visitor.visit_f64(v),
+                Scalar::String(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
+                Scalar::String(Cow::Owned(s)) => visitor.visit_string(s),
+            },
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.consume_scalar()? {
+            Scalar::Byte(v) => visitor.visit_bool(v != 0),
+            Scalar::Short(v) => visitor.visit_bool(v != 0),
+            Scalar::Int(v) => visitor.visit_bool(v != 0),
+            Scalar::Long(v) => visitor.visit_bool(v != 0),
+            _ => Err(self.error("deserialize bool: expected integral value")),
+        }
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(get_i128_value(self)?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(get_i128_value(self)? as u128)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.consume_array_start() {
+            Some(Tag::ByteArray) => {
+                let values = self.consume_array_elements(Tag::ByteArray)?;
+                visitor.visit_byte_buf(values.into_iter().map(|v| v as u8).collect())
+            }
+            Some(tag) => Err(self.error(&format!("expected bytes, found {tag}"))),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_ignored_any(&mut *self, de::IgnoredAny)?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match name {
+            // The array types check that they get the token they expect.
+            crate::BYTE_ARRAY_TOKEN | crate::INT_ARRAY_TOKEN | crate::LONG_ARRAY_TOKEN => {
+                self.deserialize_any(visitor)
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.consume_array_start() {
+            Some(tag) => {
+                let values = self.consume_array_elements(tag)?;
+                let mut seq = SeqDeserializer::<_, Error>::new(values.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // SNBT has no root name, so a NamedRoot is just a compound.
+        if name == NAMED_ROOT_TOKEN {
+            return self.deserialize_map(visitor);
+        }
+
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let variant: String = self.consume_string()?.into_owned();
+        visitor.visit_enum(variant.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+fn get_i128_value(de: &mut Deserializer) -> Result<i128> {
+    if de.consume_array_start() != Some(Tag::IntArray) {
+        return Err(de.error("deserialize i128: expected IntArray value"));
+    }
+
+    match de.consume_array_elements(Tag::IntArray)?[..] {
+        [a, b, c, d] => Ok(((a as u32 as i128) << 96)
+            | ((b as u32 as i128) << 64)
+            | ((c as u32 as i128) << 32)
+            | (d as u32 as i128)),
+        ref values => Err(Error::bespoke(format!(
+            "deserialize i128: expected IntArray of length 4, found length {}",
+            values.len()
+        ))),
+    }
+}
+
+struct CompoundAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for CompoundAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.de.peek() == Some('}') {
+            return Ok(None);
+        }
+
+        if !self.first {
+            self.de.expect(',')?;
+        }
+        self.first = false;
+
+        match self.de.consume_string()? {
+            Cow::Borrowed(key) => seed.deserialize(BorrowedStrDeserializer::new(key)),
+            Cow::Owned(key) => seed.deserialize(StringDeserializer::new(key)),
+        }
+        .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.de.expect(':')?;
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct ListAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for ListAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.de.peek() == Some(']') {
+            return Ok(None);
+        }
+
+        if !self.first {
+            self.de.expect(',')?;
+        }
+        self.first = false;
+
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+/// Presents an NBT array the same way the binary deserializer does: a map
+/// with a single entry, the key being the array token and the value being the
+/// big endian bytes of the array.
+struct ArrayAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    tag: Tag,
+    token_given: bool,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for ArrayAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.token_given {
+            return Ok(None);
+        }
+        self.token_given = true;
+
+        let token = match self.tag {
+            Tag::ByteArray => crate::BYTE_ARRAY_TOKEN,
+            Tag::IntArray => crate::INT_ARRAY_TOKEN,
+            _ => crate::LONG_ARRAY_TOKEN,
+        };
+
+        seed.deserialize(BorrowedStrDeserializer::new(token))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let values = self.de.consume_array_elements(self.tag)?;
+        let bytes: Vec<u8> = match self.tag {
+            Tag::ByteArray => values.into_iter().map(|v| v as u8).collect(),
+            Tag::IntArray => values
+                .into_iter()
+                .flat_map(|v| (v as i32).to_be_bytes())
+                .collect(),
+            _ => values.into_iter().flat_map(|v| v.to_be_bytes()).collect(),
+        };
+
+        seed.deserialize(BytesDeserializer::new(&bytes))
+    }
+}
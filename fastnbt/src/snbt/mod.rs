@@ -0,0 +1,104 @@
+//! This module contains a serde serializer and deserializer for SNBT, the
+//! "stringified" NBT found in Minecraft commands, data packs and structure
+//! files. Use [`from_str`] and [`to_string`].
+//!
+//! ```rust
+//! use fastnbt::{snbt, Value};
+//!
+//! let value: Value = snbt::from_str(r#"{Count:1b,id:"minecraft:stone"}"#).unwrap();
+//! assert_eq!(value, fastnbt::nbt!({"Count": 1i8, "id": "minecraft:stone"}));
+//!
+//! let text = snbt::to_string(&fastnbt::nbt!([1i64, 2i64])).unwrap();
+//! assert_eq!(text, "[1L,2L]");
+//! ```
+//!
+//! # Syntax
+//!
+//! | NBT type | SNBT |
+//! | -------- | ---- |
+//! | Byte | `1b`, `true`, `false` |
+//! | Short | `1s` |
+//! | Int | `1` |
+//! | Long | `1L` |
+//! | Float | `1.0f` |
+//! | Double | `1.0d` or `1.0` |
+//! | String | `"quoted"`, `'quoted'` or `unquoted` |
+//! | List | `[a, b, c]` |
+//! | Compound | `{key: value, "quoted key": value}` |
+//! | Byte Array | `[B; 1b, 2b]` |
+//! | Int Array | `[I; 1, 2]` |
+//! | Long Array | `[L; 1L, 2L]` |
+//!
+//! Suffixes are case insensitive. An unquoted value that does not parse as a
+//! number or boolean is a string, just as in game. Unquoted strings and keys
+//! may contain `0-9`, `A-Z`, `a-z`, `_`, `-`, `.` and `+`.
+//!
+//! # Differences to the binary format
+//!
+//! * The root value does not have to be a compound, so
+//!   `let s: String = from_str("hello")` works.
+//! * NBT arrays map into serde's data model the same way as they do for the
+//!   binary format, so [`Value`][`crate::Value`] and the array types in this
+//!   crate work as expected. When deserializing into a sequence such as
+//!   `Vec<i64>` an NBT array is also accepted.
+//! * The serializer produces compact output with no whitespace. Keys are only
+//!   quoted when required. Strings are always quoted, so that one like `1b`
+//!   or `true` is not read back as another type.
+
+mod de;
+mod ser;
+
+use std::io::Write;
+
+use serde::{de::Deserialize, Serialize};
+
+use crate::error::Result;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+pub(crate) use ser::{quote, quote_key_if_needed};
+
+/// Deserialize into a `T` from some SNBT text. See the [`snbt`][`crate::snbt`]
+/// module for more information.
+///
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     id: String,
+///     #[serde(rename = "Count")]
+///     count: i8,
+/// }
+///
+/// let item: Item = fastnbt::snbt::from_str("{id: diamond, Count: 64b}").unwrap();
+/// assert_eq!(item.id, "diamond");
+/// assert_eq!(item.count, 64);
+/// ```
+pub fn from_str<'a, T>(input: &'a str) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut des = Deserializer::from_str(input);
+    let t = T::deserialize(&mut des)?;
+    des.end()?;
+    Ok(t)
+}
+
+/// Serialize some `T` into SNBT text. See the [`snbt`][`crate::snbt`] module
+/// for more information.
+pub fn to_string<T: Serialize>(v: &T) -> Result<String> {
+    let mut result = Vec::new();
+    to_writer(&mut result, v)?;
+
+    // The serializer only ever writes valid UTF-8.
+    Ok(String::from_utf8(result).expect("snbt should be utf-8"))
+}
+
+/// Serialize some `T` into SNBT text, writing it to `writer`. See the
+/// [`snbt`][`crate::snbt`] module for more information.
+pub fn to_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
+    let mut serializer = Serializer::new(writer);
+    v.serialize(&mut serializer)
+}
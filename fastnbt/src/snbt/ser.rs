@@ -0,0 +1,469 @@
+use std::{fmt, io::Write};
+
+use serde::{
+    ser::{self, Impossible},
+    Serialize,
+};
+
+use crate::{
+    error::{Error, Result},
+    named_root::NAMED_ROOT_TOKEN,
+    ser::name_serializer::NameSerializer,
+    value::array_serializer::ArraySerializer,
+    IntArray, Tag, Value,
+};
+
+/// Serializer for SNBT text. See the [`snbt`][`crate::snbt`] module for more
+/// information.
+pub struct Serializer<W: Write> {
+    writer: W,
+
+    // Text to write before the next value, eg the key of a compound entry. We
+    // only write this when a value is actually written so that `None` fields
+    // are left out entirely, like they are for the binary format.
+    prefix: Option<String>,
+}
+
+impl<W: Write> Serializer<W> {
+    /// Create a serializer that writes SNBT text to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            prefix: None,
+        }
+    }
+
+    fn write_prefix(&mut self) -> Result<()> {
+        if let Some(prefix) = self.prefix.take() {
+            self.writer.write_all(prefix.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    // Allows using `write!` on the serializer directly, writing any pending
+    // prefix first.
+    fn write_fmt(&mut self, args: fmt::Arguments) -> Result<()> {
+        self.write_prefix()?;
+        self.writer.write_fmt(args)?;
+        Ok(())
+    }
+
+    fn write_array<T: fmt::Display>(
+        &mut self,
+        kind: &str,
+        suffix: &str,
+        values: &[T],
+    ) -> Result<()> {
+        write!(self, "[{kind};")?;
+        for (i, v) in values.iter().enumerate() {
+            if i != 0 {
+                write!(self, ",")?;
+            }
+            write!(self, "{v}{suffix}")?;
+        }
+        write!(self, "]")
+    }
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+pub(crate) fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub(crate) fn quote_key_if_needed(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(is_unquoted_char) {
+        key.to_owned()
+    } else {
+        quote(key)
+    }
+}
+
+fn key_to_string<T: ?Sized + Serialize>(key: &T) -> Result<String> {
+    let mut name = Vec::new();
+    key.serialize(&mut NameSerializer { name: &mut name })?;
+
+    Ok(cesu8::from_java_cesu8(&name)
+        .map_err(|_| Error::bespoke("field name was invalid cesu8".to_string()))?
+        .to_string())
+}
+
+impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = SerializeList<'a, W>;
+    type SerializeTuple = SerializeList<'a, W>;
+    type SerializeTupleStruct = SerializeList<'a, W>;
+    type SerializeTupleVariant = SerializeList<'a, W>;
+    type SerializeMap = SerializeCompound<'a, W>;
+    type SerializeStruct = SerializeCompound<'a, W>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        write!(self, "{}b", v as i8)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        write!(self, "{v}b")
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        write!(self, "{v}s")
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        write!(self, "{v}")
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        write!(self, "{v}L")
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        IntArray::new(vec![
+            (v >> 96) as i32,
+            (v >> 64) as i32,
+            (v >> 32) as i32,
+            v as i32,
+        ])
+        .serialize(self)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.serialize_i128(v as i128)
+    }
+
+    // Unsigned values are written with the same bits as the binary format
+    // would write them.
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_i8(v as i8)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_i16(v as i16)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_i32(v as i32)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+
+    // SNBT has no way to write NaN or infinity, so those are an error rather
+    // than something that reads back as a string.
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        if !v.is_finite() {
+            return Err(Error::bespoke(format!("cannot serialize {v} to snbt")));
+        }
+        write!(self, "{v:?}f")
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        if !v.is_finite() {
+            return Err(Error::bespoke(format!("cannot serialize {v} to snbt")));
+        }
+        write!(self, "{v:?}d")
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        write!(self, "{}", quote(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        // The binary format writes a list of bytes, so do the same.
+        let mut list = ser::Serializer::serialize_seq(self, Some(v.len()))?;
+        for b in v {
+            ser::SerializeSeq::serialize_element(&mut list, &(*b as i8))?;
+        }
+        ser::SerializeSeq::end(list)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(Error::bespoke("cannot serialize unit to snbt".to_string()))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        Err(Error::bespoke(format!(
+            "cannot serialize unit struct {name} to snbt"
+        )))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let tag = match variant {
+            crate::BYTE_ARRAY_TOKEN => Tag::ByteArray,
+            crate::INT_ARRAY_TOKEN => Tag::IntArray,
+            crate::LONG_ARRAY_TOKEN => Tag::LongArray,
+            _ => {
+                return Err(Error::bespoke(format!(
+                    "cannot serialize newtype variant {variant} to snbt"
+                )))
+            }
+        };
+
+        // Reuse the Value serializer to interpret the array data for us.
+        let array = value.serialize(ArraySerializer {
+            ser: &mut crate::value::Serializer,
+            tag,
+        })?;
+
+        match array {
+            Value::ByteArray(v) => self.write_array("B", "b", &v),
+            Value::IntArray(v) => self.write_array("I", "", &v),
+            Value::LongArray(v) => self.write_array("L", "L", &v),
+            _ => unreachable!(),
+        }
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        write!(self, "[")?;
+        Ok(SerializeList {
+            ser: self,
+            first: true,
+            named_root: false,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        if name == NAMED_ROOT_TOKEN {
+            return Ok(SerializeList {
+                ser: self,
+                first: true,
+                named_root: true,
+            });
+        }
+
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Error::bespoke(format!(
+            "cannot serialize tuple variant {variant} to snbt"
+        )))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        write!(self, "{{")?;
+        Ok(SerializeCompound {
+            ser: self,
+            first: true,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Error::bespoke(format!(
+            "cannot serialize struct variant {variant} to snbt"
+        )))
+    }
+}
+
+pub struct SerializeCompound<'a, W: Write> {
+    ser: &'a mut Serializer<W>,
+    first: bool,
+    key: Option<String>,
+}
+
+impl<'a, W: Write> SerializeCompound<'a, W> {
+    fn serialize_field_value<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        let separator = if self.first { "" } else { "," };
+        self.ser.prefix = Some(format!("{separator}{}:", quote_key_if_needed(key)));
+        value.serialize(&mut *self.ser)?;
+
+        // If the prefix is still there, nothing was written for this field.
+        if self.ser.prefix.take().is_none() {
+            self.first = false;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W: Write> ser::SerializeMap for SerializeCompound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key_to_string(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::bespoke("serialize_value called before serialize_key".into()))?;
+        self.serialize_field_value(&key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        write!(self.ser, "}}")
+    }
+}
+
+impl<'a, W: Write> ser::SerializeStruct for SerializeCompound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.serialize_field_value(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        write!(self.ser, "}}")
+    }
+}
+
+pub struct SerializeList<'a, W: Write> {
+    ser: &'a mut Serializer<W>,
+    first: bool,
+    // SNBT has no root name, so a NamedRoot is written as just its value.
+    named_root: bool,
+}
+
+impl<'a, W: Write> ser::SerializeSeq for SerializeList<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.ser.prefix = Some(if self.first { "" } else { "," }.to_string());
+        value.serialize(&mut *self.ser)?;
+
+        // If the prefix is still there, nothing was written for this element.
+        if self.ser.prefix.take().is_none() {
+            self.first = false;
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        write!(self.ser, "]")
+    }
+}
+
+impl<'a, W: Write> ser::SerializeTuple for SerializeList<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W: Write> ser::SerializeTupleStruct for SerializeList<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        if self.named_root {
+            if self.first {
+                self.first = false;
+                return Ok(());
+            }
+            return value.serialize(&mut *self.ser);
+        }
+
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        if self.named_root {
+            return Ok(());
+        }
+
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W: Write> ser::SerializeTupleVariant for SerializeList<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
@@ -1,8 +1,18 @@
 //! Allows streaming of NBT data without prior knowledge of the structure.
+//!
+//! [`Parser`] reads NBT as a flat stream of [`Value`]s, and [`Writer`] writes
+//! the same stream of values back out as NBT. [`Decoder`] produces the same
+//! values as the parser from input that arrives in pieces, for use with
+//! non-blocking IO.
 
 use super::Tag;
-use byteorder::{BigEndian, ReadBytesExt};
-use std::{convert::TryFrom, io::Read, str};
+use crate::{limits::Limits, DeOpts};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+    str,
+};
 
 /// An optional `String`.
 pub type Name = Option<String>;
@@ -60,6 +70,10 @@ pub enum ErrorKind {
     /// Expected unicode data but was not valid. Parser remains valid if just
     /// this value was not unicode. Contained bytes are the invalid unicode data.
     Nonunicode(Vec<u8>),
+
+    /// The input went over one of the limits given with [`Parser::with_opts`]
+    /// or [`Decoder::with_opts`].
+    LimitExceeded,
 }
 
 impl Error {
@@ -96,6 +110,13 @@ impl Error {
         }
     }
 
+    fn limit_exceeded(e: crate::error::Error) -> Self {
+        Self {
+            msg: e.to_string(),
+            kind: ErrorKind::LimitExceeded,
+        }
+    }
+
     fn eof() -> Self {
         Self {
             msg: "EOF".into(),
@@ -211,17 +232,59 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct Parser<R: Read> {
     reader: R,
     layers: Vec<Layer>,
+    skip_payloads: bool,
+    limits: Limits,
 }
 
 impl<R: Read> Parser<R> {
-    /// Create new parser for the given reader.
+    /// Create new parser for the given reader. The input is not checked
+    /// against any limits, use [`Parser::with_opts`] for that.
     pub fn new(reader: R) -> Self {
+        Self::with_opts(reader, DeOpts::default())
+    }
+
+    /// Create a parser that checks the input against the limits in `opts`:
+    /// [`max_depth`][DeOpts::max_depth],
+    /// [`max_array_len`][DeOpts::max_array_len],
+    /// [`max_string_len`][DeOpts::max_string_len] and
+    /// [`max_alloc`][DeOpts::max_alloc]. Other options are ignored.
+    ///
+    /// ```
+    /// use fastnbt::{stream::{ErrorKind, Parser}, DeOpts};
+    /// # use fastnbt::nbt;
+    /// # let input = fastnbt::to_bytes(&nbt!({"a": {"b": {}}})).unwrap();
+    ///
+    /// let mut parser = Parser::with_opts(input.as_slice(), DeOpts::new().max_depth(2));
+    /// parser.next().unwrap(); // root compound
+    /// parser.next().unwrap(); // "a"
+    /// let err = parser.next().unwrap_err();
+    /// assert!(matches!(err.kind(), ErrorKind::LimitExceeded));
+    /// ```
+    pub fn with_opts(reader: R, opts: DeOpts) -> Self {
         Self {
             reader,
             layers: Vec::new(),
+            skip_payloads: false,
+            limits: Limits::new(&opts),
         }
     }
 
+    /// Set whether the parser skips over the payloads of strings and arrays.
+    /// When skipping, the payloads are read past without being stored, and
+    /// strings and arrays are returned empty. Names and all other values are
+    /// still parsed as normal.
+    ///
+    /// This is useful when looking for something in large NBT, such as a
+    /// chunk, where most of the data is not needed.
+    pub fn set_skip_payloads(&mut self, skip: bool) {
+        self.skip_payloads = skip;
+    }
+
+    /// Get a reference to the underlying reader.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
     /// Parse the next value from the input.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Value> {
@@ -287,7 +350,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn read_size_prefixed_string(&mut self) -> Result<String> {
-        let name_len = self.reader.read_u16::<BigEndian>()? as usize;
+        let name_len = self.read_string_len()?;
 
         let mut buf = vec![0; name_len];
         self.reader.read_exact(&mut buf[..])?;
@@ -298,6 +361,28 @@ impl<R: Read> Parser<R> {
     }
 
     fn read_payload(&mut self, tag: Tag, name: Name) -> Result<Value> {
+        if self.skip_payloads {
+            match tag {
+                Tag::String => {
+                    self.skip_payload(tag)?;
+                    return Ok(Value::String(name, String::new()));
+                }
+                Tag::ByteArray => {
+                    self.skip_payload(tag)?;
+                    return Ok(Value::ByteArray(name, vec![]));
+                }
+                Tag::IntArray => {
+                    self.skip_payload(tag)?;
+                    return Ok(Value::IntArray(name, vec![]));
+                }
+                Tag::LongArray => {
+                    self.skip_payload(tag)?;
+                    return Ok(Value::LongArray(name, vec![]));
+                }
+                _ => {}
+            }
+        }
+
         match tag {
             Tag::Byte => Ok(Value::Byte(name, self.reader.read_i8()?)),
             Tag::Short => Ok(Value::Short(name, self.reader.read_i16::<BigEndian>()?)),
@@ -306,83 +391,740 @@ impl<R: Read> Parser<R> {
             Tag::Float => Ok(Value::Float(name, self.reader.read_f32::<BigEndian>()?)),
             Tag::Double => Ok(Value::Double(name, self.reader.read_f64::<BigEndian>()?)),
             Tag::Compound => {
+                self.enter()?;
                 self.layers.push(Layer::Compound);
                 Ok(Value::Compound(name))
             }
             Tag::End => panic!("end tag should have returned early"),
             Tag::List => {
+                self.enter()?;
                 let element_tag = self.reader.read_u8()?;
                 let element_tag = u8_to_tag(element_tag)?;
                 let size = self.reader.read_i32::<BigEndian>()?;
+                self.limits
+                    .list(element_tag, size.max(0) as usize)
+                    .map_err(Error::limit_exceeded)?;
                 self.layers.push(Layer::List(element_tag, size));
                 Ok(Value::List(name, element_tag, size))
             }
             Tag::String => Ok(Value::String(name, self.read_size_prefixed_string()?)),
             Tag::ByteArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0u8; size as usize];
+                let size = self.read_array_len(Tag::Byte)?;
+                let mut buf = vec![0u8; size];
                 self.reader.read_exact(&mut buf[..])?;
                 Ok(Value::ByteArray(name, vec_u8_into_i8(buf)))
             }
             Tag::IntArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i32; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i32::<BigEndian>()?;
+                let size = self.read_array_len(Tag::Int)?;
+                let mut buf = vec![0i32; size];
+                for el in buf.iter_mut() {
+                    *el = self.reader.read_i32::<BigEndian>()?;
                 }
 
                 Ok(Value::IntArray(name, buf))
             }
             Tag::LongArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i64; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i64::<BigEndian>()?;
+                let size = self.read_array_len(Tag::Long)?;
+                let mut buf = vec![0i64; size];
+                for el in buf.iter_mut() {
+                    *el = self.reader.read_i64::<BigEndian>()?;
                 }
 
                 Ok(Value::LongArray(name, buf))
             }
         }
     }
+
+    /// Read the tag and name of the next value in the current compound.
+    /// Returns `None` and leaves the compound if it has ended.
+    fn read_compound_entry(&mut self) -> Result<Option<(Tag, String)>> {
+        let tag = u8_to_tag(self.reader.read_u8()?)?;
+        if tag == Tag::End {
+            self.layers.pop();
+            return Ok(None);
+        }
+
+        let name = self.read_size_prefixed_string()?;
+        Ok(Some((tag, name)))
+    }
+
+    /// Check that a compound or list can be entered from the current layer.
+    fn enter(&self) -> Result<()> {
+        self.limits
+            .depth(self.layers.len() + 1)
+            .map_err(Error::limit_exceeded)
+    }
+
+    /// Skip the payload of a value with the given tag, including everything
+    /// inside it if it is a compound or list. Nothing is allocated.
+    fn skip_payload(&mut self, tag: Tag) -> Result<()> {
+        self.skip_nested_payload(tag, self.layers.len())
+    }
+
+    /// Skip a payload that is inside `depth` compounds and lists.
+    fn skip_nested_payload(&mut self, tag: Tag, depth: usize) -> Result<()> {
+        let enter = |limits: &Limits| limits.depth(depth + 1).map_err(Error::limit_exceeded);
+        match tag {
+            Tag::End => {}
+            Tag::Byte => self.skip_bytes(1)?,
+            Tag::Short => self.skip_bytes(2)?,
+            Tag::Int | Tag::Float => self.skip_bytes(4)?,
+            Tag::Long | Tag::Double => self.skip_bytes(8)?,
+            Tag::String => {
+                let len = self.read_string_len()?;
+                self.skip_bytes(len as u64)?;
+            }
+            Tag::ByteArray => {
+                let len = self.read_array_len(Tag::Byte)?;
+                self.skip_bytes(len as u64)?;
+            }
+            Tag::IntArray => {
+                let len = self.read_array_len(Tag::Int)?;
+                self.skip_bytes(len as u64 * 4)?;
+            }
+            Tag::LongArray => {
+                let len = self.read_array_len(Tag::Long)?;
+                self.skip_bytes(len as u64 * 8)?;
+            }
+            Tag::List => {
+                enter(&self.limits)?;
+                let element_tag = u8_to_tag(self.reader.read_u8()?)?;
+                let len = self.read_len()?;
+                self.limits
+                    .list(element_tag, len as usize)
+                    .map_err(Error::limit_exceeded)?;
+                for _ in 0..len {
+                    self.skip_nested_payload(element_tag, depth + 1)?;
+                }
+            }
+            Tag::Compound => {
+                enter(&self.limits)?;
+                loop {
+                    let tag = u8_to_tag(self.reader.read_u8()?)?;
+                    if tag == Tag::End {
+                        break;
+                    }
+                    let name_len = self.read_string_len()?;
+                    self.skip_bytes(name_len as u64)?;
+                    self.skip_nested_payload(tag, depth + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_len(&mut self) -> Result<u64> {
+        let len = self.reader.read_i32::<BigEndian>()?;
+        u64::try_from(len).map_err(|_| Error::bespoke(format!("invalid length: {}", len)))
+    }
+
+    /// Read the length of a string, checking it against the limits.
+    fn read_string_len(&mut self) -> Result<usize> {
+        let len = self.reader.read_u16::<BigEndian>()? as usize;
+        self.limits.string(len).map_err(Error::limit_exceeded)?;
+        Ok(len)
+    }
+
+    /// Read the length of an array of `element`s, checking it against the
+    /// limits.
+    fn read_array_len(&mut self, element: Tag) -> Result<usize> {
+        let len = self.read_len()? as usize;
+        self.limits
+            .array(element, len)
+            .map_err(Error::limit_exceeded)?;
+        Ok(len)
+    }
+
+    fn skip_bytes(&mut self, n: u64) -> Result<()> {
+        let skipped = std::io::copy(&mut (&mut self.reader).take(n), &mut std::io::sink())?;
+        if skipped != n {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(())
+    }
+
+    /// Skip the rest of the current list, leaving the parser after its
+    /// `ListEnd`.
+    fn skip_list_rest(&mut self) -> Result<()> {
+        if let Some(Layer::List(tag, remaining)) = self.layers.last().cloned() {
+            for _ in 0..remaining.max(0) {
+                self.skip_payload(tag)?;
+            }
+            self.layers.pop();
+        }
+        Ok(())
+    }
 }
 
 /// Parse the input until the compound we are currently inside is complete.
-/// Handles inner compounds by skipping those as well.
+/// Handles inner compounds by skipping those as well. Payloads are skipped
+/// over without being read into memory.
 pub fn skip_compound<R: Read>(parser: &mut Parser<R>) -> Result<()> {
-    let mut depth = 1;
+    while let Some(Layer::List(..)) = parser.layers.last() {
+        parser.skip_list_rest()?;
+    }
 
-    while depth != 0 {
-        let value = parser.next()?;
-        match value {
-            Value::CompoundEnd => depth -= 1,
-            Value::Compound(_) => depth += 1,
-            _ => {}
-        }
+    match parser.layers.last() {
+        Some(Layer::Compound) => {}
+        _ => return Err(Error::bespoke("expected to be in compound")),
+    }
+
+    while let Some((tag, _)) = parser.read_compound_entry()? {
+        parser.skip_payload(tag)?;
     }
     Ok(())
 }
 
 /// Parse until the compound with the given name is found. This will enter other
 /// compounds and lists, rather than find a compound at the current level.
+/// String and array payloads are skipped while searching.
 pub fn find_compound<R: Read>(parser: &mut Parser<R>, name: Option<&str>) -> Result<()> {
-    loop {
-        match parser.next()? {
-            //Value::Compound(n) if n == name.map(|s| s.to_owned()) => break,
-            Value::Compound(n) if n.as_deref() == name => break,
-            _ => {}
+    let skip = std::mem::replace(&mut parser.skip_payloads, true);
+    let res = loop {
+        match parser.next() {
+            Ok(Value::Compound(n)) if n.as_deref() == name => break Ok(()),
+            Ok(_) => {}
+            Err(e) => break Err(e),
         }
-    }
-    Ok(())
+    };
+    parser.skip_payloads = skip;
+    res
 }
 
 /// Parse until the list with the given name is found. This will enter other
 /// compounds and lists, rather than find a list at the current level.
+/// String and array payloads are skipped while searching.
 pub fn find_list<R: Read>(parser: &mut Parser<R>, name: Option<&str>) -> Result<usize> {
-    loop {
-        match parser.next()? {
-            Value::List(n, _, size) if n.as_deref() == name => return Ok(size as usize),
+    let skip = std::mem::replace(&mut parser.skip_payloads, true);
+    let res = loop {
+        match parser.next() {
+            Ok(Value::List(n, _, size)) if n.as_deref() == name => break Ok(size as usize),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    parser.skip_payloads = skip;
+    res
+}
+
+/// Parse until the value at the given path is found, and return it. Each part
+/// of the path is a compound key, or an index if the value it applies to is a
+/// list. The path starts at the root compound, so this should be used on a
+/// parser that has not parsed anything yet.
+///
+/// Everything that is not on the path is skipped over without being read into
+/// memory. If the value found is a compound or list, the parser is left inside
+/// it, so its contents can be parsed next.
+///
+/// Returns `None` if there is no value at the path. In that case the parser is
+/// left part way through the input.
+///
+/// ```
+/// use fastnbt::stream::{find_path, Parser, Value};
+/// # use fastnbt::nbt;
+/// # let input = fastnbt::to_bytes(&nbt!({
+/// #     "sections": [{"Y": 0_i8}, {"Y": 1_i8, "block_states": {"palette": []}}]
+/// # })).unwrap();
+///
+/// let mut parser = Parser::new(input.as_slice());
+/// let found = find_path(&mut parser, &["sections", "1", "Y"]).unwrap();
+/// assert_eq!(found, Some(Value::Byte(Some("Y".to_owned()), 1)));
+/// ```
+pub fn find_path<R: Read>(parser: &mut Parser<R>, path: &[&str]) -> Result<Option<Value>> {
+    let mut current = match parser.next()? {
+        v @ Value::Compound(_) => v,
+        _ => return Err(Error::bespoke("expected root compound")),
+    };
+
+    for (i, part) in path.iter().enumerate() {
+        // Only compounds and lists can be looked into.
+        if i > 0 && !matches!(current, Value::Compound(_) | Value::List(..)) {
+            return Ok(None);
+        }
+
+        current = match parser.layers.last().cloned() {
+            Some(Layer::Compound) => loop {
+                match parser.read_compound_entry()? {
+                    Some((tag, name)) if name == *part => {
+                        break parser.read_payload(tag, Some(name))?;
+                    }
+                    Some((tag, _)) => parser.skip_payload(tag)?,
+                    None => return Ok(None),
+                }
+            },
+            Some(Layer::List(tag, remaining)) => {
+                let index = match part.parse::<i32>() {
+                    Ok(i) if i >= 0 && i < remaining => i,
+                    _ => return Ok(None),
+                };
+                for _ in 0..index {
+                    parser.skip_payload(tag)?;
+                }
+                parser.layers.pop();
+                parser.layers.push(Layer::List(tag, remaining - index));
+
+                // The parser takes care of the rest of the list from here.
+                parser.next()?
+            }
+            None => return Err(Error::bespoke("expected to be in compound or list")),
+        };
+    }
+
+    Ok(Some(current))
+}
+
+/// Decoder is a push based version of [`Parser`], for when NBT arrives in
+/// pieces, such as from a non-blocking socket. Data is given to the decoder
+/// with [`Decoder::feed`] as it arrives, and [`Decoder::next`] returns values
+/// once all of their data has arrived. Does not do decompression.
+///
+/// Values are returned in the same way as the parser, but rather than an EOF
+/// error, the decoder returns `None` when it needs more input.
+///
+/// ```
+/// use fastnbt::stream::{Decoder, Value};
+/// # use fastnbt::nbt;
+/// # let input = fastnbt::to_bytes(&nbt!({"a": 1, "b": "hello"})).unwrap();
+///
+/// let mut decoder = Decoder::new();
+/// let mut values = vec![];
+///
+/// for fragment in input.chunks(3) {
+///     decoder.feed(fragment);
+///     while let Some(value) = decoder.next().unwrap() {
+///         values.push(value);
+///     }
+/// }
+///
+/// assert_eq!(values.len(), 4);
+/// assert_eq!(values[3], Value::CompoundEnd);
+/// assert_eq!(decoder.depth(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Decoder {
+    buf: Vec<u8>,
+    pos: usize,
+    layers: Vec<Layer>,
+    limits: Limits,
+    max_value_len: usize,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            buf: Vec::new(),
+            pos: 0,
+            layers: Vec::new(),
+            limits: Limits::default(),
+            max_value_len: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl Decoder {
+    /// Create a new decoder. The decoder uses the default limits of
+    /// [`DeOpts`], and the default [`max_value_len`][Self::max_value_len].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a decoder that checks the input against the limits in `opts`,
+    /// in the same way as [`Parser::with_opts`].
+    pub fn with_opts(opts: DeOpts) -> Self {
+        Self {
+            limits: Limits::new(&opts),
+            ..Self::default()
+        }
+    }
+
+    /// Set the maximum number of bytes a single value can take up in the
+    /// input, including its name. A value is only returned once all of it has
+    /// been fed to the decoder, so this stops a huge string or array in the
+    /// input from making the decoder buffer data without end. Defaults to 16
+    /// MiB. Compounds and lists count only their header, not their contents.
+    pub fn max_value_len(mut self, value: usize) -> Self {
+        self.max_value_len = value;
+        self
+    }
+
+    /// Give the decoder more input.
+    pub fn feed(&mut self, data: &[u8]) {
+        // Drop what has already been decoded so the buffer does not grow
+        // without bound.
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Decode the next value. Returns `None` if more input is needed to decode
+    /// the value, in which case this can be called again after more input is
+    /// fed to the decoder.
+    ///
+    /// After an error the decoder is in an unspecified state, and should not be
+    /// used further.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<Value>> {
+        let data = &self.buf[self.pos..];
+        let len = match event_len(data, &self.layers, &self.limits)? {
+            Some(len) if len > self.max_value_len => {
+                return Err(Error::limit_exceeded(crate::error::Error::size(format!(
+                    "value length ({}) greater than max value length ({})",
+                    len, self.max_value_len
+                ))))
+            }
+            Some(len) if len <= data.len() => len,
+            _ => return Ok(None),
+        };
+
+        // All of the data for the value is here, so the parser will not run out
+        // of input.
+        let mut parser = Parser {
+            reader: &data[..len],
+            layers: std::mem::take(&mut self.layers),
+            skip_payloads: false,
+            limits: self.limits.clone(),
+        };
+        let value = parser.next()?;
+
+        self.layers = parser.layers;
+        self.limits = parser.limits;
+        self.pos += len;
+        Ok(Some(value))
+    }
+
+    /// The number of compounds and lists the decoder is currently inside. This
+    /// is zero once a complete NBT value has been decoded.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The number of bytes fed to the decoder that have not been decoded yet.
+    pub fn buffered(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+}
+
+/// Get the number of bytes the next value takes up, or `None` if the data
+/// does not contain enough of it to tell yet. This does not read any of the
+/// payloads.
+fn event_len(data: &[u8], layers: &[Layer], limits: &Limits) -> Result<Option<usize>> {
+    match layers.last() {
+        // The end of a list has no data.
+        Some(Layer::List(_, 0)) => return Ok(Some(0)),
+        Some(Layer::List(tag, _)) => return payload_len(data, *tag, limits),
+        _ => {}
+    }
+
+    let tag = match data.first() {
+        Some(t) => u8_to_tag(*t)?,
+        None => return Ok(None),
+    };
+    if tag == Tag::End {
+        return Ok(Some(1));
+    }
+
+    let header_len = match data.get(1..3) {
+        Some(len) => 3 + BigEndian::read_u16(len) as usize,
+        None => return Ok(None),
+    };
+    match data.get(header_len..) {
+        Some(payload) => Ok(payload_len(payload, tag, limits)?.map(|len| header_len + len)),
+        None => Ok(None),
+    }
+}
+
+/// Get the number of bytes the payload of a value takes up, or `None` if the
+/// data does not contain enough of it to tell yet. Compounds and lists only
+/// count their header, as their contents are separate values. Array lengths
+/// are checked against the limits, so that the data for a huge array is not
+/// waited for.
+fn payload_len(data: &[u8], tag: Tag, limits: &Limits) -> Result<Option<usize>> {
+    let prefixed = |element: Tag, width: usize| -> Result<Option<usize>> {
+        let len = match data.get(..4) {
+            Some(len) => BigEndian::read_i32(len),
+            None => return Ok(None),
+        };
+        let len =
+            usize::try_from(len).map_err(|_| Error::bespoke(format!("invalid length: {}", len)))?;
+        limits
+            .check_array(element, len)
+            .map_err(Error::limit_exceeded)?;
+        Ok(Some(4usize.saturating_add(len.saturating_mul(width))))
+    };
+
+    let len = match tag {
+        Tag::End => Some(0),
+        Tag::Byte => Some(1),
+        Tag::Short => Some(2),
+        Tag::Int | Tag::Float => Some(4),
+        Tag::Long | Tag::Double => Some(8),
+        Tag::String => data
+            .get(..2)
+            .map(|len| 2 + BigEndian::read_u16(len) as usize),
+        Tag::ByteArray => prefixed(Tag::Byte, 1)?,
+        Tag::IntArray => prefixed(Tag::Int, 4)?,
+        Tag::LongArray => prefixed(Tag::Long, 8)?,
+        Tag::List => match data.get(..5) {
+            // Check the header is valid, as the parser relies on it.
+            Some(header) => match (u8_to_tag(header[0])?, BigEndian::read_i32(&header[1..])) {
+                (_, len) if len < 0 => {
+                    return Err(Error::bespoke(format!("invalid length: {}", len)))
+                }
+                (Tag::End, len) if len > 0 => {
+                    return Err(Error::bespoke("non-empty list with end tag elements"))
+                }
+                _ => Some(5),
+            },
+            None => None,
+        },
+        Tag::Compound => Some(0),
+    };
+
+    Ok(len)
+}
+
+/// Writer writes a stream of [`Value`]s as NBT, the reverse of [`Parser`].
+/// Does not do compression.
+///
+/// The writer checks that the values form valid NBT: values in compounds must
+/// have names, values in lists must not have names and must match the element
+/// tag of the list, every compound and list must be ended, and lists must get
+/// exactly as many elements as their length says. As with the parser, a
+/// `ListEnd` needs to be written after the elements of a list.
+///
+/// # Example
+///
+/// Values from a parser can be passed straight to a writer, allowing NBT to be
+/// modified without deserializing all of it.
+///
+/// ```
+/// use fastnbt::stream::{Parser, Value, Writer};
+/// # use fastnbt::nbt;
+/// # let input = fastnbt::to_bytes(&nbt!({"DataVersion": 3000, "Data": {}})).unwrap();
+///
+/// let mut parser = Parser::new(input.as_slice());
+/// let mut writer = Writer::new(Vec::new());
+///
+/// loop {
+///     match parser.next() {
+///         Ok(Value::Int(Some(name), _)) if name == "DataVersion" => {
+///             writer.write(&Value::Int(Some(name), 3100)).unwrap();
+///         }
+///         Ok(value) => writer.write(&value).unwrap(),
+///         Err(e) if e.is_eof() => break,
+///         Err(e) => panic!("{}", e),
+///     }
+/// }
+///
+/// let output = writer.finish().unwrap();
+/// let v: fastnbt::Value = fastnbt::from_bytes(&output).unwrap();
+/// assert_eq!(v["DataVersion"], 3100);
+/// ```
+pub struct Writer<W: Write> {
+    writer: W,
+    layers: Vec<Layer>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a new writer for the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Write the next value. Returns an error if the value is not valid where
+    /// it is being written, or its name or payload is too long for NBT, in
+    /// which case nothing is written. If the underlying writer returns an
+    /// error, some of the value may have been written and the output should
+    /// not be used.
+    pub fn write(&mut self, value: &Value) -> Result<()> {
+        if let Some(Some(name)) = value.name() {
+            java_string_len(name)?;
+        }
+        match value {
+            Value::String(_, v) => {
+                java_string_len(v)?;
+            }
+            Value::ByteArray(_, v) => array_len(v.len())?,
+            Value::IntArray(_, v) => array_len(v.len())?,
+            Value::LongArray(_, v) => array_len(v.len())?,
             _ => {}
         }
+
+        if let Value::List(_, element_tag, len) = value {
+            if *len < 0 {
+                return Err(Error::bespoke(format!("invalid list length: {}", len)));
+            }
+            if *element_tag == Tag::End && *len > 0 {
+                return Err(Error::bespoke("non-empty list with end tag elements"));
+            }
+        }
+
+        match (self.layers.last_mut(), value) {
+            (Some(Layer::List(_, 0)), Value::ListEnd) => {
+                self.layers.pop();
+                return Ok(());
+            }
+            (Some(Layer::List(_, remaining)), Value::ListEnd) => {
+                return Err(Error::bespoke(format!(
+                    "list ended with {} elements remaining",
+                    remaining
+                )));
+            }
+            (Some(Layer::List(_, 0)), _) => {
+                return Err(Error::bespoke("too many elements written to list"));
+            }
+            (Some(Layer::List(element_tag, remaining)), _) => {
+                if value.tag() != Some(*element_tag) {
+                    return Err(Error::bespoke(format!(
+                        "expected list element of type {:?}, got {:?}",
+                        element_tag,
+                        value.tag()
+                    )));
+                }
+                if matches!(value.name(), Some(Some(_))) {
+                    return Err(Error::bespoke("list elements must not have names"));
+                }
+                *remaining -= 1;
+            }
+            (Some(Layer::Compound), Value::CompoundEnd) => {
+                self.layers.pop();
+                self.writer.write_u8(Tag::End as u8)?;
+                return Ok(());
+            }
+            (_, Value::CompoundEnd) => {
+                return Err(Error::bespoke("compound ended while not in compound"));
+            }
+            (_, Value::ListEnd) => {
+                return Err(Error::bespoke("list ended while not in list"));
+            }
+            (_, _) => {
+                let name = match value.name() {
+                    Some(Some(name)) => name,
+                    _ => return Err(Error::bespoke("values in compounds must have names")),
+                };
+                // Unwrap is fine, only the end values have no tag.
+                self.writer.write_u8(value.tag().unwrap() as u8)?;
+                self.write_size_prefixed_string(name)?;
+            }
+        }
+
+        self.write_payload(value)
+    }
+
+    /// Finish writing, returning the underlying writer. Returns an error if a
+    /// compound or list has not been ended.
+    pub fn finish(self) -> Result<W> {
+        if !self.layers.is_empty() {
+            return Err(Error::bespoke("unfinished compound or list"));
+        }
+        Ok(self.writer)
+    }
+
+    fn write_size_prefixed_string(&mut self, s: &str) -> Result<()> {
+        let len = java_string_len(s)?;
+        let s = cesu8::to_java_cesu8(s);
+        self.writer.write_u16::<BigEndian>(len)?;
+        self.writer.write_all(&s)?;
+        Ok(())
+    }
+
+    fn write_len(&mut self, len: usize) -> Result<()> {
+        array_len(len)?;
+        self.writer.write_i32::<BigEndian>(len as i32)?;
+        Ok(())
+    }
+
+    fn write_payload(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::Byte(_, v) => self.writer.write_i8(*v)?,
+            Value::Short(_, v) => self.writer.write_i16::<BigEndian>(*v)?,
+            Value::Int(_, v) => self.writer.write_i32::<BigEndian>(*v)?,
+            Value::Long(_, v) => self.writer.write_i64::<BigEndian>(*v)?,
+            Value::Float(_, v) => self.writer.write_f32::<BigEndian>(*v)?,
+            Value::Double(_, v) => self.writer.write_f64::<BigEndian>(*v)?,
+            Value::String(_, v) => self.write_size_prefixed_string(v)?,
+            Value::ByteArray(_, v) => {
+                self.write_len(v.len())?;
+                for el in v {
+                    self.writer.write_i8(*el)?;
+                }
+            }
+            Value::IntArray(_, v) => {
+                self.write_len(v.len())?;
+                for el in v {
+                    self.writer.write_i32::<BigEndian>(*el)?;
+                }
+            }
+            Value::LongArray(_, v) => {
+                self.write_len(v.len())?;
+                for el in v {
+                    self.writer.write_i64::<BigEndian>(*el)?;
+                }
+            }
+            Value::List(_, element_tag, len) => {
+                self.writer.write_u8(*element_tag as u8)?;
+                self.writer.write_i32::<BigEndian>(*len)?;
+                self.layers.push(Layer::List(*element_tag, *len));
+            }
+            Value::Compound(_) => self.layers.push(Layer::Compound),
+            Value::CompoundEnd | Value::ListEnd => unreachable!("handled by write"),
+        }
+        Ok(())
+    }
+}
+
+/// Get the length of a string as written in NBT, checking it fits.
+fn java_string_len(s: &str) -> Result<u16> {
+    let len = cesu8::to_java_cesu8(s).len();
+    u16::try_from(len).map_err(|_| Error::bespoke("string too long"))
+}
+
+/// Check the length of an NBT array fits.
+fn array_len(len: usize) -> Result<()> {
+    i32::try_from(len).map_err(|_| Error::bespoke("array too long"))?;
+    Ok(())
+}
+
+impl Value {
+    /// The tag of the value, `None` for the end of compounds and lists.
+    pub(crate) fn tag(&self) -> Option<Tag> {
+        Some(match self {
+            Value::CompoundEnd | Value::ListEnd => return None,
+            Value::Byte(..) => Tag::Byte,
+            Value::Short(..) => Tag::Short,
+            Value::Int(..) => Tag::Int,
+            Value::Long(..) => Tag::Long,
+            Value::Float(..) => Tag::Float,
+            Value::Double(..) => Tag::Double,
+            Value::ByteArray(..) => Tag::ByteArray,
+            Value::String(..) => Tag::String,
+            Value::List(..) => Tag::List,
+            Value::Compound(..) => Tag::Compound,
+            Value::IntArray(..) => Tag::IntArray,
+            Value::LongArray(..) => Tag::LongArray,
+        })
+    }
+
+    /// The name of the value, `None` for the end of compounds and lists.
+    pub(crate) fn name(&self) -> Option<&Name> {
+        match self {
+            Value::CompoundEnd | Value::ListEnd => None,
+            Value::Byte(n, _)
+            | Value::Short(n, _)
+            | Value::Int(n, _)
+            | Value::Long(n, _)
+            | Value::Float(n, _)
+            | Value::Double(n, _)
+            | Value::ByteArray(n, _)
+            | Value::String(n, _)
+            | Value::List(n, _, _)
+            | Value::Compound(n)
+            | Value::IntArray(n, _)
+            | Value::LongArray(n, _) => Some(n),
+        }
     }
 }
 
@@ -408,7 +1150,7 @@ fn u8_to_tag(tag: u8) -> Result<Tag> {
     Tag::try_from(tag).map_err(|_| Error::invalid_tag(tag))
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 enum Layer {
     List(Tag, i32),
     Compound,
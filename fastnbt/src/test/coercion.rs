@@ -0,0 +1,227 @@
+use serde::Deserialize;
+
+use crate::{error::ErrorKind, from_bytes_with_opts, Coercion, DeOpts, Tag};
+
+use super::builder::Builder;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Numbers {
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+}
+
+fn numbers_nbt() -> Vec<u8> {
+    Builder::new()
+        .start_compound("")
+        .byte("byte", 1)
+        .short("short", 2)
+        .int("int", 3)
+        .long("long", 4)
+        .float("float", 5.5)
+        .double("double", 6.5)
+        .end_compound()
+        .build()
+}
+
+fn opts(coercion: Coercion) -> DeOpts {
+    DeOpts::new().coercion(coercion)
+}
+
+#[test]
+fn matching_tags_work_in_every_mode() {
+    let expected = Numbers {
+        byte: 1,
+        short: 2,
+        int: 3,
+        long: 4,
+        float: 5.5,
+        double: 6.5,
+    };
+
+    for coercion in [Coercion::Visitor, Coercion::Strict, Coercion::Lenient] {
+        let v: Numbers = from_bytes_with_opts(&numbers_nbt(), opts(coercion)).unwrap();
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn strict_rejects_other_tags() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        count: i16,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .int("count", 5)
+        .end_compound()
+        .build();
+
+    let err = from_bytes_with_opts::<V>(&input, opts(Coercion::Strict)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert!(err
+        .to_string()
+        .contains("expected short for i16, found int"));
+    assert_eq!(err.path(), Some("count"));
+
+    // Widening is also rejected.
+    let input = Builder::new()
+        .start_compound("")
+        .byte("count", 5)
+        .end_compound()
+        .build();
+    assert!(from_bytes_with_opts::<V>(&input, opts(Coercion::Strict)).is_err());
+}
+
+#[test]
+fn strict_checks_unsigned_range() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        count: u8,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .byte("count", 5)
+        .end_compound()
+        .build();
+    let v: V = from_bytes_with_opts(&input, opts(Coercion::Strict)).unwrap();
+    assert_eq!(v, V { count: 5 });
+
+    let input = Builder::new()
+        .start_compound("")
+        .byte("count", -1)
+        .end_compound()
+        .build();
+    let err = from_bytes_with_opts::<V>(&input, opts(Coercion::Strict)).unwrap_err();
+    assert!(err.to_string().contains("byte -1 is out of range for u8"));
+}
+
+#[test]
+fn lenient_converts_between_integers() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        a: i8,
+        b: i64,
+        c: u16,
+        d: f32,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .long("a", 100)
+        .byte("b", -3)
+        .int("c", 60000)
+        .short("d", 7)
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes_with_opts(&input, opts(Coercion::Lenient)).unwrap();
+    assert_eq!(
+        v,
+        V {
+            a: 100,
+            b: -3,
+            c: 60000,
+            d: 7.0
+        }
+    );
+}
+
+#[test]
+fn lenient_checks_range() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        a: i8,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .int("a", 300)
+        .end_compound()
+        .build();
+
+    let err = from_bytes_with_opts::<V>(&input, opts(Coercion::Lenient)).unwrap_err();
+    assert!(err.to_string().contains("int 300 is out of range for i8"));
+}
+
+#[test]
+fn lenient_does_not_truncate_floats() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        a: i32,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .float("a", 1.5)
+        .end_compound()
+        .build();
+
+    assert!(from_bytes_with_opts::<V>(&input, opts(Coercion::Lenient)).is_err());
+}
+
+#[test]
+fn lenient_list_elements() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        list: Vec<i8>,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(2)
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes_with_opts(&input, opts(Coercion::Lenient)).unwrap();
+    assert_eq!(v.list, [1, 2]);
+    assert!(from_bytes_with_opts::<V>(&input, opts(Coercion::Strict)).is_err());
+}
+
+#[test]
+fn bool_coercion() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct V {
+        flag: bool,
+    }
+
+    let byte = |b| {
+        Builder::new()
+            .start_compound("")
+            .byte("flag", b)
+            .end_compound()
+            .build()
+    };
+    let int = Builder::new()
+        .start_compound("")
+        .int("flag", 2)
+        .end_compound()
+        .build();
+
+    for coercion in [Coercion::Visitor, Coercion::Strict, Coercion::Lenient] {
+        let v: V = from_bytes_with_opts(&byte(1), opts(coercion)).unwrap();
+        assert_eq!(v, V { flag: true });
+        let v: V = from_bytes_with_opts(&byte(0), opts(coercion)).unwrap();
+        assert_eq!(v, V { flag: false });
+    }
+
+    for coercion in [Coercion::Visitor, Coercion::Lenient] {
+        let v: V = from_bytes_with_opts(&byte(2), opts(coercion)).unwrap();
+        assert_eq!(v, V { flag: true });
+        let v: V = from_bytes_with_opts(&int, opts(coercion)).unwrap();
+        assert_eq!(v, V { flag: true });
+    }
+
+    assert!(from_bytes_with_opts::<V>(&byte(2), opts(Coercion::Strict)).is_err());
+    assert!(from_bytes_with_opts::<V>(&int, opts(Coercion::Strict)).is_err());
+}
@@ -0,0 +1,79 @@
+use std::io::Write;
+
+use flate2::write::{GzEncoder, ZlibEncoder};
+
+use super::resources::CHUNK_RAW;
+use crate::compression::{to_compressed_writer_with_opts, Compression};
+use crate::{
+    from_bytes, from_compressed_bytes, from_compressed_reader, to_gzip_writer, to_zlib_writer,
+    SerOpts, Value,
+};
+
+fn gzip(bs: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Default::default());
+    encoder.write_all(bs).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zlib(bs: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(vec![], Default::default());
+    encoder.write_all(bs).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn detect() {
+    assert_eq!(Compression::detect(CHUNK_RAW), Compression::None);
+    assert_eq!(Compression::detect(&gzip(CHUNK_RAW)), Compression::Gzip);
+    assert_eq!(Compression::detect(&zlib(CHUNK_RAW)), Compression::Zlib);
+    assert_eq!(Compression::detect(&[]), Compression::None);
+    assert_eq!(Compression::detect(&[0x1f]), Compression::None);
+
+    // A String root followed by a byte that passes the Zlib header checksum.
+    assert_eq!(Compression::detect(&[0x08, 0x1d, b'a']), Compression::None);
+}
+
+#[test]
+fn from_compressed_bytes_detects_all() {
+    let expected: Value = from_bytes(CHUNK_RAW).unwrap();
+
+    for bs in [CHUNK_RAW.to_vec(), gzip(CHUNK_RAW), zlib(CHUNK_RAW)] {
+        let v: Value = from_compressed_bytes(&bs).unwrap();
+        assert_eq!(expected, v);
+    }
+}
+
+#[test]
+fn from_compressed_reader_detects_all() {
+    let expected: Value = from_bytes(CHUNK_RAW).unwrap();
+
+    for bs in [CHUNK_RAW.to_vec(), gzip(CHUNK_RAW), zlib(CHUNK_RAW)] {
+        let v: Value = from_compressed_reader(bs.as_slice()).unwrap();
+        assert_eq!(expected, v);
+    }
+}
+
+#[test]
+fn compressed_writers_roundtrip() {
+    let expected: Value = from_bytes(CHUNK_RAW).unwrap();
+
+    let mut gz = vec![];
+    to_gzip_writer(&mut gz, &expected).unwrap();
+    assert_eq!(Compression::detect(&gz), Compression::Gzip);
+    assert_eq!(expected, from_compressed_bytes::<Value>(&gz).unwrap());
+
+    let mut zl = vec![];
+    to_zlib_writer(&mut zl, &expected).unwrap();
+    assert_eq!(Compression::detect(&zl), Compression::Zlib);
+    assert_eq!(expected, from_compressed_bytes::<Value>(&zl).unwrap());
+
+    let mut raw = vec![];
+    to_compressed_writer_with_opts(&mut raw, &expected, Compression::None, SerOpts::new()).unwrap();
+    assert_eq!(expected, from_bytes::<Value>(&raw).unwrap());
+}
+
+#[test]
+fn truncated_gzip_errors() {
+    let gz = gzip(CHUNK_RAW);
+    assert!(from_compressed_bytes::<Value>(&gz[..gz.len() / 2]).is_err());
+}
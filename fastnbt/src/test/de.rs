@@ -2,7 +2,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 
 use crate::error::{Error, Result};
-use crate::{from_bytes, Value};
+use crate::{from_bytes, from_bytes_with_opts, DeOpts, Value};
 use crate::{ByteArray, IntArray, LongArray, Tag};
 
 use super::builder::Builder;
@@ -1431,3 +1431,18 @@ fn tuple_struct() {
     let v: Single<Rgb> = from_bytes(&input).unwrap();
     assert!(matches!(v.val, Rgb(1, 2, 3)));
 }
+
+#[test]
+fn network_nbt_has_no_root_name() {
+    let input = Builder::new()
+        .tag(Tag::Compound)
+        .int("val", 42)
+        .end_compound()
+        .build();
+
+    let v: Single<i32> = from_bytes_with_opts(&input, DeOpts::new().network_nbt(true)).unwrap();
+    assert_eq!(v.val, 42);
+
+    // Without network NBT the start of the int field is read as the name.
+    assert!(from_bytes::<Single<i32>>(&input).is_err());
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use crate::{
+    from_bytes, from_bytes_with_opts, from_value, snbt, test::builder::Builder, to_bytes, to_value,
+    Coercion, DeOpts, Nbt, Tag, Value,
+};
+
+#[derive(Nbt, Debug, PartialEq)]
+struct Section {
+    #[nbt(rename = "Y")]
+    y: i8,
+    #[nbt(long_array, rename = "BlockStates")]
+    block_states: Vec<i64>,
+    #[nbt(int_array)]
+    biomes: Vec<i32>,
+    #[nbt(byte_array)]
+    light: Box<[i8]>,
+    #[nbt(byte_bool)]
+    lit: bool,
+    #[nbt(list_of = "compound", rename = "Palette")]
+    palette: Vec<HashMap<String, String>>,
+    extra: Option<String>,
+}
+
+fn section() -> Section {
+    Section {
+        y: -4,
+        block_states: vec![1, i64::MAX],
+        biomes: vec![2, 3],
+        light: vec![4, -5].into(),
+        lit: true,
+        palette: vec![],
+        extra: Some("x".into()),
+    }
+}
+
+#[test]
+fn serialize() {
+    let v = to_value(section()).unwrap();
+    assert_eq!(
+        v,
+        nbt!({
+            "Y": -4b,
+            "BlockStates": [L; 1, i64::MAX],
+            "biomes": [I; 2, 3],
+            "light": [B; 4, -5],
+            "lit": 1b,
+            "Palette": Value::List(Tag::Compound, vec![]),
+            "extra": "x",
+        })
+    );
+
+    let bytes = to_bytes(&section()).unwrap();
+    assert_eq!(from_bytes::<Value>(&bytes).unwrap(), v);
+
+    // NBT has no null, so None is left out.
+    let s = Section {
+        extra: None,
+        ..section()
+    };
+    let v: Value = from_bytes(&to_bytes(&s).unwrap()).unwrap();
+    assert_eq!(v.get("extra"), None);
+}
+
+#[test]
+fn roundtrip() {
+    let mut s = section();
+    assert_eq!(from_bytes::<Section>(&to_bytes(&s).unwrap()).unwrap(), s);
+    assert_eq!(from_value::<Section>(&to_value(&s).unwrap()).unwrap(), s);
+
+    s.palette
+        .push(HashMap::from([("Name".into(), "stone".into())]));
+    s.lit = false;
+    assert_eq!(from_bytes::<Section>(&to_bytes(&s).unwrap()).unwrap(), s);
+    assert_eq!(from_value::<Section>(&to_value(&s).unwrap()).unwrap(), s);
+
+    s.extra = None;
+    assert_eq!(from_bytes::<Section>(&to_bytes(&s).unwrap()).unwrap(), s);
+}
+
+#[test]
+fn deserialize_is_lenient() {
+    // Any integer is a bool, missing options are None, unknown fields are
+    // ignored.
+    let v = nbt!({
+        "Y": 1b,
+        "BlockStates": [L;],
+        "biomes": [I;],
+        "light": [B;],
+        "lit": 5b,
+        "Palette": [],
+        "unknown": {"a": [1, 2]},
+    });
+    let s: Section = from_value(&v).unwrap();
+    assert!(s.lit);
+    assert_eq!(s.extra, None);
+
+    let s: Section = snbt::from_str(
+        "{Y: 1b, BlockStates: [L;], biomes: [I;], light: [B;], lit: true, Palette: []}",
+    )
+    .unwrap();
+    assert!(s.lit);
+}
+
+#[test]
+fn byte_bool_is_strict_when_asked() {
+    let strict = || DeOpts::new().coercion(Coercion::Strict);
+    let mut v = to_value(section()).unwrap();
+    let bytes = to_bytes(&v).unwrap();
+    assert!(
+        from_bytes_with_opts::<Section>(&bytes, strict())
+            .unwrap()
+            .lit
+    );
+
+    v.insert("lit", 5i8).unwrap();
+    let bytes = to_bytes(&v).unwrap();
+    assert!(from_bytes::<Section>(&bytes).unwrap().lit);
+    assert!(from_bytes_with_opts::<Section>(&bytes, strict()).is_err());
+}
+
+#[test]
+fn byte_bool_json_roundtrip() {
+    #[derive(Nbt, Debug, PartialEq)]
+    struct Flags {
+        #[nbt(byte_bool)]
+        lit: bool,
+    }
+
+    let json = serde_json::to_string(&Flags { lit: true }).unwrap();
+    assert_eq!(json, r#"{"lit":1}"#);
+    assert_eq!(
+        serde_json::from_str::<Flags>(&json).unwrap(),
+        Flags { lit: true }
+    );
+}
+
+#[test]
+fn missing_fields() {
+    let v = nbt!({"Y": 1b, "biomes": [I;], "light": [B;], "lit": 0B, "Palette": []});
+    let err = from_value::<Section>(&v).unwrap_err();
+    assert!(err.to_string().contains("BlockStates"), "{}", err);
+
+    let v = nbt!({"BlockStates": [L;], "biomes": [I;], "light": [B;], "lit": 0B, "Palette": []});
+    let err = from_value::<Section>(&v).unwrap_err();
+    assert!(err.to_string().contains('Y'), "{}", err);
+}
+
+#[test]
+fn wrong_types() {
+    // A List is not a LongArray.
+    let bytes = Builder::new()
+        .start_compound("")
+        .start_list("BlockStates", Tag::Long, 0)
+        .end_compound()
+        .build();
+    assert!(from_bytes::<Section>(&bytes).is_err());
+}
+
+#[derive(Nbt, Debug, PartialEq)]
+struct Generic<'a, T> {
+    name: &'a str,
+    #[nbt(list_of = "int")]
+    values: Vec<T>,
+    r#type: T,
+}
+
+#[test]
+fn generics_and_borrowing() {
+    let g = Generic {
+        name: "g",
+        values: Vec::<i32>::new(),
+        r#type: 1,
+    };
+    let bytes = to_bytes(&g).unwrap();
+    assert_eq!(
+        from_bytes::<Value>(&bytes).unwrap(),
+        nbt!({"name": "g", "values": Value::List(Tag::Int, vec![]), "type": 1})
+    );
+    assert_eq!(from_bytes::<Generic<i32>>(&bytes).unwrap(), g);
+}
@@ -0,0 +1,261 @@
+use crate::{
+    diff, diff::Change, from_bytes, patch, test::resources::CHUNK_RAW_WITH_ENTITIES, Tag, Value,
+};
+
+/// Check that patching `a` with the diff gives `b`, and return the diff.
+fn roundtrip(a: &Value, b: &Value) -> Vec<Change> {
+    let changes = diff(a, b);
+    let mut patched = a.clone();
+    patch(&mut patched, &changes).unwrap();
+    assert_eq!(&patched, b);
+    changes
+}
+
+#[test]
+fn no_changes() {
+    let v = nbt!({"a": [1, 2], "b": {"c": "d"}, "e": [L; 1, 2]});
+    assert_eq!(roundtrip(&v, &v), []);
+}
+
+#[test]
+fn compound_changes() {
+    let changes = roundtrip(
+        &nbt!({"a": 1, "b": {"c": 1b, "x/y~": 2s}, "d": "gone"}),
+        &nbt!({"a": 1, "b": {"c": 2b, "x/y~": 2s, "e": 3L}}),
+    );
+    assert_eq!(
+        changes,
+        [
+            Change::ValueChanged {
+                path: "/b/c".to_owned(),
+                from: nbt!(1b),
+                to: nbt!(2b),
+            },
+            Change::Added {
+                path: "/b/e".to_owned(),
+                value: nbt!(3L),
+            },
+            Change::Removed {
+                path: "/d".to_owned(),
+                value: nbt!("gone"),
+            },
+        ]
+    );
+
+    let changes = roundtrip(&nbt!({"x/y~": 1}), &nbt!({"x/y~": 2}));
+    assert_eq!(changes[0].path(), "/x~1y~0");
+}
+
+#[test]
+fn type_changes() {
+    let changes = roundtrip(
+        &nbt!({"a": 1, "b": [1, 2], "c": []}),
+        &nbt!({"a": "1", "b": ["1"], "c": [1s]}),
+    );
+    assert_eq!(
+        changes,
+        [
+            Change::TypeChanged {
+                path: "/a".to_owned(),
+                from: nbt!(1),
+                to: nbt!("1"),
+            },
+            Change::TypeChanged {
+                path: "/b".to_owned(),
+                from: nbt!([1, 2]),
+                to: nbt!(["1"]),
+            },
+            Change::TypeChanged {
+                path: "/c".to_owned(),
+                from: nbt!([]),
+                to: nbt!([1s]),
+            },
+        ]
+    );
+
+    // Emptying a list and changing its type keeps the new type.
+    let changes = roundtrip(&nbt!([1]), &Value::List(Tag::String, vec![]));
+    assert_eq!(
+        changes,
+        [Change::TypeChanged {
+            path: "".to_owned(),
+            from: nbt!([1]),
+            to: Value::List(Tag::String, vec![]),
+        }]
+    );
+    roundtrip(
+        &Value::List(Tag::Int, vec![]),
+        &Value::List(Tag::String, vec![]),
+    );
+
+    // Empty lists read from NBT have the End tag, which is not a change.
+    assert_eq!(
+        diff(
+            &Value::List(Tag::End, vec![]),
+            &Value::List(Tag::Int, vec![])
+        ),
+        []
+    );
+
+    assert_eq!(
+        roundtrip(&nbt!(1), &nbt!(2_i64)),
+        [Change::TypeChanged {
+            path: "".to_owned(),
+            from: nbt!(1),
+            to: nbt!(2_i64),
+        }]
+    );
+}
+
+#[test]
+fn nan_is_unchanged() {
+    // NaN is not equal to itself, so `roundtrip` can't be used here.
+    let v = nbt!({"f": f32::NAN, "d": [f64::NAN], "c": {"d": -f64::NAN}});
+    assert_eq!(diff(&v, &v), []);
+
+    let changes = diff(&nbt!({"f": 1.0f}), &nbt!({"f": f32::NAN}));
+    assert_eq!(changes.len(), 1);
+    let mut x = nbt!({"f": 1.0f});
+    patch(&mut x, &changes).unwrap();
+    assert!(x["f"].as_f64().unwrap().is_nan());
+
+    // The replaced value must still match, NaN included.
+    let mut x = nbt!({"c": {"d": f64::NAN}});
+    patch(
+        &mut x,
+        &diff(&nbt!({"c": {"d": f64::NAN}}), &nbt!({"c": 1})),
+    )
+    .unwrap();
+    assert_eq!(x, nbt!({"c": 1}));
+
+    let mut x = nbt!({"c": [f32::NAN]});
+    patch(&mut x, &diff(&nbt!({"c": [f32::NAN]}), &nbt!({}))).unwrap();
+    assert_eq!(x, nbt!({}));
+}
+
+#[test]
+fn list_changes() {
+    let a = nbt!([{"Slot": 0B}, {"Slot": 1b}, {"Slot": 2b}, {"Slot": 3b}]);
+    let b = nbt!([{"Slot": 0B}, {"Slot": 5b}]);
+    let changes = roundtrip(&a, &b);
+    let paths: Vec<_> = changes.iter().map(Change::path).collect();
+    assert_eq!(paths, ["/1/Slot", "/3", "/2"]);
+
+    let changes = roundtrip(&b, &a);
+    let paths: Vec<_> = changes.iter().map(Change::path).collect();
+    assert_eq!(paths, ["/1/Slot", "/2", "/3"]);
+
+    // Removing everything keeps the type of the list.
+    let mut empty = nbt!([1s]);
+    patch(
+        &mut empty,
+        &diff(&nbt!([1s]), &Value::List(Tag::Short, vec![])),
+    )
+    .unwrap();
+    assert_eq!(empty, Value::List(Tag::Short, vec![]));
+}
+
+#[test]
+fn array_changes() {
+    let changes = roundtrip(&nbt!([L; 1, 2, 3, 4, 5, 6]), &nbt!([L; 1, 0, 0, 4, 5, 0]));
+    assert_eq!(
+        changes,
+        [
+            Change::ArrayRange {
+                path: "".to_owned(),
+                start: 1,
+                from: nbt!([L; 2, 3]),
+                to: nbt!([L; 0, 0]),
+            },
+            Change::ArrayRange {
+                path: "".to_owned(),
+                start: 5,
+                from: nbt!([L; 6]),
+                to: nbt!([L; 0]),
+            },
+        ]
+    );
+
+    let changes = roundtrip(
+        &nbt!({"a": [I; 1, 2, 3, 4]}),
+        &nbt!({"a": [I; 1, 9, 9, 9, 4]}),
+    );
+    assert_eq!(
+        changes,
+        [Change::ArrayRange {
+            path: "/a".to_owned(),
+            start: 1,
+            from: nbt!([I; 2, 3]),
+            to: nbt!([I; 9, 9, 9]),
+        }]
+    );
+
+    roundtrip(&nbt!([B; 1, 2]), &nbt!([B;]));
+    roundtrip(&nbt!([B;]), &nbt!([B; 1, 2]));
+    roundtrip(&nbt!([I; 1, 1, 1]), &nbt!([I; 1, 1]));
+}
+
+#[test]
+fn chunk_roundtrip() {
+    let a: Value = from_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    let mut b = a.clone();
+    b["Level"]["xPos"] = nbt!(1000);
+    b["Level"].remove("LastUpdate");
+    b["Level"].insert("New", nbt!([1, 2])).unwrap();
+
+    let changes = roundtrip(&a, &b);
+    assert_eq!(changes.len(), 3);
+    assert_eq!(roundtrip(&b, &a).len(), 3);
+}
+
+#[test]
+fn patch_checks_changes() {
+    let changes = diff(
+        &nbt!({"a": 1, "b": [L; 1, 2]}),
+        &nbt!({"a": 2, "b": [L; 1, 3]}),
+    );
+
+    let mut other = nbt!({"a": 1, "b": [L; 5, 5]});
+    let err = patch(&mut other, &changes).unwrap_err();
+    assert!(err.to_string().contains("cannot apply change to /b"));
+    // Nothing was applied, even though the first change did apply.
+    assert_eq!(other, nbt!({"a": 1, "b": [L; 5, 5]}));
+
+    let added = diff(&nbt!({}), &nbt!({"a": 1}));
+    let mut other = nbt!({"a": 1});
+    assert!(patch(&mut other, &added).is_err());
+
+    let added = diff(&nbt!([]), &nbt!([1]));
+    let mut other = nbt!(["a"]);
+    assert!(patch(&mut other, &added).is_err());
+
+    let removed = diff(&nbt!({"a": {"b": 1}}), &nbt!({"a": {}}));
+    assert!(patch(&mut nbt!({}), &removed).is_err());
+    assert!(patch(&mut nbt!({"a": {"b": 2}}), &removed).is_err());
+}
+
+#[test]
+fn display() {
+    let lines: Vec<String> = diff(
+        &nbt!({"a": 1, "b": [I; 1, 2], "c": 1b, "d": 1}),
+        &nbt!({"a": 2, "b": [I; 1, 3], "d": "1", "e": [1s]}),
+    )
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+    assert_eq!(
+        lines,
+        [
+            "~ /a: 1 -> 2",
+            "~ /b/1..2: [I;2] -> [I;3]",
+            "- /c: 1b",
+            "~ /d: 1 -> \"1\" (int -> string)",
+            "+ /e: [1s]",
+        ]
+    );
+
+    assert_eq!(
+        diff(&nbt!([1]), &nbt!(["a"]))[0].to_string(),
+        "~ (root): [1] -> [\"a\"] (list of int -> list of string)"
+    );
+}
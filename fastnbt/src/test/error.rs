@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::builder::Builder;
+use crate::error::ErrorKind;
+use crate::{from_bytes, from_reader, snbt, Tag, Value};
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Chunk {
+    sections: Vec<Section>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Section {
+    block_states: BlockStates,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct BlockStates {
+    palette: Vec<HashMap<String, String>>,
+}
+
+fn chunk_with_bad_name() -> Vec<u8> {
+    let mut builder = Builder::new()
+        .start_compound("")
+        .start_list("sections", Tag::Compound, 2);
+
+    for i in 0..2 {
+        builder = builder
+            .start_compound("block_states")
+            .start_list("palette", Tag::Compound, 2)
+            .string("Name", "minecraft:air")
+            .end_anon_compound();
+
+        builder = if i == 1 {
+            builder.int("Name", 1).end_anon_compound()
+        } else {
+            builder
+                .string("Name", "minecraft:stone")
+                .end_anon_compound()
+        };
+
+        builder = builder.end_compound().end_anon_compound();
+    }
+
+    builder.end_compound().build()
+}
+
+#[test]
+fn error_has_path() {
+    let input = chunk_with_bad_name();
+    let err = from_bytes::<Chunk>(&input).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert_eq!(err.path(), Some("sections[1].block_states.palette[1].Name"));
+    assert!(err
+        .to_string()
+        .contains("sections[1].block_states.palette[1].Name"));
+}
+
+#[test]
+fn error_has_offset() {
+    let input = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .tag(Tag::String)
+        .name("b")
+        .raw_len(2)
+        .raw_bytes(&[0xff, 0xff])
+        .end_compound()
+        .build();
+
+    let err = from_bytes::<Value>(&input).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Nonunicode);
+    // Root, then the int, then the tag and name, length and bytes of the string.
+    assert_eq!(err.offset(), Some(3 + 8 + 4 + 2 + 2));
+    assert_eq!(err.path(), Some("b"));
+
+    let err = from_reader::<_, Value>(input.as_slice()).unwrap_err();
+    assert_eq!(err.offset(), Some(3 + 8 + 4 + 2 + 2));
+}
+
+#[test]
+fn error_kinds() {
+    let err = from_bytes::<Value>(&[Tag::Compound as u8, 0, 0, 13]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidTag);
+    assert_eq!(err.path(), None);
+    assert_eq!(err.offset(), Some(4));
+
+    let err = from_bytes::<Value>(&[Tag::Compound as u8, 0, 0, Tag::Int as u8]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let err = from_bytes::<Value>(&[Tag::String as u8, 0, 0]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+
+    let input = Builder::new()
+        .start_compound("")
+        .tag(Tag::IntArray)
+        .name("a")
+        .int_payload(-1)
+        .end_compound()
+        .build();
+    let err = from_bytes::<Value>(&input).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        a: i32,
+    }
+    let err =
+        from_bytes::<V>(&Builder::new().start_compound("").end_compound().build()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Custom);
+}
+
+#[test]
+fn snbt_error_has_offset() {
+    let err = snbt::from_str::<Value>("{a: 1, b: [1, 2}").unwrap_err();
+    assert_eq!(err.offset(), Some(15));
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    from_bytes_with_opts, from_reader_with_opts, to_bytes_with_opts, ByteArray, DeOpts, Flavour,
+    IntArray, LongArray, SerOpts, Tag, Value,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Everything {
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+    string: String,
+    list: Vec<i32>,
+    bytes: ByteArray,
+    ints: IntArray,
+    longs: LongArray,
+    nested: HashMap<String, i64>,
+}
+
+fn everything() -> Everything {
+    Everything {
+        byte: -1,
+        short: 300,
+        int: -70000,
+        long: i64::MIN,
+        float: 1.5,
+        double: -2.25,
+        string: "héllo 😀".to_owned(),
+        list: vec![1, -1, i32::MAX],
+        bytes: ByteArray::new(vec![1, 2, -3]),
+        ints: IntArray::new(vec![0, -64, 64, i32::MIN]),
+        longs: LongArray::new(vec![i64::MAX, -1, 0]),
+        nested: HashMap::from([("a".to_owned(), 1), ("b".to_owned(), -2)]),
+    }
+}
+
+fn roundtrip(flavour: Flavour) {
+    let bs = to_bytes_with_opts(&everything(), SerOpts::new().flavour(flavour)).unwrap();
+
+    let v: Everything = from_bytes_with_opts(&bs, DeOpts::new().flavour(flavour)).unwrap();
+    assert_eq!(everything(), v);
+
+    let v: Value = from_bytes_with_opts(&bs, DeOpts::new().flavour(flavour)).unwrap();
+    let bs2 = to_bytes_with_opts(&v, SerOpts::new().flavour(flavour)).unwrap();
+    let v2: Everything = from_bytes_with_opts(&bs2, DeOpts::new().flavour(flavour)).unwrap();
+    assert_eq!(everything(), v2);
+}
+
+#[test]
+fn ignore_unknown_fields() {
+    #[derive(Deserialize)]
+    struct Only {
+        string: String,
+        nested: HashMap<String, i64>,
+    }
+
+    for flavour in [
+        Flavour::JavaBigEndian,
+        Flavour::BedrockLittleEndian,
+        Flavour::BedrockNetwork,
+    ] {
+        let bs = to_bytes_with_opts(&everything(), SerOpts::new().flavour(flavour)).unwrap();
+
+        let v: Only = from_bytes_with_opts(&bs, DeOpts::new().flavour(flavour)).unwrap();
+        assert_eq!(v.string, everything().string);
+        assert_eq!(v.nested, everything().nested);
+
+        let v: Only = from_reader_with_opts(bs.as_slice(), DeOpts::new().flavour(flavour)).unwrap();
+        assert_eq!(v.nested, everything().nested);
+    }
+}
+
+#[test]
+fn roundtrip_java() {
+    roundtrip(Flavour::JavaBigEndian);
+}
+
+#[test]
+fn roundtrip_bedrock_little_endian() {
+    roundtrip(Flavour::BedrockLittleEndian);
+}
+
+#[test]
+fn roundtrip_bedrock_network() {
+    roundtrip(Flavour::BedrockNetwork);
+}
+
+#[test]
+fn little_endian_layout() {
+    #[derive(Serialize)]
+    struct V {
+        a: i32,
+    }
+
+    let bs = to_bytes_with_opts(
+        &V { a: 1 },
+        SerOpts::new().flavour(Flavour::BedrockLittleEndian),
+    )
+    .unwrap();
+
+    let expected = [
+        Tag::Compound as u8,
+        0,
+        0, // empty root name
+        Tag::Int as u8,
+        1,
+        0, // name length
+        b'a',
+        1,
+        0,
+        0,
+        0, // value
+        Tag::End as u8,
+    ];
+    assert_eq!(bs, expected);
+}
+
+#[test]
+fn network_layout() {
+    #[derive(Serialize)]
+    struct V {
+        a: i32,
+        b: i64,
+        c: Vec<i8>,
+    }
+
+    let bs = to_bytes_with_opts(
+        &V {
+            a: -1,
+            b: 64,
+            c: vec![7],
+        },
+        SerOpts::new().flavour(Flavour::BedrockNetwork),
+    )
+    .unwrap();
+
+    let expected = [
+        Tag::Compound as u8,
+        0, // root name length varint
+        Tag::Int as u8,
+        1,
+        b'a',
+        1, // zigzag -1
+        Tag::Long as u8,
+        1,
+        b'b',
+        0x80,
+        0x01, // zigzag 64
+        Tag::List as u8,
+        1,
+        b'c',
+        Tag::Byte as u8,
+        2, // zigzag 1
+        7,
+        Tag::End as u8,
+    ];
+    assert_eq!(bs, expected);
+}
+
+#[test]
+fn bedrock_strings_are_utf8() {
+    #[derive(Serialize)]
+    struct V<'a> {
+        s: &'a str,
+    }
+
+    // Java would encode this emoji as two 3-byte surrogates.
+    let bs = to_bytes_with_opts(
+        &V { s: "😀" },
+        SerOpts::new().flavour(Flavour::BedrockLittleEndian),
+    )
+    .unwrap();
+
+    assert_eq!(&bs[bs.len() - 5..bs.len() - 1], "😀".as_bytes());
+}
+
+#[test]
+fn wrong_flavour_errors() {
+    let bs = to_bytes_with_opts(
+        &everything(),
+        SerOpts::new().flavour(Flavour::BedrockLittleEndian),
+    )
+    .unwrap();
+
+    assert!(from_bytes_with_opts::<Everything>(&bs, DeOpts::new()).is_err());
+}
@@ -0,0 +1,189 @@
+use crate::{
+    fmt::{self, bytes_to_string, FmtOpts, Style},
+    Tag, Value,
+};
+
+use super::builder::Builder;
+
+fn tree() -> FmtOpts {
+    FmtOpts::new().style(Style::Tree).indent(2)
+}
+
+/// Compounds below have a single entry each, so the order of keys in a
+/// `CompoundMap` doesn't matter.
+fn nested() -> Value {
+    nbt!({
+        "Level": {
+            "Items": [{"id": "minecraft:stone"}, {"id": "a b"}],
+        },
+    })
+}
+
+#[test]
+fn snbt_compact() {
+    assert_eq!(
+        fmt::to_string(&nested(), FmtOpts::new().indent(0)),
+        r#"{Level:{Items:[{id:"minecraft:stone"},{id:"a b"}]}}"#
+    );
+    assert_eq!(
+        fmt::to_string(&nbt!([B; 1, 2]), FmtOpts::new().indent(0)),
+        "[B;1b,2b]"
+    );
+    assert_eq!(
+        fmt::to_string(&nbt!([1.5f, 2.0f]), FmtOpts::new().indent(0)),
+        "[1.5f,2.0f]"
+    );
+}
+
+#[test]
+fn snbt_matches_serializer() {
+    let value = nbt!({"weird key": [L; 1, -2], "s": "quote\"d", "n": [[], [3s]]});
+    let one = nbt!({"weird key": [L; 1, -2]});
+    assert_eq!(
+        fmt::to_string(&one, FmtOpts::new().indent(0)),
+        crate::snbt::to_string(&one).unwrap()
+    );
+
+    // Pretty SNBT reads back to the same value.
+    let text = fmt::to_string(&value, FmtOpts::new());
+    assert_eq!(crate::snbt::from_str::<Value>(&text).unwrap(), value);
+}
+
+#[test]
+fn snbt_non_finite() {
+    // Printing never fails, but only the serializer rejects these.
+    let value = Value::List(Tag::Float, vec![Value::Float(f32::NAN)]);
+    assert_eq!(fmt::to_string(&value, FmtOpts::new().indent(0)), "[NaNf]");
+    assert_eq!(Value::Double(f64::NEG_INFINITY).to_string(), "-infd");
+    assert!(crate::snbt::to_string(&value).is_err());
+}
+
+#[test]
+fn snbt_pretty() {
+    assert_eq!(
+        fmt::to_string(&nested(), FmtOpts::new().indent(2)),
+        r#"{
+  Level: {
+    Items: [
+      {
+        id: "minecraft:stone"
+      },
+      {
+        id: "a b"
+      }
+    ]
+  }
+}"#
+    );
+    assert_eq!(
+        fmt::to_string(&nbt!({"a": [1, 2], "b": {}}), FmtOpts::new())
+            .lines()
+            .filter(|line| line.contains("a: [1, 2]") || line.contains("b: {}"))
+            .count(),
+        2
+    );
+    assert_eq!(fmt::to_string(&nbt!([]), FmtOpts::new()), "[]");
+}
+
+#[test]
+fn snbt_without_types() {
+    let opts = FmtOpts::new().indent(0).types(false);
+    assert_eq!(fmt::to_string(&nbt!([1b, 2b]), opts.clone()), "[1,2]");
+    assert_eq!(fmt::to_string(&nbt!([L; 1, 2]), opts), "[L;1,2]");
+}
+
+#[test]
+fn tree_view() {
+    assert_eq!(
+        fmt::to_string(&nested(), tree().types(false)),
+        r#"Level:
+  Items: 2 entries
+    [0]:
+      id: "minecraft:stone"
+    [1]:
+      id: "a b""#
+    );
+    assert_eq!(
+        fmt::to_string(&nested(), tree()),
+        r#"Level (compound):
+  Items (list of compound): 2 entries
+    [0] (compound):
+      id (string): "minecraft:stone"
+    [1] (compound):
+      id (string): "a b""#
+    );
+    assert_eq!(fmt::to_string(&nbt!(1.5f), tree()), "(float): 1.5");
+    assert_eq!(
+        fmt::to_string(&nbt!([1s]), tree().types(false)),
+        "1 entry\n  [0]: 1"
+    );
+}
+
+#[test]
+fn truncate_arrays() {
+    let value = nbt!({"longs": [L; 1, 2, 3]});
+    assert_eq!(
+        fmt::to_string(&value, FmtOpts::new().indent(0).truncate_arrays(2)),
+        "{longs:[L;3 longs]}"
+    );
+    assert_eq!(
+        fmt::to_string(&value, tree().types(false).truncate_arrays(2)),
+        "longs: [L; 3 longs]"
+    );
+    assert_eq!(
+        fmt::to_string(&value, tree().types(false).truncate_arrays(3)),
+        "longs: [L; 1, 2, 3]"
+    );
+    assert_eq!(
+        fmt::to_string(&nbt!([B; 1]), FmtOpts::new().truncate_arrays(0)),
+        "[B; 1 byte]"
+    );
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        nested().to_string(),
+        r#"{Level:{Items:[{id:"minecraft:stone"},{id:"a b"}]}}"#
+    );
+    assert_eq!(
+        format!("{:#}", nbt!({"a": [I; 1, 2]})),
+        "{\n    a: [I; 1, 2]\n}"
+    );
+}
+
+#[test]
+fn raw_bytes() {
+    let input = Builder::new()
+        .start_compound("Root")
+        .int("a", 1)
+        .start_list("b", Tag::Compound, 1)
+        .string("c", "x")
+        .end_compound()
+        .byte_array("d", &[1, 2])
+        .end_compound()
+        .build();
+
+    assert_eq!(
+        bytes_to_string(&input, FmtOpts::new().indent(0)).unwrap(),
+        r#"{a:1,b:[{c:"x"}],d:[B;1b,2b]}"#
+    );
+    assert_eq!(
+        bytes_to_string(&input, tree().types(false)).unwrap(),
+        "Root:\n  a: 1\n  b: 1 entry\n    [0]:\n      c: \"x\"\n  d: [B; 1, 2]"
+    );
+
+    // Prints what it can before failing.
+    let mut out = Vec::new();
+    let err = fmt::reader_to_writer(
+        &input[..input.len() - 3],
+        &mut out,
+        FmtOpts::new().indent(0),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        crate::stream::ErrorKind::UnexpectedEof
+    ));
+    assert_eq!(out, br#"{a:1,b:[{c:"x"}]"#);
+}
@@ -1,6 +1,6 @@
-use std::{collections::HashMap, iter::FromIterator};
+use std::iter::FromIterator;
 
-use crate::{error::Result, from_bytes, test::builder::Builder, Tag, Value};
+use crate::{error::Result, from_bytes, test::builder::Builder, CompoundMap, Tag, Value};
 
 /// Bugs found via cargo-fuzz.
 
@@ -29,7 +29,7 @@ fn float_double() {
     //           C   name  f  name  ............ end compound
     let input = [10, 0, 0, 5, 0, 0, 0, 0, 0, 10, 0];
     let v: Value = from_bytes(&input).unwrap();
-    let expected = Value::Compound(HashMap::from_iter([(
+    let expected = Value::Compound(CompoundMap::from_iter([(
         "".to_string(),
         Value::Float(1.4e-44),
     )]));
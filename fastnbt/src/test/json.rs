@@ -0,0 +1,157 @@
+use serde_json::json;
+
+use crate::{
+    error::ErrorKind,
+    from_bytes,
+    json::{from_plain, from_typed, to_plain, to_typed},
+    test::resources::CHUNK_RAW_WITH_ENTITIES,
+    Tag, Value,
+};
+
+#[test]
+fn typed_roundtrip() {
+    let v = nbt!({
+        "byte": 1_i8,
+        "short": 2_i16,
+        "int": 3,
+        "long": 4_i64,
+        "float": 5.5_f32,
+        "double": 6.5,
+        "string": "seven",
+        "bytes": [B; 1, 2],
+        "ints": [I; 3, 4],
+        "longs": [L; 5, i64::MAX],
+        "list": [{"a": 1}, {"b": [1_i16]}],
+        "empty": Value::List(Tag::Compound, vec![]),
+        "inf": f32::INFINITY,
+        "neg_inf": f64::NEG_INFINITY,
+    });
+
+    assert_eq!(from_typed(&to_typed(&v)).unwrap(), v);
+}
+
+#[test]
+fn typed_roundtrip_chunk() {
+    let chunk: Value = from_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    assert_eq!(from_typed(&to_typed(&chunk)).unwrap(), chunk);
+}
+
+#[test]
+fn typed_nan() {
+    let typed = to_typed(&Value::Float(f32::NAN));
+    assert_eq!(typed, json!({"type": "float", "value": "NaN"}));
+    assert!(matches!(from_typed(&typed).unwrap(), Value::Float(f) if f.is_nan()));
+}
+
+#[test]
+fn typed_encoding() {
+    assert_eq!(
+        to_typed(&nbt!({"list": [1_i16], "empty": Value::List(Tag::String, vec![])})),
+        json!({
+            "type": "compound",
+            "value": {
+                "list": {
+                    "type": "list",
+                    "element": "short",
+                    "value": [{"type": "short", "value": 1}],
+                },
+                "empty": {"type": "list", "element": "string", "value": []},
+            },
+        })
+    );
+}
+
+#[test]
+fn typed_errors() {
+    let err = from_typed(&json!({"type": "byte", "value": 300})).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert!(err.to_string().contains("expected byte, found 300"));
+
+    let err = from_typed(&json!({"type": "bool", "value": true})).unwrap_err();
+    assert!(err.to_string().contains("unknown type \"bool\""));
+
+    let err = from_typed(&json!({"type": "int"})).unwrap_err();
+    assert!(err.to_string().contains("missing \"value\""));
+
+    let err = from_typed(&json!({
+        "type": "compound",
+        "value": {
+            "items": {
+                "type": "list",
+                "element": "int",
+                "value": [{"type": "int", "value": 1}, {"type": "long", "value": 2}],
+            },
+        },
+    }))
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("expected int in list of int, found long"));
+    assert_eq!(err.path(), Some("items[1]"));
+}
+
+#[test]
+fn plain_encoding() {
+    let v = nbt!({
+        "byte": 1_i8,
+        "double": 1.5,
+        "longs": [L; 1, 2],
+        "list": ["a", "b"],
+        "nan": f64::NAN,
+    });
+
+    assert_eq!(
+        to_plain(&v),
+        json!({
+            "byte": 1,
+            "double": 1.5,
+            "longs": [1, 2],
+            "list": ["a", "b"],
+            "nan": null,
+        })
+    );
+}
+
+#[test]
+fn plain_guesses_types() {
+    let v = from_plain(&json!({
+        "small": 1,
+        "big": 10_000_000_000_i64,
+        "float": 1.5,
+        "flag": true,
+        "name": "Steve",
+        "empty": [],
+        "widened": [1, 10_000_000_000_i64],
+        "doubles": [1, 1.5],
+        "nested": [[1], []],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        v,
+        nbt!({
+            "small": 1,
+            "big": 10_000_000_000_i64,
+            "float": 1.5,
+            "flag": 1_i8,
+            "name": "Steve",
+            "empty": Value::List(Tag::End, vec![]),
+            "widened": [1_i64, 10_000_000_000_i64],
+            "doubles": [1.0, 1.5],
+            "nested": Value::List(Tag::List, vec![nbt!([1]), Value::List(Tag::End, vec![])]),
+        })
+    );
+}
+
+#[test]
+fn plain_errors() {
+    let err = from_plain(&json!({"a": {"b": null}})).unwrap_err();
+    assert!(err.to_string().contains("null has no NBT equivalent"));
+    assert_eq!(err.path(), Some("a.b"));
+
+    let err = from_plain(&json!({"list": [1, "a"]})).unwrap_err();
+    assert!(err.to_string().contains("array mixes int and string"));
+    assert_eq!(err.path(), Some("list"));
+
+    assert!(from_plain(&json!(u64::MAX)).is_err());
+}
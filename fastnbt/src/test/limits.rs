@@ -0,0 +1,338 @@
+use serde::Deserialize;
+
+use crate::{
+    error::ErrorKind, from_bytes, from_bytes_with_opts, from_reader_with_opts, stream, DeOpts,
+    IntArray, LongArray, Tag, Value,
+};
+
+use super::builder::Builder;
+
+/// A root compound with compounds nested inside it, `depth` deep in total.
+fn nested_compounds(depth: usize) -> Vec<u8> {
+    let mut builder = Builder::new().start_compound("");
+    for _ in 1..depth {
+        builder = builder.start_compound("a");
+    }
+    for _ in 0..depth {
+        builder = builder.end_compound();
+    }
+    builder.build()
+}
+
+/// A root compound holding lists nested inside each other, `depth` deep in
+/// total.
+fn nested_lists(depth: usize) -> Vec<u8> {
+    let mut builder = Builder::new()
+        .start_compound("")
+        .start_list("a", Tag::List, 1);
+    for _ in 2..depth - 1 {
+        builder = builder.start_anon_list(Tag::List, 1);
+    }
+    builder.start_anon_list(Tag::Int, 0).end_compound().build()
+}
+
+#[test]
+fn default_depth_is_unlimited() {
+    // Debug builds need a bigger stack than test threads have for deep input.
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            assert!(from_bytes::<Value>(&nested_compounds(600)).is_ok());
+            assert!(from_bytes::<Value>(&nested_lists(600)).is_ok());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn deep_input_does_not_overflow_stack() {
+    let opts = || DeOpts::new().max_depth(128);
+
+    let err = from_bytes_with_opts::<Value>(&nested_compounds(100_000), opts()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+    assert!(err
+        .to_string()
+        .contains("nesting depth (129) greater than max depth (128)"));
+
+    let err = from_bytes_with_opts::<Value>(&nested_lists(100_000), opts()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+}
+
+#[test]
+fn max_depth() {
+    let opts = || DeOpts::new().max_depth(4);
+    assert!(from_bytes_with_opts::<Value>(&nested_compounds(4), opts()).is_ok());
+    assert!(from_bytes_with_opts::<Value>(&nested_compounds(5), opts()).is_err());
+    assert!(from_bytes_with_opts::<Value>(&nested_lists(4), opts()).is_ok());
+    assert!(from_bytes_with_opts::<Value>(&nested_lists(5), opts()).is_err());
+}
+
+#[test]
+fn max_depth_applies_to_ignored_values() {
+    #[derive(Deserialize)]
+    struct Empty {}
+
+    let opts = || DeOpts::new().max_depth(4);
+    assert!(from_bytes_with_opts::<Empty>(&nested_compounds(4), opts()).is_ok());
+    assert!(from_bytes_with_opts::<Empty>(&nested_compounds(5), opts()).is_err());
+    assert!(from_bytes_with_opts::<Empty>(&nested_lists(5), opts()).is_err());
+}
+
+#[test]
+fn max_array_len() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        ints: IntArray,
+    }
+
+    let input = Builder::new()
+        .start_compound("")
+        .int_array("ints", &[1, 2, 3])
+        .end_compound()
+        .build();
+
+    let opts = |len| DeOpts::new().max_array_len(len);
+    assert!(from_bytes_with_opts::<V>(&input, opts(3)).is_ok());
+    assert!(from_bytes_with_opts::<Value>(&input, opts(3)).is_ok());
+
+    let err = from_bytes_with_opts::<V>(&input, opts(2)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+    assert!(err
+        .to_string()
+        .contains("array length (3) greater than max array length (2)"));
+    assert_eq!(err.path(), Some("ints"));
+    assert!(from_bytes_with_opts::<Value>(&input, opts(2)).is_err());
+
+    // Lists are limited by max_seq_len instead.
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("ints", Tag::Int, 3)
+        .int_payload(1)
+        .int_payload(2)
+        .int_payload(3)
+        .end_compound()
+        .build();
+    assert!(from_bytes_with_opts::<Value>(&input, opts(2)).is_ok());
+}
+
+#[test]
+fn max_array_len_checked_before_reading() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        longs: LongArray,
+    }
+
+    // Claims to have i32::MAX longs, but has none.
+    let input = Builder::new()
+        .start_compound("")
+        .tag(Tag::LongArray)
+        .name("longs")
+        .int_payload(i32::MAX)
+        .build();
+
+    let opts = DeOpts::new().max_array_len(1024);
+    let err = from_reader_with_opts::<_, V>(input.as_slice(), opts).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+}
+
+#[test]
+fn max_array_len_applies_to_ignored_values() {
+    #[derive(Deserialize)]
+    struct Empty {}
+
+    let input = Builder::new()
+        .start_compound("")
+        .byte_array("bytes", &[1, 2, 3])
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().max_array_len(2);
+    assert!(from_bytes_with_opts::<Empty>(&input, opts).is_err());
+}
+
+#[test]
+fn max_string_len() {
+    let input = Builder::new()
+        .start_compound("")
+        .string("title", "hello")
+        .end_compound()
+        .build();
+
+    let opts = |len| DeOpts::new().max_string_len(len);
+    assert!(from_bytes_with_opts::<Value>(&input, opts(5)).is_ok());
+
+    let err = from_bytes_with_opts::<Value>(&input, opts(4)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+    assert!(err
+        .to_string()
+        .contains("string length (5) greater than max string length (4)"));
+
+    // Names are strings too.
+    let input = Builder::new()
+        .start_compound("")
+        .int("longname", 1)
+        .end_compound()
+        .build();
+    assert!(from_bytes_with_opts::<Value>(&input, opts(4)).is_err());
+}
+
+#[test]
+fn max_alloc() {
+    // 3 bytes of names, 8 of strings and 3 * 8 of the list.
+    let input = Builder::new()
+        .start_compound("")
+        .string("a", "abcd")
+        .string("b", "efgh")
+        .start_list("c", Tag::Long, 3)
+        .long_payload(1)
+        .long_payload(2)
+        .long_payload(3)
+        .end_compound()
+        .build();
+
+    let opts = |size| DeOpts::new().max_alloc(size);
+    assert!(from_bytes_with_opts::<Value>(&input, opts(35)).is_ok());
+
+    let err = from_bytes_with_opts::<Value>(&input, opts(34)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSize);
+    assert!(err
+        .to_string()
+        .contains("total size of strings, arrays and lists (35) greater than max allocation (34)"));
+}
+
+#[test]
+fn max_alloc_counts_arrays() {
+    let input = Builder::new()
+        .start_compound("")
+        .long_array("a", &[1, 2])
+        .long_array("b", &[3, 4])
+        .end_compound()
+        .build();
+
+    let opts = |size| DeOpts::new().max_alloc(size);
+    assert!(from_bytes_with_opts::<Value>(&input, opts(34)).is_ok());
+    assert!(from_bytes_with_opts::<Value>(&input, opts(33)).is_err());
+}
+
+#[test]
+fn parser_max_depth() {
+    let input = nested_compounds(3);
+    let mut parser = stream::Parser::with_opts(input.as_slice(), DeOpts::new().max_depth(2));
+    parser.next().unwrap();
+    parser.next().unwrap();
+    let err = parser.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+    assert!(err.to_string().contains("max depth (2)"));
+
+    // Skipping over values is limited too.
+    let mut parser = stream::Parser::with_opts(input.as_slice(), DeOpts::new().max_depth(2));
+    parser.next().unwrap();
+    assert!(stream::skip_compound(&mut parser).is_err());
+}
+
+#[test]
+fn parser_default_depth_is_unlimited() {
+    // Reading values one at a time does not recurse, so any depth is fine.
+    let input = nested_compounds(100_000);
+    let mut parser = stream::Parser::new(input.as_slice());
+    for _ in 0..200_000 {
+        parser.next().unwrap();
+    }
+    assert!(parser.next().unwrap_err().is_eof());
+}
+
+#[test]
+fn parser_max_array_and_string_len() {
+    let input = Builder::new()
+        .start_compound("")
+        .int_array("ints", &[1, 2, 3])
+        .string("s", "hello")
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().max_array_len(2);
+    let mut parser = stream::Parser::with_opts(input.as_slice(), opts);
+    parser.next().unwrap();
+    let err = parser.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+
+    let opts = DeOpts::new().max_string_len(4);
+    let mut parser = stream::Parser::with_opts(input.as_slice(), opts);
+    parser.next().unwrap();
+    parser.next().unwrap();
+    let err = parser.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+
+    // Also when the payloads are skipped.
+    let opts = DeOpts::new().max_array_len(2);
+    let mut parser = stream::Parser::with_opts(input.as_slice(), opts);
+    parser.set_skip_payloads(true);
+    parser.next().unwrap();
+    assert!(parser.next().is_err());
+}
+
+#[test]
+fn parser_max_alloc() {
+    let input = Builder::new()
+        .start_compound("")
+        .long_array("a", &[1, 2])
+        .long_array("b", &[3, 4])
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().max_alloc(20);
+    let mut parser = stream::Parser::with_opts(input.as_slice(), opts);
+    parser.next().unwrap();
+    parser.next().unwrap();
+    let err = parser.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+}
+
+#[test]
+fn decoder_rejects_array_before_data_arrives() {
+    let input = Builder::new()
+        .start_compound("")
+        .tag(Tag::ByteArray)
+        .name("bytes")
+        .int_payload(i32::MAX)
+        .build();
+
+    let mut decoder = stream::Decoder::with_opts(DeOpts::new().max_array_len(1024));
+    decoder.feed(&input);
+    decoder.next().unwrap();
+    let err = decoder.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+}
+
+#[test]
+fn decoder_default_max_value_len() {
+    let input = Builder::new()
+        .start_compound("")
+        .tag(Tag::LongArray)
+        .name("longs")
+        .int_payload(i32::MAX)
+        .build();
+
+    let mut decoder = stream::Decoder::new();
+    decoder.feed(&input);
+    decoder.next().unwrap();
+    let err = decoder.next().unwrap_err();
+    assert!(matches!(err.kind(), stream::ErrorKind::LimitExceeded));
+    assert!(err.to_string().contains("max value length"));
+
+    // Values within the limit wait for more data.
+    let input = Builder::new()
+        .start_compound("")
+        .long_array("longs", &[1])
+        .end_compound()
+        .build();
+    let mut decoder = stream::Decoder::new().max_value_len(20);
+    decoder.feed(&input[..input.len() - 3]);
+    decoder.next().unwrap();
+    assert_eq!(decoder.next().unwrap(), None);
+    decoder.feed(&input[input.len() - 3..]);
+    assert!(decoder.next().unwrap().is_some());
+}
@@ -1,6 +1,4 @@
-use std::collections::HashMap;
-
-use crate::{ByteArray, IntArray, LongArray, Value};
+use crate::{ByteArray, CompoundMap, IntArray, LongArray, Tag, Value};
 
 #[test]
 fn nbt() {
@@ -23,10 +21,10 @@ fn nbt() {
         Value::String("string".to_owned())
     );
 
-    assert_eq!(nbt!([]), Value::List(vec![]));
+    assert_eq!(nbt!([]), Value::List(Tag::End, vec![]));
     assert_eq!(
         nbt!([1, 3]),
-        Value::List(vec![Value::Int(1), Value::Int(3)])
+        Value::List(Tag::Int, vec![Value::Int(1), Value::Int(3)])
     );
     assert_eq!(
         nbt!([
@@ -35,18 +33,27 @@ fn nbt() {
             "Nam in lobortis quam, vel vehicula magna.",
             "Cras massa turpis, facilisis non volutpat vitae, elementum.",
         ]),
-        Value::List(vec![
-            Value::String("Lorem ipsum dolor sit amet, consectetur adipiscing elit.".to_owned()),
-            Value::String("Duis mattis massa metus, vel consequat lacus tincidunt ut.".to_owned()),
-            Value::String("Nam in lobortis quam, vel vehicula magna.".to_owned()),
-            Value::String("Cras massa turpis, facilisis non volutpat vitae, elementum.".to_owned()),
-        ])
+        Value::List(
+            Tag::String,
+            vec![
+                Value::String(
+                    "Lorem ipsum dolor sit amet, consectetur adipiscing elit.".to_owned()
+                ),
+                Value::String(
+                    "Duis mattis massa metus, vel consequat lacus tincidunt ut.".to_owned()
+                ),
+                Value::String("Nam in lobortis quam, vel vehicula magna.".to_owned()),
+                Value::String(
+                    "Cras massa turpis, facilisis non volutpat vitae, elementum.".to_owned()
+                ),
+            ]
+        )
     );
 
-    assert_eq!(nbt!({}), Value::Compound(HashMap::new()));
+    assert_eq!(nbt!({}), Value::Compound(CompoundMap::new()));
     assert_eq!(
         nbt!({ "key": "value" }),
-        Value::Compound(HashMap::from([(
+        Value::Compound(CompoundMap::from([(
             "key".to_owned(),
             Value::String("value".to_owned())
         ),]))
@@ -57,12 +64,12 @@ fn nbt() {
             "key2": 42,
             "key3": [4, 2],
         }),
-        Value::Compound(HashMap::from([
+        Value::Compound(CompoundMap::from([
             ("key1".to_owned(), Value::String("value1".to_owned())),
             ("key2".to_owned(), Value::Int(42)),
             (
                 "key3".to_owned(),
-                Value::List(vec![Value::Int(4), Value::Int(2)])
+                Value::List(Tag::Int, vec![Value::Int(4), Value::Int(2)])
             ),
         ]))
     );
@@ -83,3 +90,169 @@ fn nbt() {
         Value::LongArray(LongArray::new(vec![1, 2, 3]))
     );
 }
+
+#[test]
+fn nbt_snbt_suffixes() {
+    assert_eq!(nbt!(5b), Value::Byte(5));
+    assert_eq!(nbt!(-5B), Value::Byte(-5));
+    assert_eq!(nbt!(3s), Value::Short(3));
+    assert_eq!(nbt!(7L), Value::Long(7));
+    assert_eq!(nbt!(2.5f), Value::Float(2.5));
+    assert_eq!(nbt!(2.5e1F), Value::Float(25.0));
+    // Just above halfway between two f32s, but rounds to halfway as an f64.
+    assert_eq!(
+        nbt!(1.00000005960464477550f),
+        Value::Float(1.0 + f32::EPSILON)
+    );
+    assert_eq!(
+        nbt!(-1.00000005960464477550_f32),
+        Value::Float(-1.0 - f32::EPSILON)
+    );
+    assert_eq!(nbt!(1d), Value::Double(1.0));
+    assert_eq!(nbt!(-0.5), Value::Double(-0.5));
+    assert_eq!(nbt!(0xff), Value::Int(255));
+    assert_eq!(nbt!(0x7f_i8), Value::Byte(127));
+    assert_eq!(nbt!(0b1010), Value::Int(10));
+    assert_eq!(nbt!(1_000_000_i64), Value::Long(1_000_000));
+
+    assert_eq!(
+        nbt!({"a": 1b, "b": [1s, 2s], "c": [B; 1b, -2b], "d": [L; 1L, i64::MAX]}),
+        Value::Compound(CompoundMap::from([
+            ("a".to_owned(), Value::Byte(1)),
+            (
+                "b".to_owned(),
+                Value::List(Tag::Short, vec![Value::Short(1), Value::Short(2)])
+            ),
+            (
+                "c".to_owned(),
+                Value::ByteArray(ByteArray::new(vec![1, -2]))
+            ),
+            (
+                "d".to_owned(),
+                Value::LongArray(LongArray::new(vec![1, i64::MAX]))
+            ),
+        ]))
+    );
+}
+
+#[test]
+fn nbt_string_literals() {
+    assert_eq!(
+        nbt!("a\"b\\c\n\u{e9}"),
+        Value::String("a\"b\\c\n\u{e9}".to_owned())
+    );
+    assert_eq!(nbt!(r#"say "hi""#), Value::String(r#"say "hi""#.to_owned()));
+    assert_eq!(nbt!('a'), Value::Int('a' as i32));
+    assert_eq!(nbt!(b'a'), Value::Byte(b'a' as i8));
+    assert_eq!(nbt!(b"a\xff"), nbt!([97_i8, -1_i8]));
+}
+
+#[test]
+fn nbt_hex_looks_like_float_suffix() {
+    assert_eq!(nbt!({"a": 0x1f32}), nbt!({"a": 7986}));
+    assert_eq!(nbt!(0x1f64), Value::Int(0x1f64));
+    assert_eq!(nbt!(0xf32_i64), Value::Long(0xf32));
+    assert_eq!(
+        nbt!([I; 0xaf32]),
+        Value::IntArray(IntArray::new(vec![0xaf32]))
+    );
+    assert_eq!(
+        nbt!([L; 0x1f64]),
+        Value::LongArray(LongArray::new(vec![0x1f64]))
+    );
+}
+
+// These are compile errors inside nbt!, see the compile_fail examples in its
+// documentation.
+#[test]
+#[should_panic(expected = "out of range for i8")]
+fn nbt_literal_out_of_range() {
+    crate::macros::Literal::parse("200b");
+}
+
+#[test]
+#[should_panic(expected = "invalid integer")]
+fn nbt_literal_invalid() {
+    crate::macros::Literal::parse("0b12");
+}
+
+#[test]
+fn nbt_typed_numbers() {
+    let count = 3;
+    let scale = 0.5_f64;
+    assert_eq!(
+        nbt!({
+            "count": byte!(count),
+            "amount": short!(count * 100),
+            "id": int!(7_u8),
+            "time": long!(count),
+            "scale": float!(scale),
+            "list": [double!(1), double!(scale)],
+            "text": format!("{}!", count),
+        }),
+        Value::Compound(CompoundMap::from([
+            ("count".to_owned(), Value::Byte(3)),
+            ("amount".to_owned(), Value::Short(300)),
+            ("id".to_owned(), Value::Int(7)),
+            ("time".to_owned(), Value::Long(3)),
+            ("scale".to_owned(), Value::Float(0.5)),
+            (
+                "list".to_owned(),
+                Value::List(Tag::Double, vec![Value::Double(1.0), Value::Double(0.5)])
+            ),
+            ("text".to_owned(), Value::String("3!".to_owned())),
+        ]))
+    );
+    assert_eq!(nbt!(byte!(1)), Value::Byte(1));
+}
+
+#[test]
+#[should_panic(expected = "300 out of range for byte")]
+fn nbt_typed_number_out_of_range() {
+    let count = 300;
+    nbt!(byte!(count));
+}
+
+#[test]
+fn nbt_variable_keys() {
+    // Like serde_json's json!, keys are expressions rather than SNBT names.
+    let key = "dynamic";
+    let id = "name";
+    assert_eq!(
+        nbt!({"id": "minecraft:stone", "Count": 1b, "type": 2, key: 3, (id): 4}),
+        Value::Compound(CompoundMap::from([
+            ("id".to_owned(), Value::String("minecraft:stone".to_owned())),
+            ("Count".to_owned(), Value::Byte(1)),
+            ("type".to_owned(), Value::Int(2)),
+            ("dynamic".to_owned(), Value::Int(3)),
+            ("name".to_owned(), Value::Int(4)),
+        ]))
+    );
+}
+
+#[test]
+fn nbt_interpolation() {
+    #[derive(serde::Serialize)]
+    struct Pos {
+        x: i32,
+        y: i32,
+    }
+
+    let item = nbt!({"id": "minecraft:stone", "Count": 1b});
+    let pos = Pos { x: 1, y: 2 };
+    let names = vec!["a", "b"];
+    assert_eq!(
+        nbt!({"Items": [item.clone(), item], "Pos": pos, "Names": names, "Sum": 1 + 2, "Neg": -pos_x()}),
+        nbt!({
+            "Items": [{"id": "minecraft:stone", "Count": 1b}, {"id": "minecraft:stone", "Count": 1b}],
+            "Pos": {"x": 1, "y": 2},
+            "Names": ["a", "b"],
+            "Sum": 3,
+            "Neg": -1,
+        })
+    );
+}
+
+fn pos_x() -> i32 {
+    1
+}
@@ -11,11 +11,30 @@ mod de;
 mod value;
 
 mod builder;
+mod coercion;
+#[cfg(feature = "compression")]
+mod compression;
 mod de_arrays;
+#[cfg(feature = "derive")]
+mod derive;
+mod diff;
+mod error;
+mod flavour;
+mod fmt;
 mod fuzz;
+#[cfg(feature = "json")]
+mod json;
+mod limits;
 mod minecraft_chunk;
+mod named_root;
+mod reader;
+mod roundtrip;
 mod resources;
+mod schema;
 mod ser;
+#[cfg(feature = "serde_helpers")]
+mod serde_helpers;
+mod snbt;
 mod stream;
 mod macros;
 
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use super::builder::Builder;
+use crate::{
+    from_bytes, from_bytes_with_opts, from_value, snbt, to_bytes, to_bytes_with_opts, to_value,
+    DeOpts, Flavour, NamedRoot, SerOpts, Tag, Value,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Schematic {
+    width: i16,
+}
+
+#[test]
+fn reads_root_name() {
+    let input = Builder::new()
+        .start_compound("Schematic")
+        .short("width", 16)
+        .end_compound()
+        .build();
+
+    let v: NamedRoot<Schematic> = from_bytes(&input).unwrap();
+    assert_eq!(v.name, "Schematic");
+    assert_eq!(v.value, Schematic { width: 16 });
+
+    let v: NamedRoot<Value> = from_bytes(&input).unwrap();
+    assert_eq!(v.name, "Schematic");
+    assert_eq!(v.value, nbt!({"width": 16_i16}));
+}
+
+#[test]
+fn writes_root_name() {
+    let expected = Builder::new()
+        .start_compound("Schematic")
+        .short("width", 16)
+        .end_compound()
+        .build();
+
+    let v = NamedRoot::new("Schematic", Schematic { width: 16 });
+    assert_eq!(to_bytes(&v).unwrap(), expected);
+
+    let v = NamedRoot::new("Schematic", nbt!({"width": 16_i16}));
+    assert_eq!(to_bytes(&v).unwrap(), expected);
+}
+
+#[test]
+fn roundtrip_non_ascii_name() {
+    let v = NamedRoot::new("héllo 😀", Schematic { width: 1 });
+    let bs = to_bytes(&v).unwrap();
+    assert_eq!(v, from_bytes(&bs).unwrap());
+
+    let opts = SerOpts::new().flavour(Flavour::BedrockLittleEndian);
+    let bs = to_bytes_with_opts(&v, opts).unwrap();
+    let opts = DeOpts::new().flavour(Flavour::BedrockLittleEndian);
+    assert_eq!(v, from_bytes_with_opts(&bs, opts).unwrap());
+}
+
+#[test]
+fn network_nbt_has_empty_name() {
+    let v = NamedRoot::new("ignored", Schematic { width: 1 });
+    let bs = to_bytes_with_opts(&v, SerOpts::new().network_nbt(true)).unwrap();
+    assert_eq!(bs[0], Tag::Compound as u8);
+    assert_eq!(bs[1], Tag::Short as u8);
+
+    let back: NamedRoot<Schematic> =
+        from_bytes_with_opts(&bs, DeOpts::new().network_nbt(true)).unwrap();
+    assert_eq!(back.name, "");
+    assert_eq!(back.value, v.value);
+}
+
+#[test]
+fn value_and_snbt_drop_name() {
+    let v = NamedRoot::new("Schematic", Schematic { width: 16 });
+
+    assert_eq!(to_value(&v).unwrap(), nbt!({"width": 16_i16}));
+    let back: NamedRoot<Schematic> = from_value(&to_value(&v).unwrap()).unwrap();
+    assert_eq!(back.name, "");
+    assert_eq!(back.value, v.value);
+
+    let s = snbt::to_string(&v).unwrap();
+    assert_eq!(s, "{width:16s}");
+    let back: NamedRoot<Schematic> = snbt::from_str(&s).unwrap();
+    assert_eq!(back.value, v.value);
+}
+
+#[test]
+fn named_root_must_be_root() {
+    #[derive(Serialize)]
+    struct Outer {
+        inner: NamedRoot<Schematic>,
+    }
+
+    let v = Outer {
+        inner: NamedRoot::new("x", Schematic { width: 1 }),
+    };
+    assert!(to_bytes(&v).is_err());
+}
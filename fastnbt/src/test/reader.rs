@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::Deserialize;
+
+use super::builder::Builder;
+use super::resources::CHUNK_RAW;
+use crate::{
+    from_bytes, from_reader, from_reader_with_opts, to_bytes_with_opts, ByteArray, DeOpts, Flavour,
+    IntArray, LongArray, SerOpts, Tag, Value,
+};
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Everything {
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+    string: String,
+    list: Vec<i32>,
+    bytes: ByteArray,
+    ints: IntArray,
+    longs: LongArray,
+    nested: HashMap<String, i64>,
+}
+
+fn everything_nbt() -> Vec<u8> {
+    Builder::new()
+        .start_compound("")
+        .byte("byte", -1)
+        .short("short", 300)
+        .int("int", -70000)
+        .long("long", i64::MIN)
+        .float("float", 1.5)
+        .double("double", -2.25)
+        .string("string", "héllo 😀")
+        .start_list("list", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(-1)
+        .int_array("ints", &[0, -64, i32::MIN])
+        .byte_array("bytes", &[1, 2, -3])
+        .long_array("longs", &[i64::MAX, -1])
+        .start_compound("nested")
+        .long("a", 1)
+        .end_compound()
+        .end_compound()
+        .build()
+}
+
+#[test]
+fn reader_matches_bytes() {
+    let bs = everything_nbt();
+    let from_slice: Everything = from_bytes(&bs).unwrap();
+    let from_read: Everything = from_reader(bs.as_slice()).unwrap();
+    assert_eq!(from_slice, from_read);
+
+    let from_slice: Value = from_bytes(&bs).unwrap();
+    let from_read: Value = from_reader(Cursor::new(&bs)).unwrap();
+    assert_eq!(from_slice, from_read);
+}
+
+#[test]
+fn reader_chunk() {
+    let from_slice: Value = from_bytes(CHUNK_RAW).unwrap();
+    let from_read: Value = from_reader(CHUNK_RAW).unwrap();
+    assert_eq!(from_slice, from_read);
+}
+
+#[test]
+fn reader_gzip() {
+    let bs = everything_nbt();
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&bs).unwrap();
+    let gz = encoder.finish().unwrap();
+
+    let v: Everything = from_reader(GzDecoder::new(gz.as_slice())).unwrap();
+    assert_eq!(v.string, "héllo 😀");
+    assert_eq!(*v.longs, [i64::MAX, -1]);
+}
+
+#[test]
+fn reader_ignores_unwanted_fields() {
+    #[derive(Deserialize)]
+    struct V {
+        int: i32,
+    }
+
+    let bs = everything_nbt();
+    let mut reader = bs.as_slice().chain(&[1, 2, 3][..]);
+    let v: V = from_reader(&mut reader).unwrap();
+    assert_eq!(v.int, -70000);
+
+    // Only the NBT should be consumed from the reader.
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [1, 2, 3]);
+}
+
+#[test]
+fn reader_truncated_input_errors() {
+    let bs = everything_nbt();
+    for len in 0..bs.len() {
+        assert!(from_reader::<_, Value>(&bs[..len]).is_err());
+    }
+}
+
+#[test]
+fn reader_huge_array_size_errors_without_allocating() {
+    let bs = Builder::new()
+        .start_compound("")
+        .tag(Tag::LongArray)
+        .name("a")
+        .int_payload(i32::MAX)
+        .long_payload(1)
+        .end_compound()
+        .build();
+
+    assert!(from_reader::<_, Value>(bs.as_slice()).is_err());
+}
+
+#[test]
+fn reader_respects_max_seq_len() {
+    let bs = Builder::new()
+        .start_compound("")
+        .start_list("a", Tag::Byte, 3)
+        .byte_payload(1)
+        .byte_payload(2)
+        .byte_payload(3)
+        .end_compound()
+        .build();
+
+    let res = from_reader_with_opts::<_, Value>(bs.as_slice(), DeOpts::new().max_seq_len(2));
+    assert!(res.is_err());
+    let res = from_reader_with_opts::<_, Value>(bs.as_slice(), DeOpts::new().max_seq_len(4));
+    assert!(res.is_ok());
+}
+
+#[test]
+fn reader_bedrock_flavours() {
+    for flavour in [Flavour::BedrockLittleEndian, Flavour::BedrockNetwork] {
+        let v: Value = from_bytes(&everything_nbt()).unwrap();
+        let bs = to_bytes_with_opts(&v, SerOpts::new().flavour(flavour)).unwrap();
+        let v2: Value =
+            from_reader_with_opts(bs.as_slice(), DeOpts::new().flavour(flavour)).unwrap();
+        assert_eq!(v, v2);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{error::ErrorKind, roundtrip_check, test::builder::Builder, Tag};
+
+#[test]
+fn keeps_root_name() {
+    let input = Builder::new()
+        .start_compound("Schematic")
+        .short("Width", 16)
+        .end_compound()
+        .build();
+
+    assert_eq!(roundtrip_check(&input), Ok(()));
+}
+
+#[test]
+fn keeps_empty_list_tags() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("Inventory", Tag::Compound, 0)
+        .end_compound()
+        .build();
+
+    assert_eq!(roundtrip_check(&input), Ok(()));
+}
+
+#[test]
+fn keeps_float_bits() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("nans", Tag::Float, 2)
+        .float_payload(f32::from_bits(0x7fc0_0001))
+        .float_payload(f32::from_bits(0xffc0_0000))
+        .end_compound()
+        .build();
+
+    assert_eq!(roundtrip_check(&input), Ok(()));
+
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("zeros", Tag::Double, 2)
+        .double_payload(0.0)
+        .double_payload(-0.0)
+        .end_compound()
+        .build();
+
+    assert_eq!(roundtrip_check(&input), Ok(()));
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn keeps_chunk() {
+    assert_eq!(roundtrip_check(super::resources::CHUNK_RAW), Ok(()));
+}
+
+#[test]
+fn reports_path_of_first_difference() {
+    // Only the last of the duplicate keys is kept.
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("list", Tag::Compound, 1)
+        .int("a", 1)
+        .int("a", 2)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let err = roundtrip_check(&input).unwrap_err();
+    assert_eq!(err.path(), Some("list[0].a"));
+    assert_eq!(err.offset(), Some(15));
+    assert!(err.to_string().contains("read int 1 named \"a\""));
+    assert!(err.to_string().contains("wrote int 2 named \"a\""));
+}
+
+#[test]
+fn reports_trailing_data() {
+    let mut input = Builder::new().start_compound("").end_compound().build();
+    input.extend_from_slice(&[1, 2]);
+
+    let err = roundtrip_check(&input).unwrap_err();
+    assert_eq!(err.path(), None);
+    assert_eq!(err.offset(), Some(4));
+    assert!(err.to_string().contains("dropped 2 bytes"));
+}
+
+#[test]
+fn reports_invalid_input() {
+    let input = Builder::new().start_compound("").int("a", 1).build();
+
+    let err = roundtrip_check(&input).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+/// Round trip values from `Value`'s `Arbitrary` impl. Keys are only kept in
+/// order with the `preserve_order` feature.
+#[cfg(all(feature = "arbitrary1", feature = "preserve_order"))]
+#[test]
+fn arbitrary_values_roundtrip() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::{to_bytes, CompoundMap, Value};
+
+    // A fixed xorshift generator keeps the test deterministic.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut checked = 0;
+    for _ in 0..500 {
+        let len = next() as usize % 1024;
+        let data: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+
+        let v = match Value::arbitrary(&mut Unstructured::new(&data)) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let root = Value::Compound(CompoundMap::from_iter([("value".to_string(), v)]));
+        let bytes = match to_bytes(&root) {
+            Ok(bytes) => bytes,
+            // Eg strings too long for NBT.
+            Err(_) => continue,
+        };
+
+        assert_eq!(roundtrip_check(&bytes), Ok(()), "{:?}", root);
+        checked += 1;
+    }
+
+    assert!(checked > 100);
+}
@@ -0,0 +1,257 @@
+use crate::{
+    from_bytes,
+    schema::{Schema, Violation, ViolationKind},
+    stream::ErrorKind,
+    test::{builder::Builder, resources::CHUNK_RAW_WITH_ENTITIES},
+    to_bytes, DeOpts, Tag, Value,
+};
+
+/// Validate `v` as a value and as bytes, check both find the same
+/// violations, and return them. The bytes are validated in the order of the
+/// document rather than sorted, so only compare the sets.
+fn validate(schema: &Schema, v: &Value) -> Vec<Violation> {
+    let violations = schema.validate(v);
+    let bytes = to_bytes(v).unwrap();
+    let mut streamed = schema.validate_bytes(&bytes).unwrap();
+    streamed.sort_by_key(|v| v.to_string());
+    let mut sorted = violations.clone();
+    sorted.sort_by_key(|v| v.to_string());
+    assert_eq!(streamed, sorted);
+    violations
+}
+
+fn summary(violations: &[Violation]) -> Vec<(&str, ViolationKind)> {
+    violations.iter().map(|v| (v.path(), v.kind())).collect()
+}
+
+fn item() -> Schema {
+    Schema::compound()
+        .required("id", Schema::string())
+        .required("Count", Schema::byte().range(1, 64))
+        .optional("tag", Schema::compound())
+}
+
+#[test]
+fn valid() {
+    let schema = Schema::compound()
+        .required("Inventory", Schema::list(item()))
+        .optional("Pos", Schema::list(Schema::double()))
+        .required("DataVersion", Schema::int())
+        .required("anything", Schema::any());
+
+    let v = nbt!({
+        "Inventory": [{"id": "minecraft:stone", "Count": 64b}, {"id": "minecraft:dirt", "Count": 1b, "tag": {}}],
+        "DataVersion": 3465,
+        "anything": [L; 1],
+        "extra": "allowed",
+    });
+    assert_eq!(validate(&schema, &v), []);
+}
+
+#[test]
+fn missing_and_unknown() {
+    let schema = Schema::compound()
+        .required("a", Schema::int())
+        .optional("b", Schema::int())
+        .required("c", Schema::any())
+        .deny_unknown_fields();
+
+    let violations = validate(&schema, &nbt!({"b": 1, "x/y": 2}));
+    assert_eq!(
+        summary(&violations),
+        [
+            ("/x~1y", ViolationKind::Unknown),
+            ("/a", ViolationKind::Missing),
+            ("/c", ViolationKind::Missing),
+        ]
+    );
+    assert_eq!(violations[0].to_string(), "/x~1y: unknown field");
+    assert_eq!(violations[1].to_string(), "/a: missing required int");
+    assert_eq!(violations[2].to_string(), "/c: missing required field");
+}
+
+#[test]
+fn wrong_type() {
+    let schema = Schema::compound()
+        .required("a", Schema::int())
+        .required("b", Schema::compound().required("c", Schema::int()));
+
+    let violations = validate(&schema, &nbt!({"a": 1L, "b": [1]}));
+    assert_eq!(
+        summary(&violations),
+        [
+            ("/a", ViolationKind::WrongType),
+            ("/b", ViolationKind::WrongType)
+        ]
+    );
+    assert_eq!(violations[0].to_string(), "/a: expected int, found long");
+    assert_eq!(
+        violations[1].to_string(),
+        "/b: expected compound, found list"
+    );
+
+    let violations = schema.validate(&nbt!("root"));
+    assert_eq!(
+        violations.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        ["(root): expected compound, found string"]
+    );
+}
+
+#[test]
+fn list_elements() {
+    let schema = Schema::compound()
+        .required("Inventory", Schema::list(item()))
+        .required("Pos", Schema::list(Schema::double()))
+        .required("Empty", Schema::list(Schema::double()));
+
+    let v = nbt!({
+        "Inventory": [{"id": "minecraft:stone", "Count": 100b}, {"Count": 1b}, {"id": 1, "Count": 0B}],
+        "Pos": [1, 2, 3],
+        "Empty": [],
+    });
+    let violations = validate(&schema, &v);
+    assert_eq!(
+        summary(&violations),
+        [
+            ("/Inventory/0/Count", ViolationKind::OutOfRange),
+            ("/Inventory/1/id", ViolationKind::Missing),
+            ("/Inventory/2/Count", ViolationKind::OutOfRange),
+            ("/Inventory/2/id", ViolationKind::WrongType),
+            ("/Pos", ViolationKind::WrongType),
+        ]
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "/Inventory/0/Count: 100 is not between 1 and 64"
+    );
+    assert_eq!(
+        violations[4].to_string(),
+        "/Pos: expected list of double, found list of int"
+    );
+
+    let nested = Schema::list(Schema::list(Schema::int().range(0, 1)));
+    assert_eq!(
+        summary(&validate(&nested, &nbt!([[0, 1], [2]]))),
+        [("/1/0", ViolationKind::OutOfRange)]
+    );
+}
+
+#[test]
+fn ranges() {
+    let schema = Schema::compound()
+        .required("f", Schema::float().range(0, 1))
+        .required("d", Schema::double().range(-0.5, 0.5))
+        .required("nan", Schema::double().range(0, 1))
+        .required("l", Schema::long().range(0, 10))
+        .required("bytes", Schema::byte_array().range(0, 15))
+        .required("longs", Schema::long_array().range(0, 15));
+
+    let v = nbt!({
+        "f": 1.0f,
+        "d": -0.75,
+        "nan": f64::NAN,
+        "l": -1L,
+        "bytes": [B; 0, 16, 15, -1],
+        "longs": [L; 1, 2],
+    });
+    let violations = validate(&schema, &v);
+    assert_eq!(
+        summary(&violations),
+        [
+            ("/bytes/1", ViolationKind::OutOfRange),
+            ("/bytes/3", ViolationKind::OutOfRange),
+            ("/d", ViolationKind::OutOfRange),
+            ("/l", ViolationKind::OutOfRange),
+            ("/nan", ViolationKind::OutOfRange),
+        ]
+    );
+    assert_eq!(
+        violations[2].to_string(),
+        "/d: -0.75 is not between -0.5 and 0.5"
+    );
+}
+
+#[test]
+fn stream_only_checks_first_root() {
+    let schema = Schema::compound().required("a", Schema::int());
+    let mut bytes = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+    bytes.extend(Builder::new().start_compound("").end_compound().build());
+
+    assert_eq!(schema.validate_bytes(&bytes).unwrap(), []);
+}
+
+#[test]
+fn stream_error() {
+    let bytes = Builder::new()
+        .start_compound("")
+        .tag(Tag::Int)
+        .name("a")
+        .build();
+    assert!(Schema::any().validate_bytes(&bytes).is_err());
+}
+
+#[test]
+fn stream_limits() {
+    let schema = Schema::compound().required("longs", Schema::long_array().range(0, 9));
+    let bytes = to_bytes(&nbt!({"longs": [L; 1, 2, 3], "a": {"b": {}}})).unwrap();
+
+    let opts = DeOpts::new().max_array_len(3).max_depth(3);
+    let violations = schema.validate_reader_with_opts(bytes.as_slice(), opts);
+    assert_eq!(violations.unwrap(), []);
+
+    // Arrays that are skipped because nothing checks them still count.
+    let unchecked = Schema::compound();
+    let opts = DeOpts::new().max_array_len(2);
+    let err = unchecked
+        .validate_reader_with_opts(bytes.as_slice(), opts)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded));
+
+    let opts = DeOpts::new().max_depth(2);
+    let err = schema
+        .validate_reader_with_opts(bytes.as_slice(), opts)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded));
+}
+
+#[test]
+fn chunk() {
+    let section = Schema::compound()
+        .required("Y", Schema::byte().range(-4, 19))
+        .optional("BlockStates", Schema::long_array())
+        .optional(
+            "Palette",
+            Schema::list(Schema::compound().required("Name", Schema::string())),
+        );
+    let level = Schema::compound()
+        .required("xPos", Schema::int())
+        .required("zPos", Schema::int())
+        .required("Sections", Schema::list(section))
+        .required(
+            "Entities",
+            Schema::list(Schema::compound().required("id", Schema::string())),
+        );
+    let schema = Schema::compound()
+        .required("DataVersion", Schema::int())
+        .required("Level", level);
+
+    let v: Value = from_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    assert_eq!(schema.validate(&v), []);
+    assert_eq!(schema.validate_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap(), []);
+
+    let strict = Schema::compound()
+        .required("DataVersion", Schema::string())
+        .deny_unknown_fields();
+    let violations = strict.validate_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    assert_eq!(
+        summary(&violations),
+        [
+            ("/Level", ViolationKind::Unknown),
+            ("/DataVersion", ViolationKind::WrongType),
+        ]
+    );
+}
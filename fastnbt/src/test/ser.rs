@@ -1,9 +1,10 @@
 use std::{collections::HashMap, iter::FromIterator};
 
 use crate::{
-    borrow, from_bytes,
+    borrow, from_bytes, serialized_size, serialized_size_with_opts,
     test::{resources::CHUNK_RAW_WITH_ENTITIES, Single, Wrap},
-    to_bytes, ByteArray, IntArray, LongArray, Tag, Value,
+    to_bytes, to_bytes_with_opts, ByteArray, CompoundMap, Flavour, IntArray, LongArray, NamedRoot,
+    SerOpts, Tag, Value,
 };
 use serde::Serialize;
 use serde_bytes::Bytes;
@@ -436,7 +437,7 @@ fn nbt_long_array() {
 #[test]
 fn value_hashmap() {
     // let v = Value::Unit;
-    let v = Value::Compound(HashMap::from_iter([
+    let v = Value::Compound(CompoundMap::from_iter([
         ("a".to_string(), Value::Int(123)),
         ("b".to_string(), Value::Byte(123)),
     ]));
@@ -737,3 +738,72 @@ fn basic_newtype_variant_enum() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn network_nbt_has_no_root_name() {
+    let v = Single { val: 42 };
+    let actual = to_bytes_with_opts(&v, SerOpts::new().network_nbt(true)).unwrap();
+    let expected = Builder::new()
+        .tag(Tag::Compound)
+        .int("val", 42)
+        .end_compound()
+        .build();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn root_name_option() {
+    let v = Single { val: 42 };
+    let actual = to_bytes_with_opts(&v, SerOpts::new().root_name("Data")).unwrap();
+    let expected = Builder::new()
+        .start_compound("Data")
+        .int("val", 42)
+        .end_compound()
+        .build();
+    assert_eq!(actual, expected);
+
+    // A NamedRoot's own name wins.
+    let v = NamedRoot::new("Schematic", Single { val: 42 });
+    let actual = to_bytes_with_opts(&v, SerOpts::new().root_name("Data")).unwrap();
+    let expected = Builder::new()
+        .start_compound("Schematic")
+        .int("val", 42)
+        .end_compound()
+        .build();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn serialized_size_matches_output() {
+    let chunk: Value = from_bytes(CHUNK_RAW_WITH_ENTITIES).unwrap();
+    assert_eq!(
+        serialized_size(&chunk).unwrap(),
+        to_bytes(&chunk).unwrap().len()
+    );
+
+    let v = nbt!({
+        "name": "Stéve",
+        "longs": [L; 1, 2, 300_000],
+        "list": [1_i64, -1_i64],
+    });
+    for flavour in [
+        Flavour::JavaBigEndian,
+        Flavour::BedrockLittleEndian,
+        Flavour::BedrockNetwork,
+    ] {
+        let opts = || SerOpts::new().flavour(flavour).root_name("root");
+        assert_eq!(
+            serialized_size_with_opts(&v, opts()).unwrap(),
+            to_bytes_with_opts(&v, opts()).unwrap().len()
+        );
+    }
+}
+
+#[test]
+fn serialized_size_errors() {
+    let v = Single {
+        val: "a".repeat(70_000),
+    };
+    assert!(serialized_size(&v).is_err());
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    from_bytes, from_bytes_with_opts, serde_helpers, to_bytes, to_value, Coercion, DeOpts, Tag,
+};
+
+use super::builder::Builder;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Flags {
+    #[serde(with = "serde_helpers::bool_as_byte")]
+    flag: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Pos {
+    #[serde(with = "serde_helpers::position")]
+    pos: [i32; 3],
+}
+
+#[test]
+fn bool_as_byte_roundtrip() {
+    for flag in [true, false] {
+        let bs = to_bytes(&Flags { flag }).unwrap();
+        let expected = Builder::new()
+            .start_compound("")
+            .byte("flag", flag as i8)
+            .end_compound()
+            .build();
+        assert_eq!(bs, expected);
+        assert_eq!(from_bytes::<Flags>(&bs).unwrap(), Flags { flag });
+    }
+}
+
+#[test]
+fn bool_as_byte_accepts_any_integer() {
+    let bs = Builder::new()
+        .start_compound("")
+        .byte("flag", 2)
+        .end_compound()
+        .build();
+    assert_eq!(from_bytes::<Flags>(&bs).unwrap(), Flags { flag: true });
+
+    let bs = Builder::new()
+        .start_compound("")
+        .int("flag", 0)
+        .end_compound()
+        .build();
+    assert_eq!(from_bytes::<Flags>(&bs).unwrap(), Flags { flag: false });
+
+    let bs = Builder::new()
+        .start_compound("")
+        .string("flag", "true")
+        .end_compound()
+        .build();
+    assert!(from_bytes::<Flags>(&bs).is_err());
+}
+
+#[test]
+fn bool_as_byte_strict() {
+    let strict = || DeOpts::new().coercion(Coercion::Strict);
+    let bs = Builder::new()
+        .start_compound("")
+        .byte("flag", 2)
+        .end_compound()
+        .build();
+    assert!(from_bytes_with_opts::<Flags>(&bs, strict()).is_err());
+
+    let bs = to_bytes(&Flags { flag: true }).unwrap();
+    assert_eq!(
+        from_bytes_with_opts::<Flags>(&bs, strict()).unwrap(),
+        Flags { flag: true }
+    );
+}
+
+#[test]
+fn bool_as_byte_json_roundtrip() {
+    for flag in [true, false] {
+        let json = serde_json::to_string(&Flags { flag }).unwrap();
+        assert_eq!(json, format!("{{\"flag\":{}}}", flag as i8));
+        assert_eq!(
+            serde_json::from_str::<Flags>(&json).unwrap(),
+            Flags { flag }
+        );
+    }
+    let flags: Flags = serde_json::from_str(r#"{"flag":true}"#).unwrap();
+    assert_eq!(flags, Flags { flag: true });
+}
+
+#[test]
+fn bool_as_byte_from_snbt_bool() {
+    let flags: Flags = crate::snbt::from_str("{flag:true}").unwrap();
+    assert_eq!(flags, Flags { flag: true });
+}
+
+#[test]
+fn position_roundtrip() {
+    let pos = Pos { pos: [1, -64, 3] };
+    let bs = to_bytes(&pos).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .int_array("pos", &[1, -64, 3])
+        .end_compound()
+        .build();
+    assert_eq!(bs, expected);
+    assert_eq!(from_bytes::<Pos>(&bs).unwrap(), pos);
+    assert_eq!(to_value(&pos).unwrap(), nbt!({"pos": [I; 1, -64, 3]}));
+}
+
+#[test]
+fn position_wrong_length() {
+    let bs = Builder::new()
+        .start_compound("")
+        .int_array("pos", &[1, 2])
+        .end_compound()
+        .build();
+    let err = from_bytes::<Pos>(&bs).unwrap_err();
+    assert!(err.to_string().contains("found length 2"));
+
+    let bs = Builder::new()
+        .start_compound("")
+        .start_list("pos", Tag::Int, 3)
+        .int_payload(1)
+        .int_payload(2)
+        .int_payload(3)
+        .end_compound()
+        .build();
+    assert!(from_bytes::<Pos>(&bs).is_err());
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    use ::uuid::Uuid;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{from_bytes, serde_helpers::uuid, to_bytes, to_value, Value};
+
+    use super::super::builder::Builder;
+
+    const UUID: &str = "f81d4fae-7dec-11d0-a765-00a0c91e6bf6";
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct IntArrayUuid {
+        #[serde(rename = "UUID", with = "uuid::int_array")]
+        uuid: Uuid,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct MostLeastUuid {
+        #[serde(flatten, with = "uuid::most_least")]
+        uuid: Uuid,
+        name: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct StringUuid {
+        #[serde(with = "uuid::hyphenated")]
+        uuid: Uuid,
+    }
+
+    #[test]
+    fn int_array() {
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        let v = IntArrayUuid { uuid };
+        let bs = to_bytes(&v).unwrap();
+        let expected = Builder::new()
+            .start_compound("")
+            .int_array(
+                "UUID",
+                &[
+                    0xf81d4fae_u32 as i32,
+                    0x7dec11d0,
+                    0xa76500a0_u32 as i32,
+                    0xc91e6bf6_u32 as i32,
+                ],
+            )
+            .end_compound()
+            .build();
+        assert_eq!(bs, expected);
+        assert_eq!(from_bytes::<IntArrayUuid>(&bs).unwrap(), v);
+    }
+
+    #[test]
+    fn int_array_wrong_length() {
+        let bs = Builder::new()
+            .start_compound("")
+            .int_array("UUID", &[1, 2, 3])
+            .end_compound()
+            .build();
+        assert!(from_bytes::<IntArrayUuid>(&bs).is_err());
+    }
+
+    #[test]
+    fn most_least() {
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        let v = MostLeastUuid {
+            uuid,
+            name: "Steve".to_string(),
+        };
+
+        assert_eq!(
+            to_value(&v).unwrap(),
+            nbt!({
+                "UUIDMost": 0xf81d4fae7dec11d0_u64 as i64,
+                "UUIDLeast": 0xa76500a0c91e6bf6_u64 as i64,
+                "name": "Steve",
+            })
+        );
+
+        let bs = to_bytes(&v).unwrap();
+        assert_eq!(from_bytes::<MostLeastUuid>(&bs).unwrap(), v);
+    }
+
+    #[test]
+    fn hyphenated() {
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        let v = StringUuid { uuid };
+        assert_eq!(to_value(&v).unwrap(), nbt!({ "uuid": UUID }));
+
+        let bs = to_bytes(&v).unwrap();
+        assert_eq!(from_bytes::<StringUuid>(&bs).unwrap(), v);
+
+        let v: StringUuid = crate::from_value(&nbt!({"uuid": UUID.to_uppercase()})).unwrap();
+        assert_eq!(v.uuid, uuid);
+        assert!(crate::from_value::<StringUuid>(&Value::Int(1)).is_err());
+    }
+}
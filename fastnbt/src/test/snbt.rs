@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    from_bytes,
+    snbt::{from_str, to_string},
+    to_bytes, ByteArray, CompoundMap, IntArray, LongArray, Tag, Value,
+};
+
+use super::Single;
+
+#[test]
+fn numbers_use_suffix_for_type() {
+    assert_eq!(from_str::<Value>("1b").unwrap(), Value::Byte(1));
+    assert_eq!(from_str::<Value>("-2S").unwrap(), Value::Short(-2));
+    assert_eq!(from_str::<Value>("3").unwrap(), Value::Int(3));
+    assert_eq!(from_str::<Value>("4L").unwrap(), Value::Long(4));
+    assert_eq!(from_str::<Value>("4l").unwrap(), Value::Long(4));
+    assert_eq!(from_str::<Value>("1.5f").unwrap(), Value::Float(1.5));
+    assert_eq!(from_str::<Value>("1.5d").unwrap(), Value::Double(1.5));
+    assert_eq!(from_str::<Value>("1.5").unwrap(), Value::Double(1.5));
+    assert_eq!(from_str::<Value>("true").unwrap(), Value::Byte(1));
+    assert_eq!(from_str::<Value>("false").unwrap(), Value::Byte(0));
+}
+
+#[test]
+fn non_numbers_are_strings() {
+    assert_eq!(
+        from_str::<Value>("abc").unwrap(),
+        Value::String("abc".into())
+    );
+    assert_eq!(
+        from_str::<Value>("1e5").unwrap(),
+        Value::String("1e5".into())
+    );
+    assert_eq!(
+        from_str::<Value>("300b").unwrap(),
+        Value::String("300b".into())
+    );
+    assert_eq!(
+        from_str::<Value>("3000000000").unwrap(),
+        Value::String("3000000000".into())
+    );
+}
+
+#[test]
+fn quoted_strings() {
+    assert_eq!(
+        from_str::<Value>(r#""hello world""#).unwrap(),
+        Value::String("hello world".into())
+    );
+    assert_eq!(
+        from_str::<Value>(r#"'say "hi"'"#).unwrap(),
+        Value::String(r#"say "hi""#.into())
+    );
+    assert_eq!(
+        from_str::<Value>(r#""a\"b\\cé""#).unwrap(),
+        Value::String("a\"b\\cé".into())
+    );
+    assert!(from_str::<Value>(r#""unterminated"#).is_err());
+}
+
+#[test]
+fn borrows_unescaped_strings() {
+    #[derive(Deserialize)]
+    struct V<'a> {
+        #[serde(borrow)]
+        s: std::borrow::Cow<'a, str>,
+    }
+
+    let v: V = from_str(r#"{s:"hello"}"#).unwrap();
+    assert!(matches!(v.s, std::borrow::Cow::Borrowed("hello")));
+}
+
+#[test]
+fn compound_with_quoted_and_unquoted_keys() {
+    let v: Value = from_str(r#"{ a: 1b, "b c": 2s, 'd': "e" }"#).unwrap();
+    assert_eq!(
+        v,
+        Value::Compound(CompoundMap::from([
+            ("a".to_owned(), Value::Byte(1)),
+            ("b c".to_owned(), Value::Short(2)),
+            ("d".to_owned(), Value::String("e".into())),
+        ]))
+    );
+}
+
+#[test]
+fn lists() {
+    let v: Value = from_str("[1, 2, 3]").unwrap();
+    assert_eq!(
+        v,
+        Value::List(Tag::Int, vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+    );
+    assert_eq!(
+        from_str::<Value>("[]").unwrap(),
+        Value::List(Tag::End, vec![])
+    );
+}
+
+#[test]
+fn arrays_into_value() {
+    assert_eq!(
+        from_str::<Value>("[B; 1b, 2b]").unwrap(),
+        Value::ByteArray(ByteArray::new(vec![1, 2]))
+    );
+    assert_eq!(
+        from_str::<Value>("[I; 1, -2]").unwrap(),
+        Value::IntArray(IntArray::new(vec![1, -2]))
+    );
+    assert_eq!(
+        from_str::<Value>("[L; 1L, 2L]").unwrap(),
+        Value::LongArray(LongArray::new(vec![1, 2]))
+    );
+    assert_eq!(
+        from_str::<Value>("[L;]").unwrap(),
+        Value::LongArray(LongArray::new(vec![]))
+    );
+}
+
+#[test]
+fn arrays_into_array_types() {
+    let v: Single<LongArray> = from_str("{val: [L; 1L, 2L, 3L]}").unwrap();
+    assert_eq!(*v.val, [1, 2, 3]);
+
+    let v: Single<IntArray> = from_str("{val: [I; 1, 2, 3]}").unwrap();
+    assert_eq!(*v.val, [1, 2, 3]);
+
+    let v: Single<ByteArray> = from_str("{val: [B; 1b, 2b, 3b]}").unwrap();
+    assert_eq!(*v.val, [1, 2, 3]);
+
+    assert!(from_str::<Single<LongArray>>("{val: [I; 1]}").is_err());
+}
+
+#[test]
+fn arrays_into_vec() {
+    let v: Single<Vec<i64>> = from_str("{val: [L; 1L, 2L]}").unwrap();
+    assert_eq!(v.val, [1, 2]);
+}
+
+#[test]
+fn array_element_out_of_range() {
+    assert!(from_str::<Value>("[B; 128]").is_err());
+    assert!(from_str::<Value>("[I; 3000000000L]").is_err());
+}
+
+#[test]
+fn trailing_characters_error() {
+    assert!(from_str::<Value>("{a:1} b").is_err());
+    assert!(from_str::<Value>("{a:1").is_err());
+    assert!(from_str::<Value>("{a 1}").is_err());
+}
+
+#[test]
+fn deep_nesting_errors() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    // Debug builds need a bigger stack than test threads have for deep input.
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            assert!(from_str::<Value>(&nested(512)).is_ok());
+
+            let err = from_str::<Value>(&nested(513)).unwrap_err();
+            assert!(err.to_string().contains("max depth"));
+            assert!(from_str::<Value>(&"[".repeat(200_000)).is_err());
+            assert!(from_str::<Value>(&"{a:".repeat(200_000)).is_err());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn deserialize_struct() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Item {
+        id: String,
+        #[serde(rename = "Count")]
+        count: u8,
+        enchanted: bool,
+        tags: Vec<String>,
+        missing: Option<i32>,
+    }
+
+    let item: Item =
+        from_str(r#"{id:"minecraft:stick",Count:3b,enchanted:1b,tags:[a,"b"],ignored:{x:1}}"#)
+            .unwrap();
+
+    assert_eq!(
+        item,
+        Item {
+            id: "minecraft:stick".into(),
+            count: 3,
+            enchanted: true,
+            tags: vec!["a".into(), "b".into()],
+            missing: None,
+        }
+    );
+}
+
+#[test]
+fn serialize_value() {
+    let v = Value::Compound(CompoundMap::from([(
+        "list".to_owned(),
+        Value::List(
+            Tag::Byte,
+            vec![
+                Value::Byte(1),
+                Value::Short(2),
+                Value::Int(3),
+                Value::Long(4),
+                Value::Float(1.5),
+                Value::Double(2.0),
+            ],
+        ),
+    )]));
+
+    assert_eq!(to_string(&v).unwrap(), "{list:[1b,2s,3,4L,1.5f,2.0d]}");
+}
+
+#[test]
+fn serialize_arrays() {
+    assert_eq!(
+        to_string(&ByteArray::new(vec![1, -1])).unwrap(),
+        "[B;1b,-1b]"
+    );
+    assert_eq!(to_string(&IntArray::new(vec![1, 2])).unwrap(), "[I;1,2]");
+    assert_eq!(to_string(&LongArray::new(vec![1, 2])).unwrap(), "[L;1L,2L]");
+    assert_eq!(to_string(&LongArray::new(vec![])).unwrap(), "[L;]");
+}
+
+#[test]
+fn serialize_non_finite_errors() {
+    assert!(to_string(&f32::NAN).is_err());
+    assert!(to_string(&f64::INFINITY).is_err());
+    assert!(to_string(&Value::Double(f64::NEG_INFINITY)).is_err());
+    assert_eq!(to_string(&f32::MAX).unwrap(), format!("{:?}f", f32::MAX));
+}
+
+#[test]
+fn serialize_tuple_variant_errors() {
+    #[derive(Serialize)]
+    enum Shape {
+        Point(i32, i32),
+    }
+
+    let err = to_string(&Shape::Point(1, 2)).unwrap_err();
+    assert!(err.to_string().contains("tuple variant Point"));
+}
+
+#[test]
+fn human_readable_types_use_strings() {
+    let addr: std::net::Ipv4Addr = "127.0.0.1".parse().unwrap();
+    let snbt = to_string(&addr).unwrap();
+    assert_eq!(snbt, "\"127.0.0.1\"");
+    assert_eq!(from_str::<std::net::Ipv4Addr>(&snbt).unwrap(), addr);
+}
+
+#[test]
+fn serialize_quotes_when_needed() {
+    let v = Value::Compound(CompoundMap::from([(
+        "a key".to_owned(),
+        Value::String(r#"say "hi""#.to_owned()),
+    )]));
+
+    assert_eq!(to_string(&v).unwrap(), r#"{"a key":"say \"hi\""}"#);
+
+    // Strings are always quoted so they can't be read back as another type.
+    let v = nbt!({"key": "1b"});
+    assert_eq!(to_string(&v).unwrap(), r#"{key:"1b"}"#);
+    assert_eq!(from_str::<Value>(&to_string(&v).unwrap()).unwrap(), v);
+}
+
+#[test]
+fn serialize_skips_none_fields() {
+    #[derive(Serialize)]
+    struct V {
+        a: Option<i32>,
+        b: i32,
+        c: Option<i32>,
+    }
+
+    let text = to_string(&V {
+        a: None,
+        b: 1,
+        c: None,
+    })
+    .unwrap();
+    assert_eq!(text, "{b:1}");
+}
+
+#[test]
+fn roundtrip_with_binary() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Section {
+        #[serde(rename = "Y")]
+        y: i8,
+        block_states: LongArray,
+        palette: Vec<HashMap<String, String>>,
+        uuid: u128,
+        weight: f32,
+    }
+
+    let original = Section {
+        y: -4,
+        block_states: LongArray::new(vec![i64::MIN, 0, i64::MAX]),
+        palette: vec![HashMap::from([(
+            "Name".to_owned(),
+            "minecraft:stone".to_owned(),
+        )])],
+        uuid: 0x0123_4567_89ab_cdef_0123_4567_89ab_cdef,
+        weight: 0.1,
+    };
+
+    let text = to_string(&original).unwrap();
+    let from_text: Section = from_str(&text).unwrap();
+    assert_eq!(original, from_text);
+
+    let value: Value = from_str(&text).unwrap();
+    let from_binary: Section = from_bytes(&to_bytes(&value).unwrap()).unwrap();
+    assert_eq!(original, from_binary);
+}
@@ -1,5 +1,9 @@
 use super::builder::Builder;
-use crate::stream::{ErrorKind, Name, Parser, Result, Value};
+use super::resources::CHUNK_RAW;
+use crate::stream::{
+    find_compound, find_list, find_path, skip_compound, Decoder, ErrorKind, Name, Parser, Result,
+    Value, Writer,
+};
 use crate::Tag;
 
 fn name(n: &str) -> Name {
@@ -437,3 +441,414 @@ fn list_of_compounds() -> Result<()> {
     assert!(matches!(parser.next(), Err(e) if e.is_eof()));
     Ok(())
 }
+
+fn write_all(values: &[Value]) -> Result<Vec<u8>> {
+    let mut writer = Writer::new(Vec::new());
+    for v in values {
+        writer.write(v)?;
+    }
+    writer.finish()
+}
+
+#[test]
+fn writer_matches_builder() -> Result<()> {
+    let expected = Builder::new()
+        .start_compound("")
+        .byte("byte", 1)
+        .string("string", "héllo 😀")
+        .start_list("list", Tag::Compound, 2)
+        .int("a", 1)
+        .end_compound()
+        .end_compound()
+        .start_list("empty", Tag::End, 0)
+        .int_array("ints", &[1, -2])
+        .long_array("longs", &[i64::MIN])
+        .byte_array("bytes", &[-1])
+        .end_compound()
+        .build();
+
+    let actual = write_all(&[
+        Value::Compound(name("")),
+        Value::Byte(name("byte"), 1),
+        Value::String(name("string"), "héllo 😀".to_owned()),
+        Value::List(name("list"), Tag::Compound, 2),
+        Value::Compound(None),
+        Value::Int(name("a"), 1),
+        Value::CompoundEnd,
+        Value::Compound(None),
+        Value::CompoundEnd,
+        Value::ListEnd,
+        Value::List(name("empty"), Tag::End, 0),
+        Value::ListEnd,
+        Value::IntArray(name("ints"), vec![1, -2]),
+        Value::LongArray(name("longs"), vec![i64::MIN]),
+        Value::ByteArray(name("bytes"), vec![-1]),
+        Value::CompoundEnd,
+    ])?;
+
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn writer_parser_roundtrip_chunk() -> Result<()> {
+    let mut parser = Parser::new(CHUNK_RAW);
+    let mut writer = Writer::new(Vec::new());
+
+    loop {
+        match parser.next() {
+            Ok(v) => writer.write(&v)?,
+            Err(e) if e.is_eof() => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    assert_eq!(writer.finish()?, CHUNK_RAW);
+    Ok(())
+}
+
+#[test]
+fn writer_rejects_invalid_nesting() {
+    let invalid: &[&[Value]] = &[
+        &[Value::CompoundEnd],
+        &[Value::ListEnd],
+        &[Value::Int(None, 1)],
+        &[Value::Compound(name("")), Value::Int(None, 1)],
+        &[Value::Compound(name("")), Value::ListEnd],
+        &[Value::Compound(name(""))],
+        &[Value::List(name("l"), Tag::Int, 1), Value::ListEnd],
+        &[Value::List(name("l"), Tag::Int, 1), Value::CompoundEnd],
+        &[
+            Value::List(name("l"), Tag::Int, 1),
+            Value::Int(name("a"), 1),
+        ],
+        &[Value::List(name("l"), Tag::Int, 1), Value::Short(None, 1)],
+        &[
+            Value::List(name("l"), Tag::Int, 1),
+            Value::Int(None, 1),
+            Value::Int(None, 2),
+        ],
+        &[Value::List(name("l"), Tag::Int, -1)],
+        &[Value::List(name("l"), Tag::End, 1)],
+    ];
+
+    for values in invalid {
+        assert!(write_all(values).is_err(), "{:?}", values);
+    }
+}
+
+#[test]
+fn writer_error_writes_nothing() -> Result<()> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write(&Value::Compound(name("")))?;
+    assert!(writer.write(&Value::Int(None, 1)).is_err());
+    assert!(writer.write(&Value::List(name("l"), Tag::Int, -1)).is_err());
+    let long = "a".repeat(70_000);
+    assert!(writer
+        .write(&Value::String(name("s"), long.clone()))
+        .is_err());
+    assert!(writer.write(&Value::Int(Some(long), 1)).is_err());
+
+    // Nothing is counted towards the list either.
+    writer.write(&Value::List(name("l"), Tag::String, 1))?;
+    assert!(writer
+        .write(&Value::String(None, "a".repeat(70_000)))
+        .is_err());
+    writer.write(&Value::String(None, "a".to_owned()))?;
+    writer.write(&Value::ListEnd)?;
+    writer.write(&Value::CompoundEnd)?;
+
+    let expected = Builder::new()
+        .start_compound("")
+        .start_list("l", Tag::String, 1)
+        .string_payload("a")
+        .end_compound()
+        .build();
+    assert_eq!(writer.finish()?, expected);
+    Ok(())
+}
+
+fn sections_nbt() -> Vec<u8> {
+    Builder::new()
+        .start_compound("")
+        .string("Status", "full")
+        .long_array("big", &[1; 1000])
+        .start_list("sections", Tag::Compound, 3)
+        .byte("Y", 0)
+        .end_compound()
+        .byte("Y", 1)
+        .int_array("ints", &[1, 2, 3])
+        .end_compound()
+        .byte("Y", 2)
+        .start_compound("block_states")
+        .start_list("palette", Tag::String, 1)
+        .string_payload("minecraft:air")
+        .long_array("data", &[5, 6])
+        .end_compound()
+        .end_compound()
+        .int("after", 1)
+        .end_compound()
+        .build()
+}
+
+#[test]
+fn skip_payloads() -> Result<()> {
+    let payload = sections_nbt();
+    let mut parser = Parser::new(payload.as_slice());
+    parser.set_skip_payloads(true);
+
+    assert_eq!(parser.next()?, Value::Compound(name("")));
+    assert_eq!(parser.next()?, Value::String(name("Status"), String::new()));
+    assert_eq!(parser.next()?, Value::LongArray(name("big"), vec![]));
+    assert_eq!(
+        parser.next()?,
+        Value::List(name("sections"), Tag::Compound, 3)
+    );
+    assert_eq!(parser.next()?, Value::Compound(None));
+    assert_eq!(parser.next()?, Value::Byte(name("Y"), 0));
+
+    parser.set_skip_payloads(false);
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert_eq!(parser.next()?, Value::Compound(None));
+    assert_eq!(parser.next()?, Value::Byte(name("Y"), 1));
+    assert_eq!(parser.next()?, Value::IntArray(name("ints"), vec![1, 2, 3]));
+    Ok(())
+}
+
+#[test]
+fn skip_compound_skips_nested() -> Result<()> {
+    let payload = sections_nbt();
+    let mut parser = Parser::new(payload.as_slice());
+
+    assert_eq!(parser.next()?, Value::Compound(name("")));
+    assert_eq!(
+        parser.next()?,
+        Value::String(name("Status"), "full".to_owned())
+    );
+    assert_eq!(parser.next()?, Value::LongArray(name("big"), vec![1; 1000]));
+    assert_eq!(
+        parser.next()?,
+        Value::List(name("sections"), Tag::Compound, 3)
+    );
+    assert_eq!(parser.next()?, Value::Compound(None));
+
+    // Skip the first section.
+    skip_compound(&mut parser)?;
+    assert_eq!(parser.next()?, Value::Compound(None));
+    assert_eq!(parser.next()?, Value::Byte(name("Y"), 1));
+
+    // Skip the rest of the second section.
+    skip_compound(&mut parser)?;
+    assert_eq!(parser.next()?, Value::Compound(None));
+    assert_eq!(parser.next()?, Value::Byte(name("Y"), 2));
+    assert_eq!(parser.next()?, Value::Compound(name("block_states")));
+    assert_eq!(parser.next()?, Value::List(name("palette"), Tag::String, 1));
+
+    // Skipping from inside a list skips the rest of the list and the compound
+    // it is in.
+    skip_compound(&mut parser)?;
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert_eq!(parser.next()?, Value::ListEnd);
+    assert_eq!(parser.next()?, Value::Int(name("after"), 1));
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert!(parser.next().unwrap_err().is_eof());
+    Ok(())
+}
+
+#[test]
+fn find_compound_and_list() -> Result<()> {
+    let payload = sections_nbt();
+    let mut parser = Parser::new(payload.as_slice());
+
+    find_compound(&mut parser, Some("block_states"))?;
+    assert_eq!(find_list(&mut parser, Some("palette"))?, 1);
+    assert_eq!(
+        parser.next()?,
+        Value::String(None, "minecraft:air".to_owned())
+    );
+    Ok(())
+}
+
+#[test]
+fn find_path_finds_values() -> Result<()> {
+    let payload = sections_nbt();
+
+    let find = |path: &[&str]| find_path(&mut Parser::new(payload.as_slice()), path);
+
+    assert_eq!(find(&[])?, Some(Value::Compound(name(""))));
+    assert_eq!(find(&["after"])?, Some(Value::Int(name("after"), 1)));
+    assert_eq!(
+        find(&["sections", "1", "Y"])?,
+        Some(Value::Byte(name("Y"), 1))
+    );
+    assert_eq!(
+        find(&["sections", "2", "block_states", "data"])?,
+        Some(Value::LongArray(name("data"), vec![5, 6]))
+    );
+    assert_eq!(
+        find(&["sections", "2", "block_states", "palette", "0"])?,
+        Some(Value::String(None, "minecraft:air".to_owned()))
+    );
+    assert_eq!(find(&["sections", "0"])?, Some(Value::Compound(None)));
+
+    assert_eq!(find(&["missing"])?, None);
+    assert_eq!(find(&["sections", "3"])?, None);
+    assert_eq!(find(&["sections", "-1"])?, None);
+    assert_eq!(find(&["sections", "Y"])?, None);
+    assert_eq!(find(&["Status", "x"])?, None);
+    assert_eq!(find(&["sections", "1", "Y", "x"])?, None);
+    Ok(())
+}
+
+#[test]
+fn find_path_leaves_parser_inside() -> Result<()> {
+    let payload = sections_nbt();
+    let mut parser = Parser::new(payload.as_slice());
+
+    assert_eq!(
+        find_path(&mut parser, &["sections", "2", "block_states"])?,
+        Some(Value::Compound(name("block_states")))
+    );
+    assert_eq!(parser.next()?, Value::List(name("palette"), Tag::String, 1));
+    assert_eq!(
+        parser.next()?,
+        Value::String(None, "minecraft:air".to_owned())
+    );
+    assert_eq!(parser.next()?, Value::ListEnd);
+    assert_eq!(parser.next()?, Value::LongArray(name("data"), vec![5, 6]));
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert_eq!(parser.next()?, Value::ListEnd);
+    assert_eq!(parser.next()?, Value::Int(name("after"), 1));
+
+    let mut parser = Parser::new(payload.as_slice());
+    assert_eq!(
+        find_path(&mut parser, &["sections", "1"])?,
+        Some(Value::Compound(None))
+    );
+    assert_eq!(parser.next()?, Value::Byte(name("Y"), 1));
+    assert_eq!(parser.next()?, Value::IntArray(name("ints"), vec![1, 2, 3]));
+    assert_eq!(parser.next()?, Value::CompoundEnd);
+    assert_eq!(parser.next()?, Value::Compound(None));
+    Ok(())
+}
+
+#[test]
+fn find_path_truncated_errors() {
+    let payload = sections_nbt();
+    let payload = &payload[..payload.len() - 10];
+    let mut parser = Parser::new(payload);
+    assert!(find_path(&mut parser, &["missing"]).is_err());
+}
+
+fn parse_all(input: &[u8]) -> Vec<Value> {
+    let mut parser = Parser::new(input);
+    let mut values = vec![];
+    loop {
+        match parser.next() {
+            Ok(v) => values.push(v),
+            Err(e) if e.is_eof() => return values,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+fn decode_in_fragments(input: &[u8], size: usize) -> Result<Vec<Value>> {
+    let mut decoder = Decoder::new();
+    let mut values = vec![];
+    for fragment in input.chunks(size) {
+        decoder.feed(fragment);
+        while let Some(v) = decoder.next()? {
+            values.push(v);
+        }
+    }
+    assert_eq!(decoder.depth(), 0);
+    assert_eq!(decoder.buffered(), 0);
+    Ok(values)
+}
+
+#[test]
+fn decoder_matches_parser() -> Result<()> {
+    for input in [sections_nbt(), CHUNK_RAW.to_vec()] {
+        let expected = parse_all(&input);
+        for size in [1, 2, 3, 7, 100, 4096, input.len()] {
+            assert_eq!(decode_in_fragments(&input, size)?, expected);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn decoder_needs_more_input() -> Result<()> {
+    let input = Builder::new()
+        .start_compound("")
+        .long_array("a", &[1, 2])
+        .end_compound()
+        .build();
+
+    let mut decoder = Decoder::new();
+    assert_eq!(decoder.next()?, None);
+
+    decoder.feed(&input[..4]);
+    assert_eq!(decoder.next()?, Some(Value::Compound(name(""))));
+    assert_eq!(decoder.next()?, None);
+    assert_eq!(decoder.depth(), 1);
+
+    // Everything but the last byte of the array.
+    decoder.feed(&input[4..input.len() - 2]);
+    assert_eq!(decoder.next()?, None);
+    assert_eq!(decoder.buffered(), input.len() - 2 - 3);
+
+    decoder.feed(&input[input.len() - 2..]);
+    assert_eq!(
+        decoder.next()?,
+        Some(Value::LongArray(name("a"), vec![1, 2]))
+    );
+    assert_eq!(decoder.next()?, Some(Value::CompoundEnd));
+    assert_eq!(decoder.next()?, None);
+    assert_eq!(decoder.depth(), 0);
+    Ok(())
+}
+
+#[test]
+fn decoder_multiple_documents() -> Result<()> {
+    let doc = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+
+    let mut decoder = Decoder::new();
+    decoder.feed(&[doc.clone(), doc].concat());
+
+    for _ in 0..2 {
+        assert_eq!(decoder.next()?, Some(Value::Compound(name(""))));
+        assert_eq!(decoder.next()?, Some(Value::Int(name("a"), 1)));
+        assert_eq!(decoder.next()?, Some(Value::CompoundEnd));
+        assert_eq!(decoder.depth(), 0);
+    }
+    assert_eq!(decoder.next()?, None);
+    Ok(())
+}
+
+#[test]
+fn decoder_invalid_input_errors() {
+    let invalid_tag = Builder::new().start_compound("").raw_bytes(&[20]).build();
+    let negative_array = Builder::new()
+        .start_compound("")
+        .tag(Tag::IntArray)
+        .name("a")
+        .int_payload(-1)
+        .build();
+    let negative_list = Builder::new()
+        .start_compound("")
+        .start_list("a", Tag::Int, -1)
+        .build();
+
+    for input in [invalid_tag, negative_array, negative_list] {
+        let mut decoder = Decoder::new();
+        decoder.feed(&input);
+        assert_eq!(decoder.next().unwrap(), Some(Value::Compound(name(""))));
+        assert!(decoder.next().is_err());
+    }
+}
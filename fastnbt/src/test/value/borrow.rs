@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use crate::{
+    borrow, from_bytes,
+    test::{builder::Builder, resources::CHUNK_RAW},
+    to_bytes, Tag, Value,
+};
+
+fn everything_nbt() -> Vec<u8> {
+    Builder::new()
+        .start_compound("")
+        .byte("byte", -1)
+        .short("short", 300)
+        .int("int", -70000)
+        .long("long", i64::MIN)
+        .float("float", 1.5)
+        .double("double", -2.25)
+        .string("ascii", "hello")
+        .string("emoji", "😀")
+        .start_list("list", Tag::Int, 2)
+        .int_payload(1)
+        .int_payload(-1)
+        .start_list("empty", Tag::Compound, 0)
+        .byte_array("bytes", &[1, 2, -3])
+        .int_array("ints", &[0, -64, i32::MIN])
+        .long_array("longs", &[i64::MAX, -1])
+        .start_compound("nested")
+        .long("a", 1)
+        .end_compound()
+        .end_compound()
+        .build()
+}
+
+#[test]
+fn borrowed_matches_owned() {
+    let bs = everything_nbt();
+    let borrowed: borrow::Value = from_bytes(&bs).unwrap();
+    let owned: Value = from_bytes(&bs).unwrap();
+    assert_eq!(borrowed.into_owned(), owned);
+
+    let borrowed: borrow::Value = from_bytes(CHUNK_RAW).unwrap();
+    let owned: Value = from_bytes(CHUNK_RAW).unwrap();
+    assert_eq!(Value::from(borrowed), owned);
+}
+
+#[test]
+fn borrows_strings_and_arrays() {
+    let bs = everything_nbt();
+    let v: borrow::Value = from_bytes(&bs).unwrap();
+    let compound = match v {
+        borrow::Value::Compound(c) => c,
+        _ => panic!("expected compound"),
+    };
+
+    assert!(compound.keys().all(|k| matches!(k, Cow::Borrowed(_))));
+    assert!(matches!(
+        compound["ascii"],
+        borrow::Value::String(Cow::Borrowed("hello"))
+    ));
+
+    // Java strings are CESU-8, so an emoji has to be decoded into a new string.
+    assert!(matches!(
+        &compound["emoji"],
+        borrow::Value::String(Cow::Owned(s)) if s == "😀"
+    ));
+
+    match &compound["longs"] {
+        borrow::Value::LongArray(a) => {
+            assert_eq!(a.iter().collect::<Vec<_>>(), [i64::MAX, -1])
+        }
+        _ => panic!("expected long array"),
+    }
+    match &compound["ints"] {
+        borrow::Value::IntArray(a) => {
+            assert_eq!(a.iter().collect::<Vec<_>>(), [0, -64, i32::MIN])
+        }
+        _ => panic!("expected int array"),
+    }
+    match &compound["bytes"] {
+        borrow::Value::ByteArray(a) => assert_eq!(a.iter().collect::<Vec<_>>(), [1, 2, -3]),
+        _ => panic!("expected byte array"),
+    }
+}
+
+#[test]
+fn borrowed_serializes_like_owned() {
+    let bs = everything_nbt();
+    let borrowed: borrow::Value = from_bytes(&bs).unwrap();
+    let owned: Value = from_bytes(&bs).unwrap();
+
+    let reser = to_bytes(&borrowed).unwrap();
+    let v: Value = from_bytes(&reser).unwrap();
+    assert_eq!(v, owned);
+}
+
+#[test]
+fn borrowed_empty_compound() {
+    let bs = Builder::new().start_compound("").end_compound().build();
+    let v: borrow::Value = from_bytes(&bs).unwrap();
+    assert_eq!(v, borrow::Value::Compound(Default::default()));
+}
+
+#[test]
+fn borrowed_empty_list_keeps_element_tag() {
+    let bs = everything_nbt();
+    let v: borrow::Value = from_bytes(&bs).unwrap();
+    match &v {
+        borrow::Value::Compound(c) => {
+            assert_eq!(c["empty"], borrow::Value::List(Tag::Compound, vec![]))
+        }
+        _ => panic!("expected compound"),
+    }
+
+    let v: Value = from_bytes(&to_bytes(&v).unwrap()).unwrap();
+    assert_eq!(v["empty"], Value::List(Tag::Compound, vec![]));
+}
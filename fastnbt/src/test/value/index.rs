@@ -0,0 +1,137 @@
+use crate::{LongArray, Value};
+
+fn level() -> Value {
+    nbt!({
+        "Data": {
+            "SpawnX": 10,
+            "Player": {
+                "Inventory": [
+                    {"id": "minecraft:stone", "Count": 1_i8},
+                    {"id": "minecraft:dirt", "Count": 64_i8},
+                ],
+            },
+            "a/b": {"c~d": 1_i8},
+        },
+        "longs": [L; 1, 2, 3],
+    })
+}
+
+#[test]
+fn index_compound_and_list() {
+    let v = level();
+    assert_eq!(v["Data"]["SpawnX"], 10);
+    assert_eq!(v["Data"]["Player"]["Inventory"][1]["id"], "minecraft:dirt");
+    assert_eq!(v["Data"][&"SpawnX".to_owned()], 10);
+}
+
+#[test]
+#[should_panic]
+fn index_missing_key_panics() {
+    let v = level();
+    let _ = &v["Data"]["missing"];
+}
+
+#[test]
+#[should_panic]
+fn index_list_with_key_panics() {
+    let v = level();
+    let _ = &v["Data"]["Player"]["Inventory"]["id"];
+}
+
+#[test]
+fn index_mut() {
+    let mut v = level();
+    v["Data"]["SpawnX"] = Value::Int(20);
+    v["Data"]["Player"]["Inventory"][0]["Count"] = Value::Byte(2);
+    assert_eq!(v["Data"]["SpawnX"], 20);
+    assert_eq!(v["Data"]["Player"]["Inventory"][0]["Count"], 2);
+}
+
+#[test]
+fn get() {
+    let mut v = level();
+    assert_eq!(v.get("Data").and_then(|d| d.get("SpawnX")), Some(&nbt!(10)));
+    assert_eq!(v.get("missing"), None);
+    assert_eq!(v.get(0), None);
+    assert_eq!(v["Data"]["SpawnX"].get("x"), None);
+
+    *v.get_mut("Data").unwrap().get_mut("SpawnX").unwrap() = nbt!(5);
+    assert_eq!(v["Data"]["SpawnX"], 5);
+}
+
+#[test]
+fn pointer() {
+    let mut v = level();
+    assert_eq!(v.pointer(""), Some(&v));
+    assert_eq!(
+        v.pointer("/Data/Player/Inventory/0/id"),
+        Some(&nbt!("minecraft:stone"))
+    );
+    assert_eq!(v.pointer("/Data/a~1b/c~0d"), Some(&nbt!(1_i8)));
+    assert_eq!(v.pointer("/Data/Player/Inventory/2/id"), None);
+    assert_eq!(v.pointer("/Data/Player/Inventory/x"), None);
+    assert_eq!(v.pointer("/Data/SpawnX/0"), None);
+    assert_eq!(v.pointer("Data"), None);
+
+    *v.pointer_mut("/Data/Player/Inventory/1/Count").unwrap() = nbt!(1_i8);
+    assert_eq!(v["Data"]["Player"]["Inventory"][1]["Count"], 1);
+}
+
+#[test]
+fn typed_accessors() {
+    let mut v = level();
+    assert!(v.as_compound().unwrap().contains_key("Data"));
+    assert_eq!(
+        v.pointer("/Data/Player/Inventory")
+            .unwrap()
+            .as_list()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        v["longs"].as_long_array(),
+        Some(&LongArray::new(vec![1, 2, 3]))
+    );
+    assert_eq!(v["longs"].as_int_array(), None);
+    assert_eq!(v["Data"].as_list(), None);
+
+    v.pointer_mut("/Data/Player/Inventory")
+        .unwrap()
+        .as_list_mut()
+        .unwrap()
+        .pop();
+    assert_eq!(v["Data"]["Player"]["Inventory"].as_list().unwrap().len(), 1);
+
+    v.as_compound_mut().unwrap().clear();
+    assert_eq!(v, nbt!({}));
+}
+
+#[test]
+fn insert_and_remove() {
+    let mut v = level();
+    assert_eq!(v["Data"].insert("SpawnY", 64), Ok(None));
+    assert_eq!(v["Data"].insert("SpawnX", 0), Ok(Some(nbt!(10))));
+    assert_eq!(v["Data"]["SpawnY"], 64);
+    assert_eq!(v["Data"]["SpawnX"], 0);
+
+    assert_eq!(v["Data"].remove("SpawnY"), Some(nbt!(64)));
+    assert_eq!(v["Data"].remove("SpawnY"), None);
+    assert_eq!(v["longs"].remove("SpawnY"), None);
+}
+
+#[test]
+fn insert_into_non_compound_gives_value_back() {
+    let mut v = level();
+    let longs = v["longs"].clone();
+    assert_eq!(v["longs"].insert("a", 1), Err(nbt!(1)));
+    assert_eq!(v["longs"], longs);
+}
+
+#[test]
+fn tag() {
+    let v = level();
+    assert_eq!(v.tag(), crate::Tag::Compound);
+    assert_eq!(v["longs"].tag(), crate::Tag::LongArray);
+    assert_eq!(v["Data"]["Player"]["Inventory"].tag(), crate::Tag::List);
+}
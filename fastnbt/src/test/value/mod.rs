@@ -1,9 +1,9 @@
 mod ser;
 mod de;
+mod borrow;
+mod index;
 
-use std::collections::HashMap;
-
-use crate::{from_bytes, to_bytes, Tag, Value};
+use crate::{from_bytes, to_bytes, CompoundMap, Tag, Value};
 
 use super::builder::Builder;
 
@@ -118,7 +118,7 @@ fn distinguish_floats() {
 #[test]
 fn fuzz_float() {
     let v = Value::Float(1.4e-44);
-    let mut inner = HashMap::new();
+    let mut inner = CompoundMap::new();
     inner.insert("".to_string(), v);
 
     let v = Value::Compound(inner);
@@ -191,9 +191,9 @@ fn distinguish_lists() {
         .build();
 
     let v: Value = from_bytes(&input).unwrap();
-    assert_contains!(v, "a", Value::List(ref data), data.iter().eq(&[1, 2, 3]));
-    assert_contains!(v, "b", Value::List(ref data), data.iter().eq(&[1, 2, 3]));
-    assert_contains!(v, "c", Value::List(ref data), data.iter().eq(&[1, 2, 3]));
+    assert_contains!(v, "a", Value::List(_, ref data), data.iter().eq(&[1, 2, 3]));
+    assert_contains!(v, "b", Value::List(_, ref data), data.iter().eq(&[1, 2, 3]));
+    assert_contains!(v, "c", Value::List(_, ref data), data.iter().eq(&[1, 2, 3]));
 }
 
 #[test]
@@ -216,3 +216,162 @@ fn distinguish_compound() {
     let v: Value = from_bytes(&input).unwrap();
     assert_contains!(v, "a", Value::Compound(_));
 }
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn compound_preserves_order() {
+    let input = Builder::new()
+        .start_compound("")
+        .int("z", 1)
+        .int("a", 2)
+        .start_compound("m")
+        .byte("y", 3)
+        .byte("b", 4)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: Value = from_bytes(&input).unwrap();
+    let keys: Vec<_> = match &v {
+        Value::Compound(c) => c.keys().cloned().collect(),
+        _ => panic!(),
+    };
+    assert_eq!(keys, ["z", "a", "m"]);
+    assert_eq!(to_bytes(&v).unwrap(), input);
+
+    let v = nbt!({"z": 1, "a": 2, "m": {"y": 3_i8, "b": 4_i8}});
+    assert_eq!(to_bytes(&v).unwrap(), input);
+
+    #[derive(serde::Serialize)]
+    struct Inner {
+        y: i8,
+        b: i8,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Outer {
+        z: i32,
+        a: i32,
+        m: Inner,
+    }
+
+    let v = crate::to_value(Outer {
+        z: 1,
+        a: 2,
+        m: Inner { y: 3, b: 4 },
+    })
+    .unwrap();
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn empty_list_keeps_element_tag() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("Inventory", Tag::Compound, 0)
+        .end_compound()
+        .build();
+
+    let v: Value = from_bytes(&input).unwrap();
+    assert_eq!(v["Inventory"], Value::List(Tag::Compound, vec![]));
+    assert_eq!(to_bytes(&v).unwrap(), input);
+
+    // Survives a trip through the Value serializer and deserializer.
+    let v: Value = crate::to_value(&v).unwrap();
+    assert_eq!(v["Inventory"], Value::List(Tag::Compound, vec![]));
+    let v: Value = crate::from_value(&v).unwrap();
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn deserialize_from_other_formats() {
+    // The empty list hint must not get in the way of formats that don't know
+    // about it.
+    let v: Value = serde_json::from_str(r#"{"a": [], "b": [-1], "c": "x"}"#).unwrap();
+    assert_eq!(v["a"], Value::List(Tag::End, vec![]));
+    assert_eq!(v["b"], Value::List(Tag::Long, vec![Value::Long(-1)]));
+    assert_eq!(v["c"], Value::String("x".to_owned()));
+}
+
+#[test]
+fn empty_list_of_end_stays_end() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("empty", Tag::End, 0)
+        .end_compound()
+        .build();
+
+    let v: Value = from_bytes(&input).unwrap();
+    assert_eq!(v["empty"], Value::List(Tag::End, vec![]));
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn nested_empty_lists_keep_element_tag() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_list("lists", Tag::List, 2)
+        .start_anon_list(Tag::Int, 0)
+        .start_anon_list(Tag::String, 0)
+        .end_compound()
+        .build();
+
+    let v: Value = from_bytes(&input).unwrap();
+    assert_eq!(
+        v["lists"],
+        Value::List(
+            Tag::List,
+            vec![
+                Value::List(Tag::Int, vec![]),
+                Value::List(Tag::String, vec![])
+            ]
+        )
+    );
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn lists_of_arrays_roundtrip() {
+    for (tag, input) in [
+        (
+            Tag::ByteArray,
+            Builder::new()
+                .start_compound("")
+                .start_list("arrays", Tag::ByteArray, 2)
+                .int_payload(3)
+                .byte_array_payload(&[1, 2, 3])
+                .int_payload(0)
+                .end_compound()
+                .build(),
+        ),
+        (
+            Tag::IntArray,
+            Builder::new()
+                .start_compound("")
+                .start_list("arrays", Tag::IntArray, 2)
+                .int_payload(3)
+                .int_array_payload(&[1, 2, 3])
+                .int_payload(0)
+                .end_compound()
+                .build(),
+        ),
+        (
+            Tag::LongArray,
+            Builder::new()
+                .start_compound("")
+                .start_list("arrays", Tag::LongArray, 2)
+                .int_payload(3)
+                .long_array_payload(&[1, 2, 3])
+                .int_payload(0)
+                .end_compound()
+                .build(),
+        ),
+    ] {
+        let v: Value = from_bytes(&input).unwrap();
+        assert_eq!(v["arrays"].as_list().map(|l| l[0].tag()), Some(tag));
+        assert_eq!(to_bytes(&v).unwrap(), input);
+
+        let v: Value = crate::from_value(&crate::to_value(&v).unwrap()).unwrap();
+        assert_eq!(to_bytes(&v).unwrap(), input);
+    }
+}
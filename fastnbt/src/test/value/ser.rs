@@ -1,8 +1,6 @@
-use std::collections::HashMap;
-
 use serde::Serialize;
 
-use crate::{to_value, ByteArray, IntArray, LongArray, Value};
+use crate::{to_value, ByteArray, CompoundMap, IntArray, LongArray, Tag, Value};
 
 #[test]
 fn simple_types() {
@@ -43,7 +41,7 @@ fn simple_types() {
 
     let val = to_value(&v).unwrap();
     // Note: we cannot use the nbt! macro here as that uses the `to_value` function
-    let expected = Value::Compound(HashMap::from([
+    let expected = Value::Compound(CompoundMap::from([
         ("bool".to_string(), Value::Byte(1)),
         ("i8".to_string(), Value::Byte(i8::MAX)),
         ("i16".to_string(), Value::Short(i16::MAX)),
@@ -83,7 +81,7 @@ fn int_array_types() {
     };
 
     let val = to_value(&v).unwrap();
-    let expected = Value::Compound(HashMap::from([
+    let expected = Value::Compound(CompoundMap::from([
         (
             "i128".to_string(),
             // Only left most bit is 0
@@ -135,14 +133,14 @@ fn nested() {
     };
 
     let val = to_value(&v).unwrap();
-    let expected = Value::Compound(HashMap::from([
+    let expected = Value::Compound(CompoundMap::from([
         (
             "list".to_string(),
-            Value::List(vec![Value::Short(1), Value::Short(2)]),
+            Value::List(Tag::Short, vec![Value::Short(1), Value::Short(2)]),
         ),
         (
             "nested".to_string(),
-            Value::Compound(HashMap::from([("key".to_string(), Value::Byte(42))])),
+            Value::Compound(CompoundMap::from([("key".to_string(), Value::Byte(42))])),
         ),
     ]));
 
@@ -171,12 +169,15 @@ fn no_root_compound() {
         to_value(LongArray::new(vec![1, 2, 3]))
     );
     assert_eq!(
-        Ok(Value::List(vec![
-            Value::Byte(1),
-            Value::Byte(2),
-            Value::Byte(3),
-            Value::Byte(4)
-        ])),
+        Ok(Value::List(
+            Tag::Byte,
+            vec![
+                Value::Byte(1),
+                Value::Byte(2),
+                Value::Byte(3),
+                Value::Byte(4)
+            ]
+        )),
         to_value(vec![
             Value::Byte(1),
             Value::Byte(2),
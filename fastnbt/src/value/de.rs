@@ -1,18 +1,21 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::borrow::Cow;
 
 use byteorder::BigEndian;
 use serde::{
     de::{
-        value::{BorrowedBytesDeserializer, BorrowedStrDeserializer},
+        value::{BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer},
         DeserializeSeed, EnumAccess, Expected, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
         VariantAccess, Visitor,
     },
     forward_to_deserialize_any, serde_if_integer128, Deserialize, Deserializer,
 };
 
-use crate::{error::Error, ByteArray, IntArray, LongArray, Value};
+use crate::{
+    error::Error, named_root::NAMED_ROOT_TOKEN, ArrayBytes, ByteArray, IntArray, LongArray, Tag,
+    Value,
+};
 
-use super::{INT_ARRAY_VALUE_TOKEN, LONG_ARRAY_VALUE_TOKEN};
+use super::{CompoundMap, EMPTY_LIST_TOKEN, INT_ARRAY_VALUE_TOKEN, LONG_ARRAY_VALUE_TOKEN};
 
 impl<'de> Deserialize<'de> for Value {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -93,7 +96,14 @@ impl<'de> Deserialize<'de> for Value {
                     v.push(el);
                 }
 
-                Ok(Value::List(v))
+                Ok(Value::from(v))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
             }
 
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -102,7 +112,7 @@ impl<'de> Deserialize<'de> for Value {
             {
                 match map.next_key_seed(KeyClassifier)? {
                     Some(KeyClass::Compound(first_key)) => {
-                        let mut compound = HashMap::new();
+                        let mut compound = CompoundMap::new();
 
                         compound.insert(first_key, map.next_value()?);
                         while let Some((key, value)) = map.next_entry()? {
@@ -112,28 +122,41 @@ impl<'de> Deserialize<'de> for Value {
                         Ok(Value::Compound(compound))
                     }
                     Some(KeyClass::ByteArray) => {
-                        let data = map.next_value::<&[u8]>()?;
-                        Ok(Value::ByteArray(ByteArray::from_bytes(data)))
+                        let data = map.next_value::<ArrayBytes>()?.0;
+                        Ok(Value::ByteArray(ByteArray::from_bytes(&data)))
                     }
                     Some(KeyClass::IntArray) => {
-                        let data = map.next_value::<&[u8]>()?;
-                        IntArray::from_bytes::<BigEndian>(data)
+                        let data = map.next_value::<ArrayBytes>()?.0;
+                        IntArray::from_bytes::<BigEndian>(&data)
                             .map(Value::IntArray)
                             .map_err(|_| serde::de::Error::custom("could not read int array"))
                     }
                     Some(KeyClass::LongArray) => {
-                        let data = map.next_value::<&[u8]>()?;
-                        LongArray::from_bytes::<BigEndian>(data)
+                        let data = map.next_value::<ArrayBytes>()?.0;
+                        LongArray::from_bytes::<BigEndian>(&data)
                             .map(Value::LongArray)
                             .map_err(|_| serde::de::Error::custom("could not read long array"))
                     }
+                    Some(KeyClass::EmptyList) => {
+                        let tag = map.next_value::<u8>()?;
+                        let tag = Tag::try_from(tag)
+                            .map_err(|_| serde::de::Error::custom("invalid list tag"))?;
+                        Ok(Value::List(tag, vec![]))
+                    }
                     // No keys just means an empty compound.
                     None => Ok(Value::Compound(Default::default())),
                 }
             }
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        // Deserializers that know NBT types will see this token and tell us the
+        // element type of empty lists. This is also fine for other formats: in
+        // serde's data model a newtype struct is a transparent wrapper, and
+        // self-describing formats such as serde_json don't write one, so they
+        // either call visit_newtype_struct, which goes on to deserialize_any,
+        // or skip straight to visiting the content. Either way we see the same
+        // calls as deserialize_any would give us.
+        deserializer.deserialize_newtype_struct(EMPTY_LIST_TOKEN, ValueVisitor)
     }
 }
 
@@ -144,6 +167,7 @@ enum KeyClass {
     ByteArray,
     IntArray,
     LongArray,
+    EmptyList,
 }
 
 impl<'de> DeserializeSeed<'de> for KeyClassifier {
@@ -172,6 +196,7 @@ impl<'de> Visitor<'de> for KeyClassifier {
             crate::BYTE_ARRAY_TOKEN => Ok(KeyClass::ByteArray),
             crate::INT_ARRAY_TOKEN => Ok(KeyClass::IntArray),
             crate::LONG_ARRAY_TOKEN => Ok(KeyClass::LongArray),
+            EMPTY_LIST_TOKEN => Ok(KeyClass::EmptyList),
             _ => Ok(KeyClass::Compound(s)),
         }
     }
@@ -184,6 +209,7 @@ impl<'de> Visitor<'de> for KeyClassifier {
             crate::BYTE_ARRAY_TOKEN => Ok(KeyClass::ByteArray),
             crate::INT_ARRAY_TOKEN => Ok(KeyClass::IntArray),
             crate::LONG_ARRAY_TOKEN => Ok(KeyClass::LongArray),
+            EMPTY_LIST_TOKEN => Ok(KeyClass::EmptyList),
             _ => Ok(KeyClass::Compound(s.to_string())),
         }
     }
@@ -254,10 +280,7 @@ where
     }
 }
 
-fn visit_compound<'de, V>(
-    compound: &'de HashMap<String, Value>,
-    visitor: V,
-) -> Result<V::Value, Error>
+fn visit_compound<'de, V>(compound: &'de CompoundMap, visitor: V) -> Result<V::Value, Error>
 where
     V: Visitor<'de>,
 {
@@ -326,7 +349,7 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
                 token: crate::LONG_ARRAY_TOKEN,
                 value: self,
             }),
-            Value::List(ref val) => visit_list(val, visitor),
+            Value::List(_, ref val) => visit_list(val, visitor),
             Value::Compound(ref val) => visit_compound(val, visitor),
         }
     }
@@ -431,6 +454,15 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
                 token: LONG_ARRAY_VALUE_TOKEN,
                 value: self,
             }),
+            EMPTY_LIST_TOKEN => match self {
+                Value::List(tag, v) if v.is_empty() && *tag != Tag::End => {
+                    visitor.visit_map(serde::de::value::MapDeserializer::new(std::iter::once((
+                        EMPTY_LIST_TOKEN,
+                        *tag as u8,
+                    ))))
+                }
+                _ => visitor.visit_newtype_struct(self),
+            },
             _ => return visitor.visit_newtype_struct(self),
         }
     }
@@ -501,7 +533,7 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
     {
         match self {
             Value::String(v) => visitor.visit_borrowed_str(v),
-            Value::List(v) => visit_list(v, visitor),
+            Value::List(_, v) => visit_list(v, visitor),
             _ => Err(self.invalid_type(&visitor)),
         }
     }
@@ -511,7 +543,7 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
         V: Visitor<'de>,
     {
         match self {
-            Value::List(v) => visit_list(v, visitor),
+            Value::List(_, v) => visit_list(v, visitor),
             _ => Err(self.invalid_type(&visitor)),
         }
     }
@@ -525,14 +557,17 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
 
     fn deserialize_tuple_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _len: usize,
         visitor: V,
     ) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_seq(visitor)
+        match self {
+            Value::Compound(v) if name == NAMED_ROOT_TOKEN => visit_compound(v, visitor),
+            _ => self.deserialize_seq(visitor),
+        }
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -555,7 +590,7 @@ impl<'de> serde::Deserializer<'de> for &'de Value {
         V: Visitor<'de>,
     {
         match self {
-            Value::List(v) => visit_list(v, visitor),
+            Value::List(_, v) => visit_list(v, visitor),
             Value::Compound(v) => visit_compound(v, visitor),
             _ => Err(self.invalid_type(&visitor)),
         }
@@ -615,6 +650,16 @@ impl<'de> MapAccess<'de> for ArrayAccess<'de> {
     {
         let data = match self.value {
             Value::ByteArray(v) => v.to_bytes(),
+            // Through deserialize_any arrays look like they do in NBT, where
+            // the data is big endian.
+            Value::IntArray(v) if self.token == crate::INT_ARRAY_TOKEN => {
+                let data: Vec<u8> = v.iter().flat_map(|n| n.to_be_bytes()).collect();
+                return seed.deserialize(BytesDeserializer::new(&data));
+            }
+            Value::LongArray(v) if self.token == crate::LONG_ARRAY_TOKEN => {
+                let data: Vec<u8> = v.iter().flat_map(|n| n.to_be_bytes()).collect();
+                return seed.deserialize(BytesDeserializer::new(&data));
+            }
             Value::IntArray(v) => v.to_bytes(),
             Value::LongArray(v) => v.to_bytes(),
             _ => unreachable!(),
@@ -683,7 +728,7 @@ impl<'de> VariantAccess<'de> for VariantDeserializer<'de> {
         V: Visitor<'de>,
     {
         match self.value {
-            Some(Value::List(v)) => {
+            Some(Value::List(_, v)) => {
                 if v.is_empty() {
                     visitor.visit_unit()
                 } else {
@@ -755,12 +800,12 @@ impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
 }
 
 struct MapDeserializer<'de> {
-    iter: <&'de HashMap<String, Value> as IntoIterator>::IntoIter,
+    iter: <&'de CompoundMap as IntoIterator>::IntoIter,
     value: Option<&'de Value>,
 }
 
 impl<'de> MapDeserializer<'de> {
-    fn new(map: &'de HashMap<String, Value>) -> Self {
+    fn new(map: &'de CompoundMap) -> Self {
         MapDeserializer {
             iter: map.into_iter(),
             value: None,
@@ -911,7 +956,7 @@ impl Value {
             Value::ByteArray(_) => Unexpected::Seq,
             Value::IntArray(_) => Unexpected::Seq,
             Value::LongArray(_) => Unexpected::Seq,
-            Value::List(_) => Unexpected::Seq,
+            Value::List(..) => Unexpected::Seq,
             Value::Compound(_) => Unexpected::Map,
         }
     }
@@ -0,0 +1,138 @@
+use std::ops;
+
+use super::Value;
+
+/// A type that can be used to index into a [`Value`]. Strings index into
+/// compounds, and `usize` indexes into lists. This is used by [`Value::get`],
+/// [`Value::get_mut`], and the `[]` operator.
+///
+/// This trait is sealed and cannot be implemented outside of fastnbt.
+pub trait Index: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value>;
+
+    #[doc(hidden)]
+    fn describe(&self) -> String;
+}
+
+impl Index for usize {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        match v {
+            Value::List(_, list) => list.get(*self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match v {
+            Value::List(_, list) => list.get_mut(*self),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("index {}", self)
+    }
+}
+
+impl Index for str {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        match v {
+            Value::Compound(map) => map.get(self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match v {
+            Value::Compound(map) => map.get_mut(self),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("key {:?}", self)
+    }
+}
+
+impl Index for String {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        self[..].index_into(v)
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self[..].index_into_mut(v)
+    }
+
+    fn describe(&self) -> String {
+        self[..].describe()
+    }
+}
+
+impl<T> Index for &T
+where
+    T: ?Sized + Index,
+{
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(v)
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(v)
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T> Sealed for &T where T: ?Sized + Sealed {}
+}
+
+/// Index into a compound with a string or a list with a `usize`.
+///
+/// # Panics
+///
+/// Panics if the key or index does not exist. Use [`Value::get`] to get an
+/// `Option` instead.
+impl<I> ops::Index<I> for Value
+where
+    I: Index,
+{
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value {
+        match index.index_into(self) {
+            Some(v) => v,
+            None => panic!("no value at {} in NBT {:?}", index.describe(), self.tag()),
+        }
+    }
+}
+
+/// Mutably index into a compound with a string or a list with a `usize`.
+///
+/// # Panics
+///
+/// Panics if the key or index does not exist. Unlike `serde_json`, indexing
+/// does not insert missing keys, as NBT has no null value to insert. Use
+/// [`Value::insert`] to add new keys to a compound.
+impl<I> ops::IndexMut<I> for Value
+where
+    I: Index,
+{
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        let tag = self.tag();
+        match index.index_into_mut(self) {
+            Some(v) => v,
+            None => panic!("no value at {} in NBT {:?}", index.describe(), tag),
+        }
+    }
+}
@@ -1,22 +1,73 @@
-mod array_serializer;
+pub(crate) mod array_serializer;
 mod de;
+mod index;
 mod ser;
 
-use std::collections::HashMap;
-
 use serde::{serde_if_integer128, Deserialize, Serialize};
 
-use crate::{error::Error, ByteArray, IntArray, LongArray};
+use crate::{error::Error, ByteArray, IntArray, LongArray, Tag};
 
+pub use self::index::Index;
 pub use self::ser::Serializer;
 
+/// The map used for [`Value::Compound`]. By default this is a `HashMap`. With
+/// the `preserve_order` feature this is an `IndexMap`, which keeps keys in the
+/// order they were inserted, and so in the order they were read from NBT.
+#[cfg(not(feature = "preserve_order"))]
+pub type CompoundMap = std::collections::HashMap<String, Value>;
+
+/// The map used for [`Value::Compound`]. By default this is a `HashMap`. With
+/// the `preserve_order` feature this is an `IndexMap`, which keeps keys in the
+/// order they were inserted, and so in the order they were read from NBT.
+#[cfg(feature = "preserve_order")]
+pub type CompoundMap = indexmap::IndexMap<String, Value>;
+
 pub(crate) const INT_ARRAY_VALUE_TOKEN: &str = "__fastnbt_int_array_from_value";
 pub(crate) const LONG_ARRAY_VALUE_TOKEN: &str = "__fastnbt_long_array_from_value";
 
+/// Used by `Value` to ask a deserializer for the element tag of empty lists,
+/// and by the deserializer as the key of a map containing that tag.
+pub(crate) const EMPTY_LIST_TOKEN: &str = "__fastnbt_empty_list";
+
+/// Names of the tuple structs used to serialize empty lists of each tag, as
+/// there is no other way to pass the tag to the serializer. Indexed by tag.
+const EMPTY_LIST_TOKENS: [&str; 13] = [
+    "__fastnbt_empty_list_0",
+    "__fastnbt_empty_list_1",
+    "__fastnbt_empty_list_2",
+    "__fastnbt_empty_list_3",
+    "__fastnbt_empty_list_4",
+    "__fastnbt_empty_list_5",
+    "__fastnbt_empty_list_6",
+    "__fastnbt_empty_list_7",
+    "__fastnbt_empty_list_8",
+    "__fastnbt_empty_list_9",
+    "__fastnbt_empty_list_10",
+    "__fastnbt_empty_list_11",
+    "__fastnbt_empty_list_12",
+];
+
+pub(crate) fn empty_list_token(tag: Tag) -> &'static str {
+    EMPTY_LIST_TOKENS[tag as usize]
+}
+
+/// Get the element tag of an empty list from the name of a tuple struct, if it
+/// is an empty list token.
+pub(crate) fn empty_list_tag(name: &str) -> Option<Tag> {
+    let tag = EMPTY_LIST_TOKENS.iter().position(|t| *t == name)?;
+    Tag::try_from(tag as u8).ok()
+}
+
 /// Value is a complete NBT value. It owns its data. Compounds and Lists are
 /// resursively deserialized. This type takes care to preserve all the
 /// information from the original NBT, with the exception of the name of the
-/// root compound (which is usually the empty string).
+/// root compound (which is usually the empty string). Use
+/// [`NamedRoot`][`crate::NamedRoot`] to keep the name.
+///
+/// Lists keep the tag of their elements, so that empty lists keep their type
+/// when serialized, eg an empty list of compounds for an empty inventory. For
+/// lists that are not empty the tag should match the elements. `From<Vec<T>>`
+/// creates a list with the tag taken from the first element.
 ///
 /// ```no_run
 /// # use fastnbt::Value;
@@ -46,8 +97,8 @@ pub enum Value {
     ByteArray(ByteArray),
     IntArray(IntArray),
     LongArray(LongArray),
-    List(Vec<Value>),
-    Compound(HashMap<String, Value>),
+    List(Tag, Vec<Value>),
+    Compound(CompoundMap),
 }
 
 #[cfg(feature = "arbitrary1")]
@@ -64,11 +115,11 @@ where
 }
 
 #[cfg(feature = "arbitrary1")]
-fn arb_list(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Vec<Value>> {
-    use crate::Tag;
+fn arb_list(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Value> {
     use Value::*;
 
-    Ok(match u.arbitrary::<Tag>()? {
+    let tag = u.arbitrary::<Tag>()?;
+    let list = match tag {
         Tag::End => return Err(arbitrary::Error::IncorrectFormat),
         Tag::Byte => het_list(u, Byte)?,
         Tag::Short => het_list(u, Short)?,
@@ -83,20 +134,21 @@ fn arb_list(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Vec<Value>> {
             let len = u.arbitrary_len::<Value>()?;
             let mut v = vec![];
             for _ in 0..len {
-                v.push(Value::List(arb_list(u)?));
+                v.push(arb_list(u)?);
             }
             v
         }
         Tag::Compound => het_list(u, Compound)?,
         Tag::IntArray => het_list(u, IntArray)?,
         Tag::LongArray => het_list(u, LongArray)?,
-    })
+    };
+
+    Ok(List(tag, list))
 }
 
 #[cfg(feature = "arbitrary1")]
 impl<'a> arbitrary::Arbitrary<'a> for Value {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        use crate::Tag;
         use Value::*;
 
         Ok(match u.arbitrary::<Tag>()? {
@@ -114,7 +166,7 @@ impl<'a> arbitrary::Arbitrary<'a> for Value {
             Tag::LongArray => LongArray(u.arbitrary()?),
 
             // Lists need to all be the same type.
-            Tag::List => List(arb_list(u)?),
+            Tag::List => arb_list(u)?,
         })
     }
 }
@@ -162,6 +214,188 @@ impl Value {
             _ => None,
         }
     }
+
+    pub fn as_compound(&self) -> Option<&CompoundMap> {
+        match self {
+            Value::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> Option<&mut CompoundMap> {
+        match self {
+            Value::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::List(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::List(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte_array(&self) -> Option<&ByteArray> {
+        match self {
+            Value::ByteArray(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&IntArray> {
+        match self {
+            Value::IntArray(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<&LongArray> {
+        match self {
+            Value::LongArray(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Get the NBT tag of this value.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::Byte(_) => Tag::Byte,
+            Value::Short(_) => Tag::Short,
+            Value::Int(_) => Tag::Int,
+            Value::Long(_) => Tag::Long,
+            Value::Float(_) => Tag::Float,
+            Value::Double(_) => Tag::Double,
+            Value::String(_) => Tag::String,
+            Value::ByteArray(_) => Tag::ByteArray,
+            Value::IntArray(_) => Tag::IntArray,
+            Value::LongArray(_) => Tag::LongArray,
+            Value::List(..) => Tag::List,
+            Value::Compound(_) => Tag::Compound,
+        }
+    }
+
+    /// Get a value from a compound by key, or from a list by index. Returns
+    /// `None` if the key or index does not exist, or if this value is not a
+    /// compound or list.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let v = nbt!({"Data": {"SpawnX": 10}, "list": [1, 2, 3]});
+    /// assert_eq!(v.get("Data").and_then(|d| d.get("SpawnX")), Some(&nbt!(10)));
+    /// assert_eq!(v["list"].get(1), Some(&nbt!(2)));
+    /// assert_eq!(v.get("missing"), None);
+    /// ```
+    pub fn get<I: Index>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    /// Mutably get a value from a compound by key, or from a list by index.
+    /// Returns `None` if the key or index does not exist, or if this value is
+    /// not a compound or list.
+    pub fn get_mut<I: Index>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    /// Look up a value by a JSON Pointer-like path, where each `/` separated
+    /// part is a compound key or list index. As in JSON Pointer, `~1` and `~0`
+    /// can be used in keys to mean `/` and `~`. An empty pointer refers to
+    /// this value.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let player = nbt!({"Inventory": [{"id": "minecraft:stone", "Count": 1_i8}]});
+    /// assert_eq!(
+    ///     player.pointer("/Inventory/0/id"),
+    ///     Some(&nbt!("minecraft:stone"))
+    /// );
+    /// assert_eq!(player.pointer("/Inventory/1/id"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        pointer_parts(pointer)?.try_fold(self, |v, part| match v {
+            Value::List(..) => v.get(part.parse::<usize>().ok()?),
+            _ => v.get(&*part),
+        })
+    }
+
+    /// Mutably look up a value by a JSON Pointer-like path. See
+    /// [`Value::pointer`].
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let mut level = nbt!({"Data": {"SpawnX": 10}});
+    /// *level.pointer_mut("/Data/SpawnX").unwrap() = nbt!(20);
+    /// assert_eq!(level["Data"]["SpawnX"], 20);
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        pointer_parts(pointer)?.try_fold(self, |v, part| match v {
+            Value::List(..) => v.get_mut(part.parse::<usize>().ok()?),
+            _ => v.get_mut(&*part),
+        })
+    }
+
+    /// Insert a key into a compound, returning the previous value for the key
+    /// if there was one. If this value is not a compound, nothing is inserted
+    /// and the value is given back as the error.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let mut level = nbt!({"Data": {}, "Version": 1});
+    /// assert_eq!(level["Data"].insert("SpawnX", 10), Ok(None));
+    /// assert_eq!(level["Version"].insert("Id", 2), Err(nbt!(2)));
+    /// assert_eq!(level, nbt!({"Data": {"SpawnX": 10}, "Version": 1}));
+    /// ```
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<Value>, Value>
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        match self {
+            Value::Compound(map) => Ok(map.insert(key.into(), value.into())),
+            _ => Err(value.into()),
+        }
+    }
+
+    /// Remove a key from a compound, returning its value if there was one.
+    /// Returns `None` if this value is not a compound.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        match self {
+            #[cfg(not(feature = "preserve_order"))]
+            Value::Compound(map) => map.remove(key),
+            #[cfg(feature = "preserve_order")]
+            Value::Compound(map) => map.shift_remove(key),
+            _ => None,
+        }
+    }
+}
+
+/// Split a pointer into its unescaped parts. Returns `None` if the pointer is
+/// not empty and doesn't start with `/`.
+fn pointer_parts(pointer: &str) -> Option<impl Iterator<Item = String> + '_> {
+    let parts = match pointer {
+        "" => None,
+        p => Some(p.strip_prefix('/')?.split('/')),
+    };
+
+    Some(
+        parts
+            .into_iter()
+            .flatten()
+            .map(|part| part.replace("~1", "/").replace("~0", "~")),
+    )
+}
+
+/// Escape a compound key for use as part of a pointer. See
+/// [`Value::pointer`].
+pub(crate) fn escape_pointer_part(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
 }
 
 // ------------- From<T> impls -------------
@@ -196,6 +430,22 @@ from!(ByteArray, ByteArray);
 from!(IntArray, IntArray);
 from!(LongArray, LongArray);
 
+/// Formats as SNBT. `{}` gives compact SNBT on one line and `{:#}` gives
+/// indented SNBT. See [`fmt`][`crate::fmt`] for other options.
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::fmt::display(self, f)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(val: Vec<T>) -> Self {
+        let list: Vec<Value> = val.into_iter().map(Into::into).collect();
+        let tag = list.first().map_or(Tag::End, Value::tag);
+        Self::List(tag, list)
+    }
+}
+
 impl From<bool> for Value {
     fn from(val: bool) -> Self {
         Self::Byte(if val { 1 } else { 0 })
@@ -1,14 +1,17 @@
 use core::result;
-use std::collections::HashMap;
 
-use serde::{ser::Impossible, serde_if_integer128, Serialize};
+use serde::{
+    ser::{Impossible, SerializeTupleStruct},
+    serde_if_integer128, Serialize,
+};
 
 use crate::{
     error::{Error, Result},
+    named_root::NAMED_ROOT_TOKEN,
     IntArray, Tag, Value,
 };
 
-use super::array_serializer::ArraySerializer;
+use super::{array_serializer::ArraySerializer, empty_list_tag, empty_list_token, CompoundMap};
 
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
@@ -26,7 +29,12 @@ impl Serialize for Value {
             Value::ByteArray(v) => v.serialize(serializer),
             Value::IntArray(v) => v.serialize(serializer),
             Value::LongArray(v) => v.serialize(serializer),
-            Value::List(v) => v.serialize(serializer),
+            // An empty sequence can't tell the serializer what type of list it
+            // is, so empty lists use a special tuple struct for their tag.
+            Value::List(tag, v) if v.is_empty() && *tag != Tag::End => serializer
+                .serialize_tuple_struct(empty_list_token(*tag), 0)?
+                .end(),
+            Value::List(_, v) => v.serialize(serializer),
             Value::Compound(v) => v.serialize(serializer),
         }
     }
@@ -178,6 +186,7 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_bytes(self, value: &[u8]) -> Result<Value> {
         Ok(Value::List(
+            Tag::Byte,
             value.iter().map(|byte| Value::Byte(*byte as i8)).collect(),
         ))
     }
@@ -238,6 +247,8 @@ impl<'a> serde::Serializer for &'a mut Serializer {
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         Ok(SerializeVec {
             vec: Vec::with_capacity(len.unwrap_or(0)),
+            named_root: false,
+            tag: Tag::End,
         })
     }
 
@@ -247,10 +258,13 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        self.serialize_seq(Some(len))
+        let mut vec = self.serialize_seq(Some(len))?;
+        vec.named_root = name == NAMED_ROOT_TOKEN;
+        vec.tag = empty_list_tag(name).unwrap_or(Tag::End);
+        Ok(vec)
     }
 
     fn serialize_tuple_variant(
@@ -268,7 +282,7 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
         Ok(SerializeMap {
-            map: HashMap::new(),
+            map: CompoundMap::new(),
             next_key: None,
         })
     }
@@ -286,7 +300,7 @@ impl<'a> serde::Serializer for &'a mut Serializer {
     ) -> Result<Self::SerializeStructVariant> {
         Ok(SerializeStructVariant {
             name: variant.into(),
-            map: HashMap::new(),
+            map: CompoundMap::new(),
         })
     }
 
@@ -312,6 +326,10 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
 pub struct SerializeVec {
     vec: Vec<Value>,
+    // Value has no root name, so a NamedRoot becomes just its value.
+    named_root: bool,
+    // The element tag to use if the list is empty.
+    tag: Tag,
 }
 
 pub struct SerializeTupleVariant {
@@ -320,13 +338,13 @@ pub struct SerializeTupleVariant {
 }
 
 pub struct SerializeMap {
-    map: HashMap<String, Value>,
+    map: CompoundMap,
     next_key: Option<String>,
 }
 
 pub struct SerializeStructVariant {
     name: String,
-    map: HashMap<String, Value>,
+    map: CompoundMap,
 }
 
 impl serde::ser::SerializeSeq for SerializeVec {
@@ -342,7 +360,8 @@ impl serde::ser::SerializeSeq for SerializeVec {
     }
 
     fn end(self) -> Result<Value> {
-        Ok(Value::List(self.vec))
+        let tag = self.vec.first().map_or(self.tag, Value::tag);
+        Ok(Value::List(tag, self.vec))
     }
 }
 
@@ -373,7 +392,14 @@ impl serde::ser::SerializeTupleStruct for SerializeVec {
         serde::ser::SerializeSeq::serialize_element(self, value)
     }
 
-    fn end(self) -> Result<Value> {
+    fn end(mut self) -> Result<Value> {
+        if self.named_root {
+            return self
+                .vec
+                .pop()
+                .ok_or_else(|| Error::bespoke("NamedRoot had no value".to_string()));
+        }
+
         serde::ser::SerializeSeq::end(self)
     }
 }
@@ -391,9 +417,9 @@ impl serde::ser::SerializeTupleVariant for SerializeTupleVariant {
     }
 
     fn end(self) -> Result<Value> {
-        let mut object = HashMap::new();
+        let mut object = CompoundMap::new();
 
-        object.insert(self.name, Value::List(self.vec));
+        object.insert(self.name, Value::from(self.vec));
 
         Ok(Value::Compound(object))
     }
@@ -635,7 +661,7 @@ impl serde::ser::SerializeStructVariant for SerializeStructVariant {
     }
 
     fn end(self) -> Result<Value> {
-        let mut object = HashMap::new();
+        let mut object = CompoundMap::new();
 
         object.insert(self.name, Value::Compound(self.map));
 
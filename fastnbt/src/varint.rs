@@ -0,0 +1,68 @@
+//! Variable length integers as used by Bedrock Edition's network NBT. Signed
+//! integers are zigzag encoded before being written as an unsigned varint.
+
+use std::io::{self, Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+fn too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "varint too long")
+}
+
+pub(crate) fn read_var_u32<R: Read + ?Sized>(r: &mut R) -> io::Result<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = r.read_u8()?;
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(too_long())
+}
+
+pub(crate) fn read_var_u64<R: Read + ?Sized>(r: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..70).step_by(7) {
+        let byte = r.read_u8()?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(too_long())
+}
+
+pub(crate) fn read_var_i32<R: Read + ?Sized>(r: &mut R) -> io::Result<i32> {
+    let v = read_var_u32(r)?;
+    Ok((v >> 1) as i32 ^ -((v & 1) as i32))
+}
+
+pub(crate) fn read_var_i64<R: Read + ?Sized>(r: &mut R) -> io::Result<i64> {
+    let v = read_var_u64(r)?;
+    Ok((v >> 1) as i64 ^ -((v & 1) as i64))
+}
+
+pub(crate) fn write_var_u32<W: Write + ?Sized>(w: &mut W, mut v: u32) -> io::Result<()> {
+    while v >= 0x80 {
+        w.write_u8(v as u8 | 0x80)?;
+        v >>= 7;
+    }
+    w.write_u8(v as u8)
+}
+
+pub(crate) fn write_var_u64<W: Write + ?Sized>(w: &mut W, mut v: u64) -> io::Result<()> {
+    while v >= 0x80 {
+        w.write_u8(v as u8 | 0x80)?;
+        v >>= 7;
+    }
+    w.write_u8(v as u8)
+}
+
+pub(crate) fn write_var_i32<W: Write + ?Sized>(w: &mut W, v: i32) -> io::Result<()> {
+    write_var_u32(w, ((v << 1) ^ (v >> 31)) as u32)
+}
+
+pub(crate) fn write_var_i64<W: Write + ?Sized>(w: &mut W, v: i64) -> io::Result<()> {
+    write_var_u64(w, ((v << 1) ^ (v >> 63)) as u64)
+}
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use fastnbt::error::Result;
+use fastnbt::{from_bytes_with_opts, roundtrip_check, to_bytes, DeOpts, NamedRoot, Value};
+
+fuzz_target!(|data: &[u8]| {
+    // Any input must give a clean result, even if it does not round trip.
+    let _ = roundtrip_check(data);
+
+    let value: Result<NamedRoot<Value>> =
+        from_bytes_with_opts(data, DeOpts::new().max_seq_len(100));
+    if let Ok(v) = value {
+        // What fastnbt writes must always round trip.
+        let bs = to_bytes(&v).unwrap();
+        roundtrip_check(&bs).unwrap();
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use fastnbt::{roundtrip_check, to_bytes, CompoundMap, Value};
+
+fuzz_target!(|v: Value| {
+    let mut inner = CompoundMap::new();
+    inner.insert("".to_string(), v);
+
+    if let Ok(bs) = to_bytes(&Value::Compound(inner)) {
+        roundtrip_check(&bs).unwrap();
+    }
+});
@@ -2,15 +2,14 @@
 use libfuzzer_sys::fuzz_target;
 
 use serde::Serialize;
-use std::collections::HashMap;
 
 use fastnbt::error::Result;
 use fastnbt::from_bytes;
 use fastnbt::to_bytes;
-use fastnbt::Value;
+use fastnbt::{CompoundMap, Value};
 
 fuzz_target!(|v: Value| {
-    let mut inner = HashMap::new();
+    let mut inner = CompoundMap::new();
     inner.insert("".to_string(), v);
 
     let v = Value::Compound(inner);
@@ -0,0 +1,72 @@
+use std::{fs::File, io::Read, path::Path, process::exit};
+
+use clap::{App, Arg};
+use fastanvil::Region;
+use fastnbt::Value;
+
+fn main() {
+    let matches = App::new("nbt-diff")
+        .about("Show the differences between two NBT files, eg playerdata .dat files or chunks in region files. Exits with 1 if there are differences.")
+        .arg(Arg::with_name("a").required(true).help("the old file"))
+        .arg(Arg::with_name("b").required(true).help("the new file"))
+        .arg(
+            Arg::with_name("chunk")
+                .long("chunk")
+                .short("c")
+                .takes_value(true)
+                .required(false)
+                .help("chunk to compare for region files, as X,Z in region coordinates"),
+        )
+        .get_matches();
+
+    let chunk = matches.value_of("chunk").map(|chunk| {
+        let (x, z) = chunk.split_once(',').expect("chunk should be X,Z");
+        let x: usize = x.trim().parse().expect("chunk X should be a number");
+        let z: usize = z.trim().parse().expect("chunk Z should be a number");
+        (x, z)
+    });
+
+    let a = load(matches.value_of("a").unwrap(), chunk);
+    let b = load(matches.value_of("b").unwrap(), chunk);
+
+    let changes = fastnbt::diff(&a, &b);
+    for change in &changes {
+        println!("{}", change);
+    }
+
+    if !changes.is_empty() {
+        exit(1);
+    }
+}
+
+/// Load a file as a Value. Region files need the chunk to use, other files
+/// can be GZip, Zlib or uncompressed NBT.
+fn load(path: &str, chunk: Option<(usize, usize)>) -> Value {
+    let mut file = File::open(path).unwrap_or_else(|e| fail(path, e));
+
+    let is_region = matches!(
+        Path::new(path).extension().and_then(|ext| ext.to_str()),
+        Some("mca" | "mcr")
+    );
+
+    let data = if is_region {
+        let (x, z) = chunk.unwrap_or_else(|| fail(path, "--chunk is needed for region files"));
+        let mut region = Region::from_stream(file).unwrap_or_else(|e| fail(path, e));
+        region
+            .read_chunk(x, z)
+            .unwrap_or_else(|e| fail(path, e))
+            .unwrap_or_else(|| fail(path, format!("chunk {},{} does not exist", x, z)))
+    } else {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)
+            .unwrap_or_else(|e| fail(path, e));
+        data
+    };
+
+    fastnbt::from_compressed_bytes(&data).unwrap_or_else(|e| fail(path, e))
+}
+
+fn fail(path: &str, e: impl std::fmt::Display) -> ! {
+    eprintln!("{}: {}", path, e);
+    exit(2);
+}
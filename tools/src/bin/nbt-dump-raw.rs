@@ -1,32 +1,11 @@
-use fastnbt::stream::{Parser, Value};
+use clap::App;
+use fastnbt_tools::dump;
 use std::io;
 
 fn main() {
-    let stdin = io::stdin();
-    let mut parser = Parser::new(stdin);
-    let mut indent = 0;
+    let matches =
+        dump::args(App::new("nbt-dump-raw").about("Dump uncompressed NBT read from stdin"))
+            .get_matches();
 
-    loop {
-        match parser.next() {
-            Err(e) => {
-                println!("{:?}", e);
-                break;
-            }
-            Ok(value) => {
-                match value {
-                    Value::CompoundEnd => indent -= 4,
-                    Value::ListEnd => indent -= 4,
-                    _ => {}
-                }
-
-                println!("{:indent$}{:?}", "", value, indent = indent);
-
-                match value {
-                    Value::Compound(_) => indent += 4,
-                    Value::List(_, _, _) => indent += 4,
-                    _ => {}
-                }
-            }
-        }
-    }
+    dump::dump(io::stdin(), &matches);
 }
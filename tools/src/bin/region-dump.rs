@@ -8,7 +8,10 @@ use std::{
 use clap::{App, Arg};
 use env_logger::Env;
 use fastanvil::Region;
-use fastnbt::Value;
+use fastnbt::{
+    fmt::{self, FmtOpts, Style},
+    json, Value,
+};
 
 fn main() -> Result<(), Box<dyn Error>> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info"))
@@ -24,7 +27,17 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .takes_value(true)
                 .required(false)
                 .default_value("rust")
-                .possible_values(&["rust", "rust-pretty", "json", "json-pretty", "nbt"])
+                .possible_values(&[
+                    "rust",
+                    "rust-pretty",
+                    "tree",
+                    "snbt",
+                    "json",
+                    "json-pretty",
+                    "typed-json",
+                    "typed-json-pretty",
+                    "nbt",
+                ])
                 .help("output format"),
         )
         .arg(
@@ -57,7 +70,8 @@ fn main() -> Result<(), Box<dyn Error>> {
                     let mut out: Box<dyn Write> = if let Some(dir) = out_dir {
                         let ext = match output_format {
                             "nbt" => "nbt",
-                            "json" | "json-pretty" => "json",
+                            "snbt" => "snbt",
+                            "json" | "json-pretty" | "typed-json" | "typed-json-pretty" => "json",
                             _ => "txt",
                         };
                         Box::new(File::create(format!("{}/{}.{}.{}", dir, x, z, ext)).unwrap())
@@ -74,14 +88,29 @@ fn main() -> Result<(), Box<dyn Error>> {
                         "rust-pretty" => {
                             write!(&mut out, "{:#?}", chunk).unwrap();
                         }
+                        "tree" => {
+                            let opts = FmtOpts::new().style(Style::Tree);
+                            fmt::to_writer(&mut out, &chunk, opts).unwrap();
+                        }
+                        "snbt" => {
+                            write!(&mut out, "{:#}", chunk).unwrap();
+                        }
                         "nbt" => {
                             out.write_all(&data).unwrap();
                         }
                         "json" => {
-                            serde_json::ser::to_writer(out, &chunk).unwrap();
+                            serde_json::ser::to_writer(out, &json::to_plain(&chunk)).unwrap();
                         }
                         "json-pretty" => {
-                            serde_json::ser::to_writer_pretty(out, &chunk).unwrap();
+                            serde_json::ser::to_writer_pretty(out, &json::to_plain(&chunk))
+                                .unwrap();
+                        }
+                        "typed-json" => {
+                            serde_json::ser::to_writer(out, &json::to_typed(&chunk)).unwrap();
+                        }
+                        "typed-json-pretty" => {
+                            serde_json::ser::to_writer_pretty(out, &json::to_typed(&chunk))
+                                .unwrap();
                         }
                         _ => panic!("unknown output format '{}'", output_format),
                     }
@@ -0,0 +1,83 @@
+//! The output options shared by the `nbt-dump-raw` and `nbt-dump-gz` tools.
+
+use clap::{App, Arg, ArgMatches};
+use fastnbt::{
+    fmt::{self, FmtOpts, Style},
+    json,
+};
+use std::io::{self, Read};
+
+/// Add the output options to a dump tool's arguments.
+pub fn args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("format")
+            .long("format")
+            .short("f")
+            .takes_value(true)
+            .required(false)
+            .default_value("tree")
+            .possible_values(&[
+                "tree",
+                "snbt",
+                "json",
+                "json-pretty",
+                "typed-json",
+                "typed-json-pretty",
+            ])
+            .help("output format"),
+    )
+    .arg(
+        Arg::with_name("truncate-arrays")
+            .long("truncate-arrays")
+            .short("t")
+            .takes_value(true)
+            .required(false)
+            .help("summarize NBT arrays longer than this rather than printing them"),
+    )
+    .arg(
+        Arg::with_name("no-types")
+            .long("no-types")
+            .help("leave out the types of values in tree and snbt output"),
+    )
+}
+
+/// Dump the uncompressed NBT read from `input` to stdout, in the format given
+/// by the options added with [`args`].
+pub fn dump(input: impl Read, matches: &ArgMatches) {
+    let mut opts = FmtOpts::new().types(!matches.is_present("no-types"));
+    if let Some(len) = matches.value_of("truncate-arrays") {
+        opts = opts.truncate_arrays(len.parse().expect("truncate-arrays must be a number"));
+    }
+
+    match matches
+        .value_of("format")
+        .expect("no output format specified")
+    {
+        "tree" => dump_pretty(input, opts.style(Style::Tree)),
+        "snbt" => dump_pretty(input, opts.style(Style::Snbt)),
+        format => dump_json(input, format),
+    }
+}
+
+fn dump_json(input: impl Read, format: &str) {
+    let value: fastnbt::Value = fastnbt::from_reader(input).unwrap();
+    let out = io::stdout();
+
+    match format {
+        "json" => serde_json::to_writer(out, &json::to_plain(&value)).unwrap(),
+        "json-pretty" => serde_json::to_writer_pretty(out, &json::to_plain(&value)).unwrap(),
+        "typed-json" => serde_json::to_writer(out, &json::to_typed(&value)).unwrap(),
+        "typed-json-pretty" => serde_json::to_writer_pretty(out, &json::to_typed(&value)).unwrap(),
+        _ => panic!("unknown output format '{}'", format),
+    }
+    println!();
+}
+
+fn dump_pretty(input: impl Read, opts: FmtOpts) {
+    let out = io::BufWriter::new(io::stdout().lock());
+    let result = fmt::reader_to_writer(input, out, opts);
+    println!();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod dump;
+
 use fastanvil::{
     tex::{Blockstate, Model, Render, Renderer, Texture},
     Rgba,